@@ -0,0 +1,192 @@
+use image::DynamicImage;
+use rxing::{BarcodeFormat, Exceptions, RXingResult};
+use serde::{Deserialize, Serialize};
+use snow_shot_app_shared::ElementRect;
+
+/// 支持识别的条码类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BarcodeType {
+    QrCode,
+    DataMatrix,
+    Code128,
+    Ean13,
+    Ean8,
+}
+
+impl BarcodeType {
+    fn from_format(format: &BarcodeFormat) -> Option<Self> {
+        match format {
+            BarcodeFormat::QR_CODE => Some(BarcodeType::QrCode),
+            BarcodeFormat::DATA_MATRIX => Some(BarcodeType::DataMatrix),
+            BarcodeFormat::CODE_128 => Some(BarcodeType::Code128),
+            BarcodeFormat::EAN_13 => Some(BarcodeType::Ean13),
+            BarcodeFormat::EAN_8 => Some(BarcodeType::Ean8),
+            _ => None,
+        }
+    }
+}
+
+/// 识别到的条码，坐标基于传入的原图
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarcodeResult {
+    pub barcode_type: BarcodeType,
+    pub text: String,
+    pub rect: ElementRect,
+    /// 定位点，二维码为三个角的定位图案，一维码为首尾两点
+    pub points: Vec<[f32; 2]>,
+}
+
+pub struct BarcodeService;
+
+impl BarcodeService {
+    /// 识别图片中的条码
+    ///
+    /// # 参数
+    /// * `image` - 截图
+    /// * `region` - 识别区域，为空时识别整张图片
+    /// * `barcode_types` - 需要识别的条码类型，为空时识别全部支持的类型
+    pub fn detect(
+        image: &DynamicImage,
+        region: Option<ElementRect>,
+        barcode_types: &[BarcodeType],
+    ) -> Result<Vec<BarcodeResult>, String> {
+        let image_rect = ElementRect {
+            min_x: 0,
+            min_y: 0,
+            max_x: image.width() as i32,
+            max_y: image.height() as i32,
+        };
+        let region = match region {
+            Some(region) => region.clip_rect(&image_rect),
+            None => image_rect,
+        };
+        if region.min_x >= region.max_x || region.min_y >= region.max_y {
+            return Err("[BarcodeService::detect] Invalid region".to_string());
+        }
+
+        let width = (region.max_x - region.min_x) as u32;
+        let height = (region.max_y - region.min_y) as u32;
+        let mut luma = image
+            .crop_imm(region.min_x as u32, region.min_y as u32, width, height)
+            .to_luma8()
+            .into_raw();
+
+        let mut results = Self::detect_in_luma(luma.clone(), width, height)?;
+        if results.is_empty() {
+            // 深色模式下的条码为浅色前景，反色后再尝试一次
+            luma.iter_mut().for_each(|value| *value = 255 - *value);
+            results = Self::detect_in_luma(luma, width, height)?;
+        }
+
+        Ok(results
+            .iter()
+            .filter_map(|result| Self::to_barcode_result(result, &region))
+            .filter(|result| {
+                barcode_types.is_empty() || barcode_types.contains(&result.barcode_type)
+            })
+            .collect())
+    }
+
+    fn detect_in_luma(luma: Vec<u8>, width: u32, height: u32) -> Result<Vec<RXingResult>, String> {
+        match rxing::helpers::detect_multiple_in_luma(luma, width, height) {
+            Ok(results) => Ok(results),
+            Err(Exceptions::NotFoundException(_)) => Ok(Vec::new()),
+            Err(e) => Err(format!(
+                "[BarcodeService::detect_in_luma] Failed to detect barcode: {}",
+                e
+            )),
+        }
+    }
+
+    fn to_barcode_result(result: &RXingResult, region: &ElementRect) -> Option<BarcodeResult> {
+        let barcode_type = BarcodeType::from_format(result.getBarcodeFormat())?;
+
+        let points: Vec<[f32; 2]> = result
+            .getPoints()
+            .iter()
+            .map(|point| [point.x + region.min_x as f32, point.y + region.min_y as f32])
+            .collect();
+        if points.is_empty() {
+            return None;
+        }
+
+        let min_x = points.iter().map(|point| point[0]).fold(f32::MAX, f32::min);
+        let min_y = points.iter().map(|point| point[1]).fold(f32::MAX, f32::min);
+        let max_x = points.iter().map(|point| point[0]).fold(f32::MIN, f32::max);
+        let max_y = points.iter().map(|point| point[1]).fold(f32::MIN, f32::max);
+
+        Some(BarcodeResult {
+            barcode_type,
+            text: result.getText().to_string(),
+            rect: ElementRect {
+                min_x: min_x.floor() as i32,
+                min_y: min_y.floor() as i32,
+                max_x: max_x.ceil() as i32,
+                max_y: max_y.ceil() as i32,
+            },
+            points,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+    use rxing::Writer;
+    use rxing::qrcode::QRCodeWriter;
+
+    /// 生成白底黑码的二维码图片，`inverted` 为 true 时生成黑底白码
+    fn render_qr_code(text: &str, inverted: bool) -> DynamicImage {
+        let matrix = QRCodeWriter
+            .encode(text, &BarcodeFormat::QR_CODE, 200, 200)
+            .unwrap();
+
+        let image = GrayImage::from_fn(matrix.getWidth(), matrix.getHeight(), |x, y| {
+            if matrix.get(x, y) != inverted {
+                Luma([0])
+            } else {
+                Luma([255])
+            }
+        });
+
+        DynamicImage::ImageLuma8(image)
+    }
+
+    #[test]
+    fn test_detect_qr_code() {
+        let image = render_qr_code("https://snowshot.top", false);
+
+        let results = BarcodeService::detect(&image, None, &[]).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].barcode_type, BarcodeType::QrCode);
+        assert_eq!(results[0].text, "https://snowshot.top");
+        assert!(results[0].rect.min_x >= 0 && results[0].rect.max_x <= 200);
+    }
+
+    #[test]
+    fn test_detect_inverted_qr_code() {
+        let image = render_qr_code("dark mode", true);
+
+        let results = BarcodeService::detect(&image, None, &[]).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "dark mode");
+    }
+
+    #[test]
+    fn test_detect_barcode_type_filter() {
+        let image = render_qr_code("filter", false);
+
+        assert_eq!(
+            BarcodeService::detect(&image, None, &[BarcodeType::QrCode])
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(
+            BarcodeService::detect(&image, None, &[BarcodeType::Code128, BarcodeType::Ean13])
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
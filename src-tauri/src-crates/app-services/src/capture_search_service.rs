@@ -0,0 +1,491 @@
+use std::path::{Path, PathBuf};
+
+use paddle_ocr_rs::ocr_result::TextBlock;
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use snow_shot_app_shared::ElementRect;
+
+/// trigram 分词器无法匹配少于 3 个字符的关键词
+const TRIGRAM_MIN_LENGTH: usize = 3;
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// 截图记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureSearchRecord {
+    pub path: PathBuf,
+    pub window_title: Option<String>,
+    /// 截图时间，毫秒
+    pub captured_at: u64,
+    pub text_blocks: Vec<TextBlock>,
+    /// text_blocks 相对原图的缩放比例
+    pub scale_factor: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureSearchQuery {
+    /// 关键词，以空白分隔，所有关键词都需要匹配
+    pub text: String,
+    /// 截图时间范围，毫秒
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// 命中的文本框，坐标基于原图
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureSearchHighlight {
+    pub text: String,
+    pub rect: ElementRect,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureSearchResult {
+    pub path: PathBuf,
+    pub window_title: Option<String>,
+    pub captured_at: u64,
+    /// 相关度，越大越相关
+    pub score: f64,
+    pub highlights: Vec<CaptureSearchHighlight>,
+}
+
+/// 截图历史的全文检索，基于 SQLite FTS5
+pub struct CaptureSearchService {
+    connection: Option<Connection>,
+}
+
+impl CaptureSearchService {
+    pub fn new() -> Self {
+        Self { connection: None }
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    pub fn init(&mut self, database_path: &Path) -> Result<(), String> {
+        log::info!(
+            "[CaptureSearchService::init] database_path: {}",
+            database_path.display()
+        );
+
+        if let Some(parent) = database_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                format!(
+                    "[CaptureSearchService::init] Failed to create database dir: {}",
+                    e
+                )
+            })?;
+        }
+
+        let connection = Connection::open(database_path).map_err(|e| {
+            format!(
+                "[CaptureSearchService::init] Failed to open database: {}",
+                e
+            )
+        })?;
+
+        self.init_connection(connection)
+    }
+
+    fn init_connection(&mut self, connection: Connection) -> Result<(), String> {
+        // trigram 分词器支持中文等没有空格分隔的文字
+        connection
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                CREATE TABLE IF NOT EXISTS captures (
+                    path TEXT PRIMARY KEY,
+                    window_title TEXT,
+                    captured_at INTEGER NOT NULL,
+                    text_blocks TEXT NOT NULL,
+                    scale_factor REAL NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS captures_captured_at ON captures (captured_at);
+                CREATE VIRTUAL TABLE IF NOT EXISTS captures_fts USING fts5 (
+                    path UNINDEXED,
+                    window_title,
+                    text,
+                    tokenize = 'trigram'
+                );",
+            )
+            .map_err(|e| {
+                format!(
+                    "[CaptureSearchService::init] Failed to create tables: {}",
+                    e
+                )
+            })?;
+
+        self.connection = Some(connection);
+
+        Ok(())
+    }
+
+    fn get_connection(&mut self) -> Result<&mut Connection, String> {
+        match self.connection.as_mut() {
+            Some(connection) => Ok(connection),
+            None => Err("[CaptureSearchService] Service is not initialized".to_string()),
+        }
+    }
+
+    /// 写入截图记录，同一路径的记录会被替换
+    pub fn upsert(&mut self, record: &CaptureSearchRecord) -> Result<(), String> {
+        let connection = self.get_connection()?;
+
+        let path = record.path.to_string_lossy().to_string();
+        let text = record
+            .text_blocks
+            .iter()
+            .map(|text_block| text_block.text.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let text_blocks = serde_json::to_string(&record.text_blocks).map_err(|e| {
+            format!(
+                "[CaptureSearchService::upsert] Failed to serialize text blocks: {}",
+                e
+            )
+        })?;
+
+        let transaction = connection.transaction().map_err(|e| {
+            format!(
+                "[CaptureSearchService::upsert] Failed to begin transaction: {}",
+                e
+            )
+        })?;
+        let result = transaction
+            .execute(
+                "INSERT OR REPLACE INTO captures (path, window_title, captured_at, text_blocks, scale_factor)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    path,
+                    record.window_title,
+                    record.captured_at as i64,
+                    text_blocks,
+                    record.scale_factor
+                ],
+            )
+            .and_then(|_| transaction.execute("DELETE FROM captures_fts WHERE path = ?1", [&path]))
+            .and_then(|_| {
+                transaction.execute(
+                    "INSERT INTO captures_fts (path, window_title, text) VALUES (?1, ?2, ?3)",
+                    params![path, record.window_title.clone().unwrap_or_default(), text],
+                )
+            })
+            .and_then(|_| transaction.commit());
+
+        result.map_err(|e| {
+            format!(
+                "[CaptureSearchService::upsert] Failed to write record: {}",
+                e
+            )
+        })
+    }
+
+    pub fn remove(&mut self, path: &Path) -> Result<(), String> {
+        let connection = self.get_connection()?;
+        let path = path.to_string_lossy().to_string();
+
+        connection
+            .execute("DELETE FROM captures WHERE path = ?1", [&path])
+            .and_then(|_| connection.execute("DELETE FROM captures_fts WHERE path = ?1", [&path]))
+            .map_err(|e| {
+                format!(
+                    "[CaptureSearchService::remove] Failed to remove record: {}",
+                    e
+                )
+            })?;
+
+        Ok(())
+    }
+
+    pub fn search(
+        &mut self,
+        query: &CaptureSearchQuery,
+    ) -> Result<Vec<CaptureSearchResult>, String> {
+        let connection = self.get_connection()?;
+
+        let keywords: Vec<String> = query
+            .text
+            .split_whitespace()
+            .map(|keyword| keyword.to_lowercase())
+            .collect();
+        let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT) as i64;
+        let offset = query.offset.unwrap_or(0) as i64;
+        let start_time = query.start_time.map(|time| time as i64).unwrap_or(0);
+        let end_time = query.end_time.map(|time| time as i64).unwrap_or(i64::MAX);
+
+        let use_match = !keywords.is_empty()
+            && keywords
+                .iter()
+                .all(|keyword| keyword.chars().count() >= TRIGRAM_MIN_LENGTH);
+
+        let (sql, filter) = if use_match {
+            (
+                "SELECT c.path, c.window_title, c.captured_at, c.text_blocks, c.scale_factor, bm25(captures_fts) AS rank
+                FROM captures_fts JOIN captures c ON c.path = captures_fts.path
+                WHERE captures_fts MATCH ?1 AND c.captured_at BETWEEN ?2 AND ?3
+                ORDER BY rank, c.captured_at DESC
+                LIMIT ?4 OFFSET ?5",
+                Self::to_match_query(&keywords),
+            )
+        } else {
+            // 关键词过短时退化为按顺序的 LIKE 匹配，按时间排序
+            (
+                "SELECT c.path, c.window_title, c.captured_at, c.text_blocks, c.scale_factor, 0.0 AS rank
+                FROM captures_fts JOIN captures c ON c.path = captures_fts.path
+                WHERE (?1 = '' OR (captures_fts.text || ' ' || captures_fts.window_title) LIKE ?1 ESCAPE '\\')
+                    AND c.captured_at BETWEEN ?2 AND ?3
+                ORDER BY c.captured_at DESC
+                LIMIT ?4 OFFSET ?5",
+                Self::to_like_pattern(&keywords),
+            )
+        };
+
+        let mut statement = connection.prepare_cached(sql).map_err(|e| {
+            format!(
+                "[CaptureSearchService::search] Failed to prepare query: {}",
+                e
+            )
+        })?;
+
+        let rows = statement
+            .query_map(
+                params![filter, start_time, end_time, limit, offset],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, f64>(4)?,
+                        row.get::<_, f64>(5)?,
+                    ))
+                },
+            )
+            .map_err(|e| format!("[CaptureSearchService::search] Failed to query: {}", e))?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            let (path, window_title, captured_at, text_blocks, scale_factor, rank) = row
+                .map_err(|e| format!("[CaptureSearchService::search] Failed to read row: {}", e))?;
+
+            let text_blocks: Vec<TextBlock> =
+                serde_json::from_str(&text_blocks).unwrap_or_default();
+
+            results.push(CaptureSearchResult {
+                path: PathBuf::from(path),
+                window_title,
+                captured_at: captured_at as u64,
+                // bm25 越小越相关
+                score: -rank,
+                highlights: Self::get_highlights(&text_blocks, scale_factor as f32, &keywords),
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// 每个关键词作为短语，避免关键词中的符号被解析为 FTS5 语法
+    fn to_match_query(keywords: &[String]) -> String {
+        keywords
+            .iter()
+            .map(|keyword| format!("\"{}\"", keyword.replace('"', "\"\"")))
+            .collect::<Vec<String>>()
+            .join(" AND ")
+    }
+
+    fn to_like_pattern(keywords: &[String]) -> String {
+        if keywords.is_empty() {
+            return String::new();
+        }
+
+        let keywords = keywords
+            .iter()
+            .map(|keyword| {
+                keyword
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            })
+            .collect::<Vec<String>>()
+            .join("%");
+
+        format!("%{}%", keywords)
+    }
+
+    fn get_highlights(
+        text_blocks: &[TextBlock],
+        scale_factor: f32,
+        keywords: &[String],
+    ) -> Vec<CaptureSearchHighlight> {
+        if keywords.is_empty() {
+            return Vec::new();
+        }
+
+        let scale_factor = if scale_factor > 0.0 {
+            scale_factor
+        } else {
+            1.0
+        };
+
+        text_blocks
+            .iter()
+            .filter(|text_block| {
+                let text = text_block.text.to_lowercase();
+                keywords
+                    .iter()
+                    .any(|keyword| text.contains(keyword.as_str()))
+            })
+            .filter_map(|text_block| {
+                let min_x = text_block.box_points.iter().map(|point| point.x).min()?;
+                let min_y = text_block.box_points.iter().map(|point| point.y).min()?;
+                let max_x = text_block.box_points.iter().map(|point| point.x).max()?;
+                let max_y = text_block.box_points.iter().map(|point| point.y).max()?;
+
+                Some(CaptureSearchHighlight {
+                    text: text_block.text.clone(),
+                    rect: ElementRect {
+                        min_x: (min_x as f32 / scale_factor).floor() as i32,
+                        min_y: (min_y as f32 / scale_factor).floor() as i32,
+                        max_x: (max_x as f32 / scale_factor).ceil() as i32,
+                        max_y: (max_y as f32 / scale_factor).ceil() as i32,
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use paddle_ocr_rs::ocr_result::Point;
+
+    fn get_record(path: &str, captured_at: u64, texts: &[&str]) -> CaptureSearchRecord {
+        CaptureSearchRecord {
+            path: PathBuf::from(path),
+            window_title: Some("Editor".to_string()),
+            captured_at,
+            text_blocks: texts
+                .iter()
+                .map(|text| TextBlock {
+                    box_points: vec![
+                        Point { x: 20, y: 10 },
+                        Point { x: 80, y: 10 },
+                        Point { x: 80, y: 30 },
+                        Point { x: 20, y: 30 },
+                    ],
+                    box_score: 1.0,
+                    angle_index: 0,
+                    angle_score: 1.0,
+                    text: text.to_string(),
+                    text_score: 1.0,
+                })
+                .collect(),
+            scale_factor: 2.0,
+        }
+    }
+
+    fn search_paths(service: &mut CaptureSearchService, text: &str) -> Vec<PathBuf> {
+        service
+            .search(&CaptureSearchQuery {
+                text: text.to_string(),
+                ..Default::default()
+            })
+            .unwrap()
+            .into_iter()
+            .map(|result| result.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_search_round_trip() {
+        let mut service = CaptureSearchService::new();
+        service
+            .init_connection(Connection::open_in_memory().unwrap())
+            .unwrap();
+
+        service
+            .upsert(&get_record(
+                "/captures/a.png",
+                100,
+                &["Hello World", "截图工具"],
+            ))
+            .unwrap();
+        service
+            .upsert(&get_record("/captures/b.png", 200, &["cargo build"]))
+            .unwrap();
+
+        // trigram 匹配
+        assert_eq!(
+            search_paths(&mut service, "hello"),
+            vec![PathBuf::from("/captures/a.png")]
+        );
+        assert_eq!(
+            search_paths(&mut service, "截图工"),
+            vec![PathBuf::from("/captures/a.png")]
+        );
+        let results = service
+            .search(&CaptureSearchQuery {
+                text: "build".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].highlights.len(), 1);
+        assert_eq!(results[0].highlights[0].rect.min_x, 10);
+        assert_eq!(results[0].highlights[0].rect.max_y, 15);
+
+        // 少于 3 个字符时使用 LIKE 匹配
+        assert_eq!(
+            search_paths(&mut service, "截图"),
+            vec![PathBuf::from("/captures/a.png")]
+        );
+        assert_eq!(
+            search_paths(&mut service, "go"),
+            vec![PathBuf::from("/captures/b.png")]
+        );
+        // 关键词为空时按时间倒序返回全部记录
+        assert_eq!(
+            search_paths(&mut service, ""),
+            vec![
+                PathBuf::from("/captures/b.png"),
+                PathBuf::from("/captures/a.png")
+            ]
+        );
+
+        // 同一路径的记录会被替换
+        service
+            .upsert(&get_record("/captures/a.png", 100, &["replaced"]))
+            .unwrap();
+        assert!(search_paths(&mut service, "hello").is_empty());
+
+        service.remove(Path::new("/captures/a.png")).unwrap();
+        assert!(search_paths(&mut service, "replaced").is_empty());
+        assert_eq!(
+            search_paths(&mut service, ""),
+            vec![PathBuf::from("/captures/b.png")]
+        );
+    }
+
+    #[test]
+    fn test_to_match_query() {
+        let keywords = vec!["hello".to_string(), "a\"b*".to_string()];
+
+        assert_eq!(
+            CaptureSearchService::to_match_query(&keywords),
+            "\"hello\" AND \"a\"\"b*\""
+        );
+    }
+
+    #[test]
+    fn test_to_like_pattern() {
+        let keywords = vec!["ab".to_string(), "5%".to_string()];
+
+        assert_eq!(
+            CaptureSearchService::to_like_pattern(&keywords),
+            "%ab%5\\%%"
+        );
+        assert_eq!(CaptureSearchService::to_like_pattern(&[]), "");
+    }
+}
@@ -1,9 +1,18 @@
+pub mod barcode_service;
+pub mod capture_search_service;
 pub mod device_event_handler_service;
 pub mod file_cache_service;
 pub mod free_drag_window_service;
 pub mod hot_load_page_service;
 pub mod listen_key_service;
 pub mod listen_mouse_service;
+pub mod native_video_record_service;
+pub mod ocr_batch_service;
+pub mod ocr_engine;
 pub mod ocr_service;
+pub mod record_overlay_service;
+pub mod redaction_service;
 pub mod resize_window_service;
+pub mod translation_render_service;
+pub mod video_export_service;
 pub mod video_record_service;
@@ -0,0 +1,611 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use image::RgbImage;
+use image::imageops::FilterType;
+use minimp4::Mp4Muxer;
+use openh264::OpenH264API;
+use openh264::encoder::{BitRate, Encoder, EncoderConfig, FrameRate};
+use openh264::formats::{RgbSliceU8, YUVBuffer};
+use serde::{Deserialize, Serialize};
+use snow_shot_app_shared::ElementRect;
+use snow_shot_app_utils::monitor_info::{
+    CaptureOption, ColorFormat, CorrectHdrColorAlgorithm, MonitorList,
+};
+use tokio::sync::mpsc::{Receiver, Sender, channel};
+use tokio::task::JoinHandle;
+
+use crate::record_overlay_service::{RecordOverlay, RecordOverlayOptions, RecordOverlayRenderer};
+use crate::video_record_service::VideoRecordState;
+
+/// 编码队列长度，编码跟不上采集时采集会等待
+const FRAME_QUEUE_SIZE: usize = 4;
+
+/// 暂停时检查状态的间隔
+const PAUSE_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Cisco 发布的 OpenH264 动态库，作为插件下载，不随应用分发
+#[cfg(target_os = "windows")]
+const OPENH264_LIBRARY_NAME: &str = "openh264.dll";
+#[cfg(target_os = "macos")]
+const OPENH264_LIBRARY_NAME: &str = "libopenh264.dylib";
+#[cfg(target_os = "linux")]
+const OPENH264_LIBRARY_NAME: &str = "libopenh264.so";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NativeVideoRecordOptions {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub max_x: i32,
+    pub max_y: i32,
+    /// 输出的 mp4 文件
+    pub output_file: PathBuf,
+    pub frame_rate: u32,
+    /// 码率，kbps，为空时根据分辨率和帧率估算
+    pub bitrate: Option<u32>,
+    pub video_max_width: u32,
+    pub video_max_height: u32,
+    /// 光标、点击和按键效果，为空时不绘制
+    ///
+    /// 只有进程内录制可以逐帧绘制，ffmpeg 录制直接采集屏幕，不支持该选项
+    #[serde(default)]
+    pub overlay: Option<RecordOverlayOptions>,
+}
+
+impl NativeVideoRecordOptions {
+    /// 输出视频的大小，保持宽高比且宽高均为偶数
+    fn get_video_size(&self) -> (u32, u32) {
+        let width = (self.max_x - self.min_x).max(0) as u32;
+        let height = (self.max_y - self.min_y).max(0) as u32;
+
+        let scale = (self.video_max_width as f64 / width as f64)
+            .min(self.video_max_height as f64 / height as f64)
+            .min(1.0);
+
+        let target_width = (width as f64 * scale) as u32;
+        let target_height = (height as f64 * scale) as u32;
+
+        (target_width & !1, target_height & !1)
+    }
+
+    fn get_bitrate(&self, width: u32, height: u32) -> u32 {
+        match self.bitrate {
+            Some(bitrate) => bitrate * 1000,
+            // 屏幕内容变化较少，每像素 0.1 bit 足够清晰
+            None => (width as f64 * height as f64 * self.frame_rate as f64 * 0.1) as u32,
+        }
+    }
+}
+
+/// 录制时间轴，扣除暂停的时长后计算每一帧的时间
+struct FrameClock {
+    frame_interval: Duration,
+    paused_duration: Duration,
+    /// 已写入的帧数
+    frame_index: u32,
+}
+
+impl FrameClock {
+    fn new(frame_rate: u32) -> Self {
+        Self {
+            frame_interval: Duration::from_secs_f64(1.0 / frame_rate as f64),
+            paused_duration: Duration::ZERO,
+            frame_index: 0,
+        }
+    }
+
+    fn add_paused_duration(&mut self, duration: Duration) {
+        self.paused_duration += duration;
+    }
+
+    /// 开始录制后经过的时间减去暂停的时长
+    fn get_recorded_duration(&self, elapsed: Duration) -> Duration {
+        elapsed.saturating_sub(self.paused_duration)
+    }
+
+    /// 距离下一帧的等待时间，为空时需要立即采集
+    fn get_wait_duration(&self, recorded_duration: Duration) -> Option<Duration> {
+        let next_frame_time = self.frame_interval * self.frame_index;
+        if recorded_duration < next_frame_time {
+            Some(next_frame_time - recorded_duration)
+        } else {
+            None
+        }
+    }
+
+    /// 采集耗时超过帧间隔时错过的帧数，这些帧使用上一帧补齐
+    fn get_missed_frames(&self, recorded_duration: Duration) -> u32 {
+        let mut missed_frames = 0;
+        while self.frame_interval * (self.frame_index + missed_frames + 1) <= recorded_duration {
+            missed_frames += 1;
+        }
+
+        missed_frames
+    }
+
+    fn next_frame(&mut self) {
+        self.frame_index += 1;
+    }
+}
+
+struct NativeRecordingJob {
+    paused: Arc<AtomicBool>,
+    stopped: Arc<AtomicBool>,
+    handle: JoinHandle<Result<PathBuf, String>>,
+    // 录制结束后停止监听输入事件
+    _overlay: Option<RecordOverlay>,
+}
+
+/// 进程内的屏幕录制，直接采集显示器画面并编码为 H.264
+///
+/// 暂停时不再写入帧，恢复后时间轴继续，不需要分段再合并
+///
+/// 只录制视频，不采集麦克风和系统音频，需要音频时使用 ffmpeg 录制
+///
+/// 编码器运行时加载 OpenH264 插件中的 Cisco 二进制，未安装插件时无法开始录制
+pub struct NativeVideoRecordService {
+    pub state: VideoRecordState,
+    job: Option<NativeRecordingJob>,
+    openh264_path: Option<PathBuf>,
+}
+
+impl NativeVideoRecordService {
+    pub fn new() -> Self {
+        Self {
+            state: VideoRecordState::Idle,
+            job: None,
+            openh264_path: None,
+        }
+    }
+
+    pub fn init(&mut self, openh264_plugin_dir: &Path) {
+        self.openh264_path = Some(openh264_plugin_dir.join(OPENH264_LIBRARY_NAME));
+    }
+
+    /// 加载 OpenH264 动态库，会校验是否为 Cisco 发布的版本
+    fn load_openh264_api(openh264_path: &Path) -> Result<OpenH264API, String> {
+        OpenH264API::from_blob_path(openh264_path).map_err(|e| {
+            format!(
+                "[NativeVideoRecordService::load_openh264_api] Failed to load {}: {}",
+                openh264_path.display(),
+                e
+            )
+        })
+    }
+
+    pub fn start(&mut self, options: NativeVideoRecordOptions) -> Result<(), String> {
+        if self.state != VideoRecordState::Idle {
+            return Err(
+                "[NativeVideoRecordService::start] Recording is already in progress".to_string(),
+            );
+        }
+
+        if options.frame_rate == 0 {
+            return Err("[NativeVideoRecordService::start] Invalid frame rate".to_string());
+        }
+
+        let (width, height) = options.get_video_size();
+        if width == 0 || height == 0 {
+            return Err(
+                "[NativeVideoRecordService::start] Invalid recording area dimensions".to_string(),
+            );
+        }
+
+        if let Some(parent_dir) = options.output_file.parent() {
+            std::fs::create_dir_all(parent_dir).map_err(|e| {
+                format!(
+                    "[NativeVideoRecordService::start] Failed to create output directory: {}",
+                    e
+                )
+            })?;
+        }
+
+        let openh264_api = match &self.openh264_path {
+            Some(openh264_path) => Self::load_openh264_api(openh264_path)?,
+            None => {
+                return Err(
+                    "[NativeVideoRecordService::start] OpenH264 plugin is not installed"
+                        .to_string(),
+                );
+            }
+        };
+
+        // 先创建编码器，避免开始录制后才发现参数无效
+        let encoder = Encoder::with_api_config(
+            openh264_api,
+            EncoderConfig::new()
+                .max_frame_rate(FrameRate::from_hz(options.frame_rate as f32))
+                .bitrate(BitRate::from_bps(options.get_bitrate(width, height))),
+        )
+        .map_err(|e| {
+            format!(
+                "[NativeVideoRecordService::start] Failed to create encoder: {}",
+                e
+            )
+        })?;
+        let output_file = File::create(&options.output_file).map_err(|e| {
+            format!(
+                "[NativeVideoRecordService::start] Failed to create output file: {}",
+                e
+            )
+        })?;
+
+        log::info!(
+            "[NativeVideoRecordService::start] {}x{} at {} fps, output: {}",
+            width,
+            height,
+            options.frame_rate,
+            options.output_file.display()
+        );
+
+        let overlay = match &options.overlay {
+            Some(overlay_options) => Some(RecordOverlay::start(overlay_options.clone())?),
+            None => None,
+        };
+        let overlay_renderer = overlay.as_ref().map(|overlay| overlay.get_renderer());
+
+        let (frame_sender, frame_receiver) = channel::<Arc<RgbImage>>(FRAME_QUEUE_SIZE);
+        let frame_rate = options.frame_rate;
+        let encode_thread = std::thread::spawn(move || {
+            Self::encode_frames(
+                encoder,
+                output_file,
+                frame_receiver,
+                width,
+                height,
+                frame_rate,
+            )
+        });
+
+        let paused = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let handle = tokio::spawn(Self::capture_frames(
+            options,
+            width,
+            height,
+            paused.clone(),
+            stopped.clone(),
+            frame_sender,
+            encode_thread,
+            overlay_renderer,
+        ));
+
+        self.job = Some(NativeRecordingJob {
+            paused,
+            stopped,
+            handle,
+            _overlay: overlay,
+        });
+        self.state = VideoRecordState::Recording;
+
+        Ok(())
+    }
+
+    pub fn pause(&mut self) -> Result<(), String> {
+        match (&self.job, self.state) {
+            (Some(job), VideoRecordState::Recording) => {
+                job.paused.store(true, Ordering::Relaxed);
+                self.state = VideoRecordState::Paused;
+                Ok(())
+            }
+            _ => Err("[NativeVideoRecordService::pause] No recording in progress".to_string()),
+        }
+    }
+
+    pub fn resume(&mut self) -> Result<(), String> {
+        match (&self.job, self.state) {
+            (Some(job), VideoRecordState::Paused) => {
+                job.paused.store(false, Ordering::Relaxed);
+                self.state = VideoRecordState::Recording;
+                Ok(())
+            }
+            _ => Err("[NativeVideoRecordService::resume] Recording is not paused".to_string()),
+        }
+    }
+
+    /// 停止录制，等待剩余的帧编码完成，返回输出文件
+    pub async fn stop(&mut self) -> Result<Option<PathBuf>, String> {
+        let job = match self.job.take() {
+            Some(job) => job,
+            None => return Ok(None),
+        };
+        self.state = VideoRecordState::Idle;
+
+        job.stopped.store(true, Ordering::Relaxed);
+        job.paused.store(false, Ordering::Relaxed);
+
+        match job.handle.await {
+            Ok(result) => result.map(Some),
+            Err(e) => Err(format!(
+                "[NativeVideoRecordService::stop] Failed to join capture task: {}",
+                e
+            )),
+        }
+    }
+
+    /// 按帧率采集画面，采集耗时超过帧间隔时重复上一帧，保证视频时长与实际录制时长一致
+    async fn capture_frames(
+        options: NativeVideoRecordOptions,
+        width: u32,
+        height: u32,
+        paused: Arc<AtomicBool>,
+        stopped: Arc<AtomicBool>,
+        frame_sender: Sender<Arc<RgbImage>>,
+        encode_thread: std::thread::JoinHandle<Result<(), String>>,
+        overlay_renderer: Option<RecordOverlayRenderer>,
+    ) -> Result<PathBuf, String> {
+        let region = ElementRect {
+            min_x: options.min_x,
+            min_y: options.min_y,
+            max_x: options.max_x,
+            max_y: options.max_y,
+        };
+        let monitor_list = MonitorList::get_by_region(region, true);
+        let capture_option = CaptureOption {
+            color_format: ColorFormat::Rgb8,
+            correct_hdr_color_algorithm: CorrectHdrColorAlgorithm::None,
+            correct_color_filter: false,
+        };
+
+        let start_time = Instant::now();
+        let mut clock = FrameClock::new(options.frame_rate);
+        let mut last_frame: Option<Arc<RgbImage>> = None;
+        let mut capture_error = None;
+
+        while !stopped.load(Ordering::Relaxed) {
+            if paused.load(Ordering::Relaxed) {
+                let pause_start = Instant::now();
+                while paused.load(Ordering::Relaxed) && !stopped.load(Ordering::Relaxed) {
+                    tokio::time::sleep(PAUSE_CHECK_INTERVAL).await;
+                }
+                clock.add_paused_duration(pause_start.elapsed());
+                continue;
+            }
+
+            let recorded_duration = clock.get_recorded_duration(start_time.elapsed());
+            if let Some(wait_duration) = clock.get_wait_duration(recorded_duration) {
+                tokio::time::sleep(wait_duration).await;
+                continue;
+            }
+
+            // 错过的帧使用上一帧补齐
+            if let Some(last_frame) = &last_frame {
+                for _ in 0..clock.get_missed_frames(recorded_duration) {
+                    if frame_sender.send(last_frame.clone()).await.is_err() {
+                        break;
+                    }
+                    clock.next_frame();
+                }
+            }
+
+            let mut frame = match monitor_list
+                .capture_region(region, None, capture_option)
+                .await
+            {
+                Ok(image) => image.to_rgb8(),
+                Err(e) => {
+                    capture_error = Some(e);
+                    break;
+                }
+            };
+            if let Some(overlay_renderer) = &overlay_renderer {
+                overlay_renderer.render(&mut frame, region.min_x, region.min_y);
+            }
+            let frame = if frame.dimensions() != (width, height) {
+                image::imageops::resize(&frame, width, height, FilterType::Triangle)
+            } else {
+                frame
+            };
+            let frame = Arc::new(frame);
+
+            // 编码线程退出时结束录制
+            if frame_sender.send(frame.clone()).await.is_err() {
+                break;
+            }
+            last_frame = Some(frame);
+            clock.next_frame();
+        }
+
+        drop(frame_sender);
+
+        let encode_result = match tokio::task::spawn_blocking(move || encode_thread.join()).await {
+            Ok(Ok(result)) => result,
+            _ => {
+                Err("[NativeVideoRecordService::capture_frames] Encode thread panicked".to_string())
+            }
+        };
+
+        if let Some(e) = capture_error {
+            return Err(format!(
+                "[NativeVideoRecordService::capture_frames] Failed to capture frame: {}",
+                e
+            ));
+        }
+        encode_result?;
+
+        log::info!(
+            "[NativeVideoRecordService::capture_frames] Recorded {} frames",
+            clock.frame_index
+        );
+
+        Ok(options.output_file)
+    }
+
+    fn encode_frames(
+        mut encoder: Encoder,
+        output_file: File,
+        mut frame_receiver: Receiver<Arc<RgbImage>>,
+        width: u32,
+        height: u32,
+        frame_rate: u32,
+    ) -> Result<(), String> {
+        let mut muxer = Mp4Muxer::new(BufWriter::new(output_file));
+        muxer.init_video(width as i32, height as i32, false, "Snow Shot");
+
+        while let Some(frame) = frame_receiver.blocking_recv() {
+            let yuv = YUVBuffer::from_rgb_source(RgbSliceU8::new(
+                frame.as_raw(),
+                (width as usize, height as usize),
+            ));
+
+            let bitstream = encoder
+                .encode(&yuv)
+                .map_err(|e| {
+                    format!(
+                        "[NativeVideoRecordService::encode_frames] Failed to encode frame: {}",
+                        e
+                    )
+                })?
+                .to_vec();
+
+            muxer.write_video_with_fps(&bitstream, frame_rate);
+        }
+
+        muxer.close();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_options(width: i32, height: i32) -> NativeVideoRecordOptions {
+        NativeVideoRecordOptions {
+            min_x: 0,
+            min_y: 0,
+            max_x: width,
+            max_y: height,
+            output_file: PathBuf::new(),
+            frame_rate: 30,
+            bitrate: None,
+            video_max_width: 1920,
+            video_max_height: 1080,
+            overlay: None,
+        }
+    }
+
+    #[test]
+    fn test_get_video_size() {
+        assert_eq!(get_options(1281, 721).get_video_size(), (1280, 720));
+        assert_eq!(get_options(3840, 2160).get_video_size(), (1920, 1080));
+        assert_eq!(get_options(3840, 1080).get_video_size(), (1920, 540));
+    }
+
+    #[test]
+    fn test_frame_clock_padding() {
+        let mut clock = FrameClock::new(10);
+
+        // 第一帧立即采集
+        assert_eq!(clock.get_wait_duration(Duration::ZERO), None);
+        clock.next_frame();
+        assert_eq!(
+            clock.get_wait_duration(Duration::from_millis(40)),
+            Some(Duration::from_millis(60))
+        );
+        assert_eq!(clock.get_missed_frames(Duration::from_millis(150)), 0);
+
+        // 采集耗时 350ms，错过第 2、3 帧
+        let recorded_duration = Duration::from_millis(350);
+        assert_eq!(clock.get_wait_duration(recorded_duration), None);
+        assert_eq!(clock.get_missed_frames(recorded_duration), 2);
+        for _ in 0..2 {
+            clock.next_frame();
+        }
+        clock.next_frame();
+        assert_eq!(clock.frame_index, 4);
+        assert_eq!(
+            clock.get_wait_duration(recorded_duration),
+            Some(Duration::from_millis(50))
+        );
+    }
+
+    #[test]
+    fn test_frame_clock_pause_resume() {
+        let mut clock = FrameClock::new(10);
+        clock.next_frame();
+        clock.next_frame();
+
+        // 暂停 5 秒后恢复，时间轴从暂停前继续
+        clock.add_paused_duration(Duration::from_secs(5));
+        let recorded_duration = clock.get_recorded_duration(Duration::from_millis(5150));
+        assert_eq!(recorded_duration, Duration::from_millis(150));
+        assert_eq!(
+            clock.get_wait_duration(recorded_duration),
+            Some(Duration::from_millis(50))
+        );
+        assert_eq!(clock.get_missed_frames(recorded_duration), 0);
+
+        assert_eq!(
+            clock.get_recorded_duration(Duration::from_secs(1)),
+            Duration::ZERO
+        );
+    }
+
+    /// 需要通过 SNOW_SHOT_OPENH264_PATH 指定 Cisco 发布的 OpenH264 动态库
+    #[test]
+    fn test_encode_frames() {
+        let openh264_api = match std::env::var_os("SNOW_SHOT_OPENH264_PATH") {
+            Some(openh264_path) => {
+                NativeVideoRecordService::load_openh264_api(Path::new(&openh264_path)).unwrap()
+            }
+            None => {
+                println!("SNOW_SHOT_OPENH264_PATH is not set, skip");
+                return;
+            }
+        };
+
+        let (width, height, frame_rate) = (64, 48, 10);
+        let output_file = std::env::temp_dir().join(format!(
+            "snow_shot_native_record_test_{}.mp4",
+            std::process::id()
+        ));
+
+        let encoder = Encoder::with_api_config(
+            openh264_api,
+            EncoderConfig::new()
+                .max_frame_rate(FrameRate::from_hz(frame_rate as f32))
+                .bitrate(BitRate::from_bps(
+                    get_options(width, height).get_bitrate(width as u32, height as u32),
+                )),
+        )
+        .unwrap();
+        let file = File::create(&output_file).unwrap();
+
+        let (frame_sender, frame_receiver) = channel::<Arc<RgbImage>>(FRAME_QUEUE_SIZE);
+        let encode_thread = std::thread::spawn(move || {
+            NativeVideoRecordService::encode_frames(
+                encoder,
+                file,
+                frame_receiver,
+                width as u32,
+                height as u32,
+                frame_rate,
+            )
+        });
+
+        for index in 0..20u32 {
+            let frame = RgbImage::from_fn(width as u32, height as u32, |x, y| {
+                image::Rgb([(x * 4) as u8, (y * 5) as u8, (index * 12) as u8])
+            });
+            frame_sender.blocking_send(Arc::new(frame)).unwrap();
+        }
+        drop(frame_sender);
+        encode_thread.join().unwrap().unwrap();
+
+        let content = std::fs::read(&output_file).unwrap();
+        std::fs::remove_file(&output_file).unwrap();
+
+        // mp4 以 ftyp box 开始，包含 H.264 的 avc1 描述和媒体数据
+        assert_eq!(&content[4..8], b"ftyp");
+        let contains = |name: &[u8]| content.windows(name.len()).any(|window| window == name);
+        assert!(contains(b"avc1"));
+        assert!(contains(b"mdat"));
+        assert!(contains(b"moov"));
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::UNIX_EPOCH;
+
+use dashmap::DashMap;
+use paddle_ocr_rs::ocr_result::TextBlock;
+use serde::{Deserialize, Serialize};
+
+/// 批量识别支持的图片格式
+const SUPPORTED_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "webp", "bmp", "tiff"];
+
+/// 索引文件版本，结构变化时递增，旧版本的索引会被丢弃
+const OCR_INDEX_VERSION: u32 = 1;
+
+/// 单个文件的识别结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrIndexEntry {
+    /// 文件修改时间，毫秒
+    pub modified_time: u64,
+    pub file_size: u64,
+    pub text_blocks: Vec<TextBlock>,
+    /// text_blocks 相对原图的缩放比例，坐标需要除以该值才能对应原图
+    pub scale_factor: f32,
+}
+
+impl OcrIndexEntry {
+    pub fn get_text(&self) -> String {
+        self.text_blocks
+            .iter()
+            .map(|text_block| text_block.text.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n")
+    }
+}
+
+/// 识别结果索引，以文件路径为键
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrIndex {
+    pub version: u32,
+    pub entries: HashMap<PathBuf, OcrIndexEntry>,
+}
+
+impl Default for OcrIndex {
+    fn default() -> Self {
+        Self {
+            version: OCR_INDEX_VERSION,
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl OcrIndex {
+    /// 读取索引，文件不存在或版本不一致时返回空索引
+    pub async fn load(index_path: &Path) -> Result<Self, String> {
+        let content = match tokio::fs::read(index_path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(format!(
+                    "[OcrIndex::load] Failed to read index {}: {}",
+                    index_path.display(),
+                    e
+                ));
+            }
+        };
+
+        let index: OcrIndex = match serde_json::from_slice(&content) {
+            Ok(index) => index,
+            Err(e) => {
+                log::warn!(
+                    "[OcrIndex::load] Invalid index {}, rebuild it: {}",
+                    index_path.display(),
+                    e
+                );
+                return Ok(Self::default());
+            }
+        };
+
+        if index.version != OCR_INDEX_VERSION {
+            return Ok(Self::default());
+        }
+
+        Ok(index)
+    }
+
+    /// 先写入临时文件再替换，避免中途退出导致索引损坏
+    pub async fn save(&self, index_path: &Path) -> Result<(), String> {
+        let content = serde_json::to_vec(self)
+            .map_err(|e| format!("[OcrIndex::save] Failed to serialize index: {}", e))?;
+
+        if let Some(parent) = index_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("[OcrIndex::save] Failed to create index dir: {}", e))?;
+        }
+
+        let temp_path = index_path.with_extension("tmp");
+        tokio::fs::write(&temp_path, content)
+            .await
+            .map_err(|e| format!("[OcrIndex::save] Failed to write index: {}", e))?;
+        tokio::fs::rename(&temp_path, index_path)
+            .await
+            .map_err(|e| format!("[OcrIndex::save] Failed to replace index: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn is_unchanged(&self, file: &OcrBatchFile) -> bool {
+        match self.entries.get(&file.path) {
+            Some(entry) => {
+                entry.modified_time == file.modified_time && entry.file_size == file.file_size
+            }
+            None => false,
+        }
+    }
+
+    /// 移除目录下已经不存在的文件
+    ///
+    /// 非递归遍历时只处理目录下直接包含的文件，子目录中的索引保持不变
+    pub fn retain_files(&mut self, directory: &Path, recursive: bool, files: &[OcrBatchFile]) {
+        let file_paths: HashSet<&PathBuf> = files.iter().map(|file| &file.path).collect();
+
+        self.entries.retain(|path, _| {
+            let in_scope = if recursive {
+                path.starts_with(directory)
+            } else {
+                path.parent() == Some(directory)
+            };
+
+            !in_scope || file_paths.contains(path)
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OcrBatchFile {
+    pub path: PathBuf,
+    pub modified_time: u64,
+    pub file_size: u64,
+}
+
+/// 批量识别任务的进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrBatchProgress {
+    pub job_id: String,
+    pub total: usize,
+    /// 已识别的文件数
+    pub processed: usize,
+    /// 未修改而跳过的文件数
+    pub skipped: usize,
+    pub failed: usize,
+    pub current_path: Option<PathBuf>,
+    pub finished: bool,
+    pub cancelled: bool,
+    pub error: Option<String>,
+}
+
+pub struct OcrBatchService {
+    jobs: DashMap<String, Arc<AtomicBool>>,
+    next_job_id: AtomicU64,
+}
+
+impl OcrBatchService {
+    pub fn new() -> Self {
+        Self {
+            jobs: DashMap::new(),
+            next_job_id: AtomicU64::new(1),
+        }
+    }
+
+    /// 创建任务，返回任务 id 和取消标记
+    pub fn create_job(&self) -> (String, Arc<AtomicBool>) {
+        let job_id = format!(
+            "ocr-batch-{}",
+            self.next_job_id.fetch_add(1, Ordering::Relaxed)
+        );
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.jobs.insert(job_id.clone(), cancelled.clone());
+
+        (job_id, cancelled)
+    }
+
+    pub fn cancel_job(&self, job_id: &str) -> Result<(), String> {
+        match self.jobs.get(job_id) {
+            Some(cancelled) => {
+                cancelled.store(true, Ordering::Relaxed);
+                Ok(())
+            }
+            None => Err(format!(
+                "[OcrBatchService::cancel_job] Job not found: {}",
+                job_id
+            )),
+        }
+    }
+
+    pub fn finish_job(&self, job_id: &str) {
+        self.jobs.remove(job_id);
+    }
+
+    /// 遍历目录下的图片文件
+    pub fn collect_image_files(
+        directory: &Path,
+        recursive: bool,
+    ) -> Result<Vec<OcrBatchFile>, String> {
+        if !directory.is_dir() {
+            return Err(format!(
+                "[OcrBatchService::collect_image_files] Directory not found: {}",
+                directory.display()
+            ));
+        }
+
+        let mut files = Vec::new();
+        let mut directories = vec![directory.to_path_buf()];
+
+        while let Some(current_directory) = directories.pop() {
+            let entries = match std::fs::read_dir(&current_directory) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    log::warn!(
+                        "[OcrBatchService::collect_image_files] Failed to read dir {}: {}",
+                        current_directory.display(),
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let metadata = match entry.metadata() {
+                    Ok(metadata) => metadata,
+                    Err(_) => continue,
+                };
+
+                if metadata.is_dir() {
+                    if recursive {
+                        directories.push(path);
+                    }
+                    continue;
+                }
+
+                let is_image = path
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .map(|extension| {
+                        SUPPORTED_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+                    })
+                    .unwrap_or(false);
+                if !is_image {
+                    continue;
+                }
+
+                let modified_time = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|modified_time| modified_time.duration_since(UNIX_EPOCH).ok())
+                    .map(|duration| duration.as_millis() as u64)
+                    .unwrap_or(0);
+
+                files.push(OcrBatchFile {
+                    path,
+                    modified_time,
+                    file_size: metadata.len(),
+                });
+            }
+        }
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_entry(modified_time: u64, file_size: u64) -> OcrIndexEntry {
+        OcrIndexEntry {
+            modified_time,
+            file_size,
+            text_blocks: Vec::new(),
+            scale_factor: 1.0,
+        }
+    }
+
+    fn get_file(path: &str, modified_time: u64, file_size: u64) -> OcrBatchFile {
+        OcrBatchFile {
+            path: PathBuf::from(path),
+            modified_time,
+            file_size,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_index_load_save() {
+        let index_path = std::env::temp_dir()
+            .join(format!("snow_shot_ocr_index_test_{}", std::process::id()))
+            .join("index.json");
+
+        // 索引不存在时返回空索引
+        assert!(
+            OcrIndex::load(&index_path)
+                .await
+                .unwrap()
+                .entries
+                .is_empty()
+        );
+
+        let mut index = OcrIndex::default();
+        index
+            .entries
+            .insert(PathBuf::from("/images/a.png"), get_entry(100, 10));
+        index.save(&index_path).await.unwrap();
+
+        let loaded = OcrIndex::load(&index_path).await.unwrap();
+        assert_eq!(loaded.version, OCR_INDEX_VERSION);
+        let entry = loaded.entries.get(Path::new("/images/a.png")).unwrap();
+        assert_eq!(entry.modified_time, 100);
+        assert_eq!(entry.file_size, 10);
+
+        tokio::fs::remove_dir_all(index_path.parent().unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_index_is_unchanged() {
+        let mut index = OcrIndex::default();
+        index
+            .entries
+            .insert(PathBuf::from("/images/a.png"), get_entry(100, 10));
+
+        assert!(index.is_unchanged(&get_file("/images/a.png", 100, 10)));
+        assert!(!index.is_unchanged(&get_file("/images/a.png", 200, 10)));
+        assert!(!index.is_unchanged(&get_file("/images/a.png", 100, 20)));
+        assert!(!index.is_unchanged(&get_file("/images/b.png", 100, 10)));
+    }
+
+    #[test]
+    fn test_index_retain_files() {
+        let mut index = OcrIndex::default();
+        for path in [
+            "/images/a.png",
+            "/images/b.png",
+            "/images/sub/c.png",
+            "/other/d.png",
+        ] {
+            index.entries.insert(PathBuf::from(path), get_entry(0, 0));
+        }
+        let files = [get_file("/images/a.png", 0, 0)];
+
+        // 非递归时保留子目录中的文件
+        let mut non_recursive = index.clone();
+        non_recursive.retain_files(Path::new("/images"), false, &files);
+        let mut paths: Vec<&PathBuf> = non_recursive.entries.keys().collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                Path::new("/images/a.png"),
+                Path::new("/images/sub/c.png"),
+                Path::new("/other/d.png"),
+            ]
+        );
+
+        index.retain_files(Path::new("/images"), true, &files);
+        let mut paths: Vec<&PathBuf> = index.entries.keys().collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![Path::new("/images/a.png"), Path::new("/other/d.png")]
+        );
+    }
+}
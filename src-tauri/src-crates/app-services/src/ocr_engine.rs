@@ -0,0 +1,100 @@
+pub mod detect_options;
+pub mod rapid_ocr;
+pub mod rapid_ocr_model;
+pub mod tesseract;
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use image::RgbImage;
+use paddle_ocr_rs::ocr_result::TextBlock;
+use serde::{Deserialize, Serialize};
+
+use crate::ocr_engine::detect_options::OcrDetectOptions;
+use crate::ocr_engine::rapid_ocr_model::OcrRecModelConfig;
+
+/// OCR 引擎类型
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Default, PartialOrd, Serialize, Deserialize)]
+pub enum OcrEngineType {
+    /// 基于 paddle-ocr-rs 的 RapidOCR 模型
+    #[default]
+    RapidOcr,
+    /// 调用 Tesseract 命令行
+    Tesseract,
+}
+
+impl FromStr for OcrEngineType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "RapidOcr" => Ok(OcrEngineType::RapidOcr),
+            "Tesseract" => Ok(OcrEngineType::Tesseract),
+            _ => Err(format!(
+                "[OcrEngineType::from_str] Unknown ocr engine: {}",
+                value
+            )),
+        }
+    }
+}
+
+/// 引擎可用的识别模型
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrModelInfo {
+    pub engine: OcrEngineType,
+    /// 模型 id，识别时通过 id 选择模型
+    pub id: String,
+    pub name: String,
+    pub languages: Vec<String>,
+    /// 识别模型文件路径，tesseract 为空
+    pub model_path: Option<PathBuf>,
+    /// 字典文件路径，字典内置在模型中时为空
+    pub dict_path: Option<PathBuf>,
+    /// 字典字符数
+    pub dict_size: Option<usize>,
+    /// 模型输入形状，动态维度为 -1
+    pub input_shape: Option<Vec<i64>>,
+    /// 是否为内置模型
+    pub built_in: bool,
+    /// 会话是否已加载
+    pub loaded: bool,
+}
+
+/// OCR 引擎
+///
+/// 由 OcrService 统一管理，识别结果统一转换为 TextBlock，坐标基于传入的图片
+pub trait OcrEngine: Send {
+    fn engine_type(&self) -> OcrEngineType;
+
+    /// 会话是否已经初始化
+    fn is_session_ready(&self) -> bool;
+
+    /// 初始化识别会话，会话已存在时重新创建
+    fn init_session(&mut self) -> Result<(), String>;
+
+    /// 释放识别会话
+    fn release_session(&mut self);
+
+    /// 获取引擎可用的识别模型
+    fn get_models(&self) -> Vec<OcrModelInfo>;
+
+    /// 注册自定义识别模型，同 id 的模型会被替换
+    fn register_model(&mut self, config: OcrRecModelConfig) -> Result<OcrModelInfo, String> {
+        Err(format!(
+            "[OcrEngine::register_model] Engine {:?} does not support custom model: {}",
+            self.engine_type(),
+            config.id
+        ))
+    }
+
+    /// 识别文字，`model_id` 为空时使用引擎的默认模型
+    ///
+    /// 图片的缩放和预处理由调用方完成，引擎只使用 `options` 中的检测参数
+    fn detect(
+        &mut self,
+        image: &RgbImage,
+        detect_angle: bool,
+        options: &OcrDetectOptions,
+        model_id: Option<&str>,
+    ) -> Result<Vec<TextBlock>, String>;
+}
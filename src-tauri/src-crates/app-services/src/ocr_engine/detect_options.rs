@@ -0,0 +1,291 @@
+use std::str::FromStr;
+
+use image::RgbImage;
+use serde::{Deserialize, Serialize};
+
+/// 识别参数预设
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Default, Serialize, Deserialize)]
+pub enum OcrDetectPreset {
+    /// 屏幕界面，文字清晰且多为横排
+    #[default]
+    ScreenUi,
+    /// 密集的代码和小字号文本
+    DenseCode,
+    /// 照片或扫描文档
+    PhotoDocument,
+}
+
+impl FromStr for OcrDetectPreset {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "ScreenUi" => Ok(OcrDetectPreset::ScreenUi),
+            "DenseCode" => Ok(OcrDetectPreset::DenseCode),
+            "PhotoDocument" => Ok(OcrDetectPreset::PhotoDocument),
+            _ => Err(format!(
+                "[OcrDetectPreset::from_str] Unknown detect preset: {}",
+                value
+            )),
+        }
+    }
+}
+
+impl OcrDetectPreset {
+    pub fn options(&self) -> OcrDetectOptions {
+        match self {
+            OcrDetectPreset::ScreenUi => OcrDetectOptions {
+                padding: 50,
+                box_score_thresh: 0.5,
+                box_thresh: 0.3,
+                unclip_ratio: 1.6,
+                // 屏幕截取的文字质量通常较高，且非横向排版的情况较少，尽量减少角度的影响
+                angle_threshold: 0.9,
+                min_scale_factor: 1.5,
+                preprocess: OcrPreprocessOptions::default(),
+            },
+            // 降低阈值避免漏掉小字，缩小扩张比例避免相邻行被合并
+            OcrDetectPreset::DenseCode => OcrDetectOptions {
+                padding: 50,
+                box_score_thresh: 0.4,
+                box_thresh: 0.25,
+                unclip_ratio: 1.3,
+                angle_threshold: 0.95,
+                min_scale_factor: 2.0,
+                preprocess: OcrPreprocessOptions::default(),
+            },
+            // 照片中的文字可能倾斜且对比度较低
+            OcrDetectPreset::PhotoDocument => OcrDetectOptions {
+                padding: 50,
+                box_score_thresh: 0.6,
+                box_thresh: 0.3,
+                unclip_ratio: 2.0,
+                angle_threshold: 0.7,
+                min_scale_factor: 1.0,
+                preprocess: OcrPreprocessOptions {
+                    contrast_stretch: true,
+                    ..OcrPreprocessOptions::default()
+                },
+            },
+        }
+    }
+}
+
+/// 识别前的图像预处理
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OcrPreprocessOptions {
+    /// 使用 Otsu 阈值二值化
+    pub binarize: bool,
+    /// 将亮度范围拉伸到 0-255
+    pub contrast_stretch: bool,
+    /// 平均亮度较低时反色，处理深色模式的界面
+    pub invert_dark_mode: bool,
+}
+
+/// 识别参数
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OcrDetectOptions {
+    /// 图片四周的填充像素
+    pub padding: u32,
+    /// 文本框的最低得分
+    pub box_score_thresh: f32,
+    /// 文本区域的像素阈值
+    pub box_thresh: f32,
+    /// 文本框的扩张比例
+    pub unclip_ratio: f32,
+    /// 文字方向的置信度阈值
+    pub angle_threshold: f32,
+    /// 有效缩放低于该值时放大图片，为 0 时不放大
+    pub min_scale_factor: f32,
+    pub preprocess: OcrPreprocessOptions,
+}
+
+impl Default for OcrDetectOptions {
+    fn default() -> Self {
+        OcrDetectPreset::default().options()
+    }
+}
+
+impl OcrPreprocessOptions {
+    pub fn is_empty(&self) -> bool {
+        !self.binarize && !self.contrast_stretch && !self.invert_dark_mode
+    }
+
+    pub fn apply(&self, image: &mut RgbImage) {
+        if self.invert_dark_mode && is_dark_image(image) {
+            image
+                .pixels_mut()
+                .for_each(|pixel| pixel.0 = pixel.0.map(|value| 255 - value));
+        }
+
+        if self.contrast_stretch {
+            contrast_stretch(image);
+        }
+
+        if self.binarize {
+            binarize(image);
+        }
+    }
+}
+
+fn get_luminance(pixel: &image::Rgb<u8>) -> u8 {
+    let [r, g, b] = pixel.0;
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+fn get_histogram(image: &RgbImage) -> [u32; 256] {
+    let mut histogram = [0u32; 256];
+    image
+        .pixels()
+        .for_each(|pixel| histogram[get_luminance(pixel) as usize] += 1);
+    histogram
+}
+
+fn is_dark_image(image: &RgbImage) -> bool {
+    let pixel_count = image.width() as u64 * image.height() as u64;
+    if pixel_count == 0 {
+        return false;
+    }
+
+    let total: u64 = image
+        .pixels()
+        .map(|pixel| get_luminance(pixel) as u64)
+        .sum();
+
+    total / pixel_count < 128
+}
+
+/// 忽略两端 1% 的像素，避免个别极值影响拉伸效果
+fn contrast_stretch(image: &mut RgbImage) {
+    let histogram = get_histogram(image);
+    let pixel_count: u32 = histogram.iter().sum();
+    let clip_count = pixel_count / 100;
+
+    let mut low = 0;
+    let mut count = 0;
+    for (value, value_count) in histogram.iter().enumerate() {
+        count += value_count;
+        if count > clip_count {
+            low = value;
+            break;
+        }
+    }
+
+    let mut high = 255;
+    let mut count = 0;
+    for (value, value_count) in histogram.iter().enumerate().rev() {
+        count += value_count;
+        if count > clip_count {
+            high = value;
+            break;
+        }
+    }
+
+    if high <= low {
+        return;
+    }
+
+    let range = (high - low) as f32;
+    let lookup: Vec<u8> = (0..256)
+        .map(|value| {
+            (((value as f32 - low as f32) / range) * 255.0)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        })
+        .collect();
+
+    image
+        .pixels_mut()
+        .for_each(|pixel| pixel.0 = pixel.0.map(|value| lookup[value as usize]));
+}
+
+fn get_otsu_threshold(histogram: &[u32; 256]) -> u8 {
+    let pixel_count: u64 = histogram.iter().map(|count| *count as u64).sum();
+    let total: u64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(value, count)| value as u64 * *count as u64)
+        .sum();
+
+    let mut background_count = 0u64;
+    let mut background_total = 0u64;
+    let mut best_threshold = 0;
+    let mut best_variance = 0.0;
+
+    for (value, count) in histogram.iter().enumerate() {
+        background_count += *count as u64;
+        if background_count == 0 {
+            continue;
+        }
+
+        let foreground_count = pixel_count - background_count;
+        if foreground_count == 0 {
+            break;
+        }
+
+        background_total += value as u64 * *count as u64;
+        let background_mean = background_total as f64 / background_count as f64;
+        let foreground_mean = (total - background_total) as f64 / foreground_count as f64;
+        let variance = background_count as f64
+            * foreground_count as f64
+            * (background_mean - foreground_mean).powi(2);
+
+        if variance > best_variance {
+            best_variance = variance;
+            best_threshold = value;
+        }
+    }
+
+    best_threshold as u8
+}
+
+fn binarize(image: &mut RgbImage) {
+    let threshold = get_otsu_threshold(&get_histogram(image));
+
+    image.pixels_mut().for_each(|pixel| {
+        let value = if get_luminance(pixel) > threshold {
+            255
+        } else {
+            0
+        };
+        pixel.0 = [value; 3];
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preprocess() {
+        let mut image = RgbImage::from_fn(4, 1, |x, _| image::Rgb([(x * 10) as u8 + 20; 3]));
+
+        OcrPreprocessOptions {
+            binarize: false,
+            contrast_stretch: false,
+            invert_dark_mode: true,
+        }
+        .apply(&mut image);
+        assert_eq!(image.get_pixel(0, 0).0, [235; 3]);
+
+        OcrPreprocessOptions {
+            binarize: false,
+            contrast_stretch: true,
+            invert_dark_mode: false,
+        }
+        .apply(&mut image);
+        assert_eq!(image.get_pixel(0, 0).0, [255; 3]);
+        assert_eq!(image.get_pixel(3, 0).0, [0; 3]);
+
+        OcrPreprocessOptions {
+            binarize: true,
+            contrast_stretch: false,
+            invert_dark_mode: false,
+        }
+        .apply(&mut image);
+        assert_eq!(image.get_pixel(1, 0).0, [255; 3]);
+        assert_eq!(image.get_pixel(2, 0).0, [0; 3]);
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use image::RgbImage;
+use num_cpus;
+use ort::session::builder::SessionBuilder;
+use paddle_ocr_rs::{ocr_lite::OcrLite, ocr_result::TextBlock};
+
+use crate::ocr_engine::detect_options::OcrDetectOptions;
+use crate::ocr_engine::rapid_ocr_model::{OcrRecModelConfig, RapidOcrRecModel};
+use crate::ocr_engine::{OcrEngine, OcrEngineType, OcrModelInfo};
+use crate::ocr_service::OcrModel;
+
+/// 内置识别模型的 id
+pub const DEFAULT_REC_MODEL_ID: &str = "default";
+
+pub struct RapidOcrEngine {
+    /// 每个识别模型对应一个会话，按需创建
+    ocr_cores: HashMap<String, OcrLite>,
+    det_model: (PathBuf, Option<Vec<u8>>),
+    cls_model: (PathBuf, Option<Vec<u8>>),
+    /// 第一个为内置模型
+    rec_models: Vec<RapidOcrRecModel>,
+    ocr_model_write_to_memory: bool,
+}
+
+impl RapidOcrEngine {
+    pub async fn new(
+        orc_plugin_path: &Path,
+        model: OcrModel,
+        ocr_model_write_to_memory: bool,
+    ) -> Result<Self, String> {
+        let (det_model_path, cls_model_path, rec_model_path) = match model {
+            OcrModel::RapidOcrV4 => (
+                orc_plugin_path.join("ch_PP-OCRv4_det_infer.onnx"),
+                orc_plugin_path.join("ch_ppocr_mobile_v2.0_cls_infer.onnx"),
+                orc_plugin_path.join("ch_PP-OCRv4_rec_infer.onnx"),
+            ),
+            OcrModel::RapidOcrV5 => (
+                orc_plugin_path.join("ch_PP-OCRv4_det_infer.onnx"),
+                orc_plugin_path.join("ch_ppocr_mobile_v2.0_cls_infer.onnx"),
+                orc_plugin_path.join("ch_PP-OCRv5_rec_mobile_infer.onnx"),
+            ),
+        };
+
+        // 加载模型到内存
+        let (det_model_data, cls_model_data, rec_model_data) = if ocr_model_write_to_memory {
+            let (det_result, cls_result, rec_result) =
+                Self::read_model_data(&det_model_path, &cls_model_path, &rec_model_path).await?;
+
+            (Some(det_result), Some(cls_result), Some(rec_result))
+        } else {
+            (None, None, None)
+        };
+
+        Ok(Self {
+            ocr_cores: HashMap::new(),
+            det_model: (det_model_path, det_model_data),
+            cls_model: (cls_model_path, cls_model_data),
+            rec_models: vec![RapidOcrRecModel::built_in(
+                DEFAULT_REC_MODEL_ID,
+                &format!("{:?}", model),
+                vec!["ch".to_string(), "en".to_string()],
+                rec_model_path,
+                rec_model_data,
+            )],
+            ocr_model_write_to_memory,
+        })
+    }
+
+    async fn read_model_data(
+        det_path: &Path,
+        cls_path: &Path,
+        rec_path: &Path,
+    ) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), String> {
+        let (det_result, cls_result, rec_result) = tokio::join!(
+            tokio::fs::read(det_path),
+            tokio::fs::read(cls_path),
+            tokio::fs::read(rec_path)
+        );
+
+        Ok((
+            det_result.map_err(|e| {
+                format!(
+                    "[RapidOcrEngine::read_model_data] Failed to read det model data: {}",
+                    e
+                )
+            })?,
+            cls_result.map_err(|e| {
+                format!(
+                    "[RapidOcrEngine::read_model_data] Failed to read cls model data: {}",
+                    e
+                )
+            })?,
+            rec_result.map_err(|e| {
+                format!(
+                    "[RapidOcrEngine::read_model_data] Failed to read rec model data: {}",
+                    e
+                )
+            })?,
+        ))
+    }
+
+    fn read_model_file(path: &Path) -> Result<Vec<u8>, String> {
+        std::fs::read(path).map_err(|e| {
+            format!(
+                "[RapidOcrEngine::read_model_file] Failed to read model data {}: {}",
+                path.display(),
+                e
+            )
+        })
+    }
+
+    fn build_session(builder: SessionBuilder) -> Result<SessionBuilder, ort::Error> {
+        let num_thread = num_cpus::get_physical();
+        Ok(builder
+            .with_inter_threads(num_thread)?
+            .with_intra_threads(num_thread)?
+            .with_optimization_level(ort::session::builder::GraphOptimizationLevel::Level3)?)
+    }
+
+    fn get_model_data<'a>(
+        model: &'a (PathBuf, Option<Vec<u8>>),
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<&'a [u8], String> {
+        match &model.1 {
+            Some(data) => Ok(data.as_slice()),
+            None => {
+                *buffer = Self::read_model_file(&model.0)?;
+                Ok(buffer.as_slice())
+            }
+        }
+    }
+
+    fn create_ocr_core(&self, rec_model: &RapidOcrRecModel) -> Result<OcrLite, String> {
+        let (mut det_buffer, mut cls_buffer, mut rec_buffer) = (Vec::new(), Vec::new(), Vec::new());
+        let det_model_data = Self::get_model_data(&self.det_model, &mut det_buffer)?;
+        let cls_model_data = Self::get_model_data(&self.cls_model, &mut cls_buffer)?;
+        let rec_model_data = match &rec_model.data {
+            Some(data) => data.as_slice(),
+            None => {
+                rec_buffer = Self::read_model_file(&rec_model.path)?;
+                rec_buffer.as_slice()
+            }
+        };
+
+        let mut ocr_core = OcrLite::new();
+        ocr_core
+            .init_models_from_memory_custom(
+                det_model_data,
+                cls_model_data,
+                rec_model_data,
+                Self::build_session,
+            )
+            .map_err(|e| {
+                format!(
+                    "[RapidOcrEngine::create_ocr_core] Failed to init models {}: {}",
+                    rec_model.info.id, e
+                )
+            })?;
+
+        Ok(ocr_core)
+    }
+}
+
+impl OcrEngine for RapidOcrEngine {
+    fn engine_type(&self) -> OcrEngineType {
+        OcrEngineType::RapidOcr
+    }
+
+    fn is_session_ready(&self) -> bool {
+        self.ocr_cores.contains_key(DEFAULT_REC_MODEL_ID)
+    }
+
+    /// 只创建内置模型的会话，自定义模型在首次使用时创建
+    fn init_session(&mut self) -> Result<(), String> {
+        self.ocr_cores.clear();
+
+        let ocr_core = self.create_ocr_core(&self.rec_models[0])?;
+        self.ocr_cores
+            .insert(DEFAULT_REC_MODEL_ID.to_string(), ocr_core);
+
+        Ok(())
+    }
+
+    fn release_session(&mut self) {
+        self.ocr_cores.clear();
+    }
+
+    fn get_models(&self) -> Vec<OcrModelInfo> {
+        self.rec_models
+            .iter()
+            .map(|rec_model| OcrModelInfo {
+                loaded: self.ocr_cores.contains_key(&rec_model.info.id),
+                ..rec_model.info.clone()
+            })
+            .collect()
+    }
+
+    fn register_model(&mut self, config: OcrRecModelConfig) -> Result<OcrModelInfo, String> {
+        if config.id == DEFAULT_REC_MODEL_ID {
+            return Err(format!(
+                "[RapidOcrEngine::register_model] Model id is reserved: {}",
+                config.id
+            ));
+        }
+
+        let rec_model = RapidOcrRecModel::load(&config, self.ocr_model_write_to_memory)?;
+        let info = rec_model.info.clone();
+
+        self.ocr_cores.remove(&config.id);
+        self.rec_models
+            .retain(|rec_model| rec_model.info.id != config.id);
+        self.rec_models.push(rec_model);
+
+        Ok(info)
+    }
+
+    fn detect(
+        &mut self,
+        image: &RgbImage,
+        detect_angle: bool,
+        options: &OcrDetectOptions,
+        model_id: Option<&str>,
+    ) -> Result<Vec<TextBlock>, String> {
+        let model_id = model_id.unwrap_or(DEFAULT_REC_MODEL_ID);
+
+        if !self.ocr_cores.contains_key(model_id) {
+            let rec_model = match self
+                .rec_models
+                .iter()
+                .find(|rec_model| rec_model.info.id == model_id)
+            {
+                Some(rec_model) => rec_model,
+                None => {
+                    return Err(format!(
+                        "[RapidOcrEngine::detect] Model not found: {}",
+                        model_id
+                    ));
+                }
+            };
+
+            let ocr_core = self.create_ocr_core(rec_model)?;
+            self.ocr_cores.insert(model_id.to_string(), ocr_core);
+        }
+
+        let ocr_core = self.ocr_cores.get_mut(model_id).unwrap();
+
+        let max_size = image.height().max(image.width());
+
+        let ocr_result = ocr_core.detect_angle_rollback(
+            image,
+            options.padding,
+            max_size,
+            options.box_score_thresh,
+            options.box_thresh,
+            options.unclip_ratio,
+            detect_angle,
+            false,
+            options.angle_threshold,
+        );
+
+        match ocr_result {
+            Ok(ocr_result) => Ok(ocr_result.text_blocks),
+            Err(e) => Err(format!(
+                "[RapidOcrEngine::detect] Failed to detect text: {}",
+                e
+            )),
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::path::{Path, PathBuf};
+
+use ort::session::Session;
+use ort::tensor::TensorElementType;
+use serde::{Deserialize, Serialize};
+
+use crate::ocr_engine::{OcrEngineType, OcrModelInfo};
+
+/// RapidOCR 模型中存放字典的元数据键
+const DICT_METADATA_KEY: &str = "character";
+
+/// 自定义识别模型配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrRecModelConfig {
+    pub id: String,
+    pub name: String,
+    /// 模型支持的语言，例如 ["japan"]、["korean"]、["cyrillic"]
+    pub languages: Vec<String>,
+    pub rec_model_path: PathBuf,
+    /// 字典文件，每行一个字符，模型内置字典时可为空
+    pub dict_path: Option<PathBuf>,
+}
+
+/// 已校验的识别模型
+pub struct RapidOcrRecModel {
+    pub info: OcrModelInfo,
+    pub path: PathBuf,
+    /// 模型数据，需要注入字典时始终保存在内存中
+    pub data: Option<Vec<u8>>,
+}
+
+impl RapidOcrRecModel {
+    /// 内置模型由插件提供，不做额外校验
+    pub fn built_in(
+        id: &str,
+        name: &str,
+        languages: Vec<String>,
+        path: PathBuf,
+        data: Option<Vec<u8>>,
+    ) -> Self {
+        Self {
+            info: OcrModelInfo {
+                engine: OcrEngineType::RapidOcr,
+                id: id.to_string(),
+                name: name.to_string(),
+                languages,
+                model_path: Some(path.clone()),
+                dict_path: None,
+                dict_size: None,
+                input_shape: None,
+                built_in: true,
+                loaded: false,
+            },
+            path,
+            data,
+        }
+    }
+
+    /// 读取并校验自定义识别模型
+    pub fn load(config: &OcrRecModelConfig, write_to_memory: bool) -> Result<Self, String> {
+        if config.id.is_empty() {
+            return Err("[RapidOcrRecModel::load] Model id is empty".to_string());
+        }
+
+        let mut data = read_file(&config.rec_model_path, "rec model")?;
+        let dict = match &config.dict_path {
+            Some(dict_path) => Some(read_dictionary(dict_path)?),
+            None => None,
+        };
+
+        let session = match Session::builder().and_then(|builder| builder.commit_from_memory(&data))
+        {
+            Ok(session) => session,
+            Err(e) => {
+                return Err(format!(
+                    "[RapidOcrRecModel::load] Invalid onnx model {}: {}",
+                    config.rec_model_path.display(),
+                    e
+                ));
+            }
+        };
+
+        // 识别模型的输入为 [N, 3, H, W] 的 float32 张量
+        let input = match session.inputs.first() {
+            Some(input) => input,
+            None => {
+                return Err(format!(
+                    "[RapidOcrRecModel::load] Model has no input: {}",
+                    config.rec_model_path.display()
+                ));
+            }
+        };
+        let input_shape = match (
+            input.input_type.tensor_type(),
+            input.input_type.tensor_shape(),
+        ) {
+            (Some(TensorElementType::Float32), Some(shape))
+                if shape.len() == 4 && (shape[1] == 3 || shape[1] == -1) =>
+            {
+                shape.to_vec()
+            }
+            _ => {
+                return Err(format!(
+                    "[RapidOcrRecModel::load] Unsupported model input {}: {:?}, expected float32 [N, 3, H, W]",
+                    input.name, input.input_type
+                ));
+            }
+        };
+
+        // 输出最后一维为字符类别数，等于字典字符数加上空白符和空格
+        let output_classes = session
+            .outputs
+            .first()
+            .and_then(|output| output.output_type.tensor_shape())
+            .and_then(|shape| shape.last().copied())
+            .filter(|classes| *classes > 0);
+
+        let embedded_dict = match session.metadata() {
+            Ok(metadata) => metadata.custom(DICT_METADATA_KEY).unwrap_or(None),
+            Err(_) => None,
+        };
+        drop(session);
+
+        let dict_size = match (embedded_dict, dict) {
+            (Some(embedded_dict), dict) => {
+                if dict.is_some() {
+                    log::warn!(
+                        "[RapidOcrRecModel::load] Model {} has embedded dictionary, dict file is ignored",
+                        config.id
+                    );
+                }
+
+                embedded_dict.split('\n').count()
+            }
+            (None, Some(dict)) => {
+                let dict_size = dict.len();
+                append_model_metadata(&mut data, DICT_METADATA_KEY, &dict.join("\n"));
+                dict_size
+            }
+            (None, None) => {
+                return Err(format!(
+                    "[RapidOcrRecModel::load] Model {} has no embedded dictionary, dict_path is required",
+                    config.id
+                ));
+            }
+        };
+
+        if let Some(output_classes) = output_classes {
+            if output_classes != dict_size as i64 + 2 {
+                return Err(format!(
+                    "[RapidOcrRecModel::load] Dictionary size {} does not match model output classes {}",
+                    dict_size, output_classes
+                ));
+            }
+        }
+
+        let keep_data = write_to_memory || config.dict_path.is_some();
+
+        Ok(Self {
+            info: OcrModelInfo {
+                engine: OcrEngineType::RapidOcr,
+                id: config.id.clone(),
+                name: config.name.clone(),
+                languages: config.languages.clone(),
+                model_path: Some(config.rec_model_path.clone()),
+                dict_path: config.dict_path.clone(),
+                dict_size: Some(dict_size),
+                input_shape: Some(input_shape),
+                built_in: false,
+                loaded: false,
+            },
+            path: config.rec_model_path.clone(),
+            data: if keep_data { Some(data) } else { None },
+        })
+    }
+}
+
+fn read_file(path: &Path, file_type: &str) -> Result<Vec<u8>, String> {
+    if !path.is_file() {
+        return Err(format!(
+            "[RapidOcrRecModel::read_file] {} file not found: {}",
+            file_type,
+            path.display()
+        ));
+    }
+
+    std::fs::read(path).map_err(|e| {
+        format!(
+            "[RapidOcrRecModel::read_file] Failed to read {} file {}: {}",
+            file_type,
+            path.display(),
+            e
+        )
+    })
+}
+
+/// 读取字典文件，每行一个字符
+fn read_dictionary(path: &Path) -> Result<Vec<String>, String> {
+    let content = match String::from_utf8(read_file(path, "dict")?) {
+        Ok(content) => content,
+        Err(_) => {
+            return Err(format!(
+                "[RapidOcrRecModel::read_dictionary] Dictionary is not valid UTF-8: {}",
+                path.display()
+            ));
+        }
+    };
+
+    let dict: Vec<String> = content
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect();
+
+    if dict.is_empty() {
+        return Err(format!(
+            "[RapidOcrRecModel::read_dictionary] Dictionary is empty: {}",
+            path.display()
+        ));
+    }
+
+    Ok(dict)
+}
+
+fn append_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn append_length_delimited(buffer: &mut Vec<u8>, field_number: u64, data: &[u8]) {
+    append_varint(buffer, (field_number << 3) | 2);
+    append_varint(buffer, data.len() as u64);
+    buffer.extend_from_slice(data);
+}
+
+/// 向 ONNX 模型追加一条 metadata_props
+///
+/// protobuf 解析时会合并重复字段，直接在 ModelProto 末尾追加第 14 号字段即可，
+/// 这样 paddle-ocr-rs 可以像读取内置字典一样读取外部字典
+fn append_model_metadata(model_data: &mut Vec<u8>, key: &str, value: &str) {
+    let mut entry = Vec::with_capacity(key.len() + value.len() + 8);
+    append_length_delimited(&mut entry, 1, key.as_bytes());
+    append_length_delimited(&mut entry, 2, value.as_bytes());
+
+    append_length_delimited(model_data, 14, &entry);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_model_metadata() {
+        let mut model_data = vec![0x08, 0x07];
+        append_model_metadata(&mut model_data, "character", "a\nb");
+
+        assert_eq!(
+            model_data,
+            vec![
+                0x08, 0x07, // ir_version
+                0x72, 0x10, // metadata_props
+                0x0a, 0x09, b'c', b'h', b'a', b'r', b'a', b'c', b't', b'e', b'r', // key
+                0x12, 0x03, b'a', b'\n', b'b', // value
+            ]
+        );
+    }
+
+    #[test]
+    fn test_append_varint() {
+        let mut buffer = Vec::new();
+        append_varint(&mut buffer, 300);
+
+        assert_eq!(buffer, vec![0xac, 0x02]);
+    }
+}
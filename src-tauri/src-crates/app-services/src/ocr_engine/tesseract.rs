@@ -0,0 +1,394 @@
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use image::RgbImage;
+use paddle_ocr_rs::ocr_result::{Point, TextBlock};
+use serde::{Deserialize, Serialize};
+
+use crate::ocr_engine::detect_options::OcrDetectOptions;
+use crate::ocr_engine::{OcrEngine, OcrEngineType, OcrModelInfo};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TesseractConfig {
+    /// tesseract 可执行文件路径，为空时从 PATH 中查找
+    pub tesseract_path: Option<PathBuf>,
+    /// traineddata 所在目录，为空时使用 tesseract 默认目录
+    pub tessdata_dir: Option<PathBuf>,
+    /// 识别语言，例如 ["eng", "jpn"]，对应 tesseract 的 `-l eng+jpn`
+    pub languages: Vec<String>,
+    /// 页面分割模式，对应 tesseract 的 `--psm`
+    pub page_segmentation_mode: Option<u32>,
+}
+
+pub struct TesseractOcrEngine {
+    config: TesseractConfig,
+    available_languages: Option<Vec<String>>,
+}
+
+/// TSV 中一行文字的识别结果
+struct TesseractLine {
+    key: (u32, u32, u32, u32),
+    words: Vec<String>,
+    confidences: Vec<f32>,
+    min_x: u32,
+    min_y: u32,
+    max_x: u32,
+    max_y: u32,
+}
+
+impl TesseractOcrEngine {
+    pub fn new(config: TesseractConfig) -> Self {
+        Self {
+            config,
+            available_languages: None,
+        }
+    }
+
+    pub fn get_available_languages(&self) -> Option<&Vec<String>> {
+        self.available_languages.as_ref()
+    }
+
+    fn get_command(&self) -> Command {
+        let mut command = Command::new(
+            self.config
+                .tesseract_path
+                .clone()
+                .unwrap_or_else(|| PathBuf::from("tesseract")),
+        );
+
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+
+            // CREATE_NO_WINDOW，避免弹出控制台窗口
+            command.creation_flags(0x08000000);
+        }
+
+        if let Some(tessdata_dir) = &self.config.tessdata_dir {
+            command.arg("--tessdata-dir").arg(tessdata_dir);
+        }
+
+        command
+    }
+
+    /// `model_id` 为 `eng+jpn` 形式的语言组合，为空时使用配置的语言
+    fn get_language_arg(&self, model_id: Option<&str>) -> Result<String, String> {
+        let model_id = match model_id {
+            Some(model_id) => model_id,
+            None if self.config.languages.is_empty() => return Ok("eng".to_string()),
+            None => return Ok(self.config.languages.join("+")),
+        };
+
+        if let Some(available_languages) = &self.available_languages {
+            if let Some(language) = model_id
+                .split('+')
+                .find(|language| !available_languages.iter().any(|item| item == language))
+            {
+                return Err(format!(
+                    "[TesseractOcrEngine::get_language_arg] Language data not found: {}",
+                    language
+                ));
+            }
+        }
+
+        Ok(model_id.to_string())
+    }
+
+    /// 解析 `tesseract --list-langs` 的输出
+    fn parse_language_list(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.ends_with(':'))
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    /// 将 tesseract TSV 输出按行合并为 TextBlock
+    fn parse_tsv(output: &str) -> Vec<TextBlock> {
+        let mut lines: Vec<TesseractLine> = Vec::new();
+
+        // 首行为表头
+        for row in output.lines().skip(1) {
+            let columns: Vec<&str> = row.splitn(12, '\t').collect();
+            if columns.len() < 12 {
+                continue;
+            }
+
+            // level 5 为单词
+            if columns[0] != "5" {
+                continue;
+            }
+
+            let text = columns[11].trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            let parse_u32 = |value: &str| value.parse::<u32>().unwrap_or(0);
+            let key = (
+                parse_u32(columns[1]),
+                parse_u32(columns[2]),
+                parse_u32(columns[3]),
+                parse_u32(columns[4]),
+            );
+            let left = parse_u32(columns[6]);
+            let top = parse_u32(columns[7]);
+            let right = left + parse_u32(columns[8]);
+            let bottom = top + parse_u32(columns[9]);
+            let confidence = columns[10].parse::<f32>().unwrap_or(0.0).max(0.0) / 100.0;
+
+            let line = match lines.iter_mut().find(|line| line.key == key) {
+                Some(line) => line,
+                None => {
+                    lines.push(TesseractLine {
+                        key,
+                        words: Vec::new(),
+                        confidences: Vec::new(),
+                        min_x: left,
+                        min_y: top,
+                        max_x: right,
+                        max_y: bottom,
+                    });
+                    lines.last_mut().unwrap()
+                }
+            };
+
+            line.words.push(text.to_string());
+            line.confidences.push(confidence);
+            line.min_x = line.min_x.min(left);
+            line.min_y = line.min_y.min(top);
+            line.max_x = line.max_x.max(right);
+            line.max_y = line.max_y.max(bottom);
+        }
+
+        lines
+            .into_iter()
+            .map(|line| {
+                let text_score =
+                    line.confidences.iter().sum::<f32>() / line.confidences.len() as f32;
+
+                TextBlock {
+                    box_points: vec![
+                        Point {
+                            x: line.min_x,
+                            y: line.min_y,
+                        },
+                        Point {
+                            x: line.max_x,
+                            y: line.min_y,
+                        },
+                        Point {
+                            x: line.max_x,
+                            y: line.max_y,
+                        },
+                        Point {
+                            x: line.min_x,
+                            y: line.max_y,
+                        },
+                    ],
+                    box_score: text_score,
+                    angle_index: 0,
+                    angle_score: 1.0,
+                    text: line.words.join(" "),
+                    text_score,
+                }
+            })
+            .collect()
+    }
+}
+
+impl OcrEngine for TesseractOcrEngine {
+    fn engine_type(&self) -> OcrEngineType {
+        OcrEngineType::Tesseract
+    }
+
+    fn is_session_ready(&self) -> bool {
+        self.available_languages.is_some()
+    }
+
+    /// tesseract 每次识别都会启动新进程，这里只校验可执行文件和语言数据
+    fn init_session(&mut self) -> Result<(), String> {
+        let output = match self.get_command().arg("--list-langs").output() {
+            Ok(output) => output,
+            Err(e) => {
+                return Err(format!(
+                    "[TesseractOcrEngine::init_session] Failed to run tesseract: {}",
+                    e
+                ));
+            }
+        };
+
+        if !output.status.success() {
+            return Err(format!(
+                "[TesseractOcrEngine::init_session] Failed to list languages: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        // 旧版本的 tesseract 会将语言列表输出到 stderr
+        let mut available_languages =
+            Self::parse_language_list(&String::from_utf8_lossy(&output.stdout));
+        if available_languages.is_empty() {
+            available_languages =
+                Self::parse_language_list(&String::from_utf8_lossy(&output.stderr));
+        }
+
+        if let Some(language) = self
+            .config
+            .languages
+            .iter()
+            .find(|language| !available_languages.contains(language))
+        {
+            return Err(format!(
+                "[TesseractOcrEngine::init_session] Language data not found: {}",
+                language
+            ));
+        }
+
+        self.available_languages = Some(available_languages);
+
+        Ok(())
+    }
+
+    fn release_session(&mut self) {
+        self.available_languages.take();
+    }
+
+    /// 每个已安装的语言数据视为一个模型
+    fn get_models(&self) -> Vec<OcrModelInfo> {
+        let available_languages = match &self.available_languages {
+            Some(available_languages) => available_languages,
+            None => return Vec::new(),
+        };
+
+        available_languages
+            .iter()
+            .map(|language| OcrModelInfo {
+                engine: OcrEngineType::Tesseract,
+                id: language.clone(),
+                name: language.clone(),
+                languages: vec![language.clone()],
+                model_path: None,
+                dict_path: None,
+                dict_size: None,
+                input_shape: None,
+                built_in: true,
+                loaded: self.config.languages.contains(language),
+            })
+            .collect()
+    }
+
+    /// tesseract 自行处理文字方向和文本框检测，忽略 detect_angle 和检测参数
+    fn detect(
+        &mut self,
+        image: &RgbImage,
+        _detect_angle: bool,
+        _options: &OcrDetectOptions,
+        model_id: Option<&str>,
+    ) -> Result<Vec<TextBlock>, String> {
+        let language_arg = self.get_language_arg(model_id)?;
+
+        let mut image_data = Vec::new();
+        if let Err(e) = image.write_to(&mut Cursor::new(&mut image_data), image::ImageFormat::Png) {
+            return Err(format!(
+                "[TesseractOcrEngine::detect] Failed to encode image: {}",
+                e
+            ));
+        }
+
+        let mut command = self.get_command();
+        command
+            .arg("stdin")
+            .arg("stdout")
+            .arg("-l")
+            .arg(language_arg);
+        if let Some(page_segmentation_mode) = self.config.page_segmentation_mode {
+            command.arg("--psm").arg(page_segmentation_mode.to_string());
+        }
+        command
+            .arg("tsv")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                return Err(format!(
+                    "[TesseractOcrEngine::detect] Failed to spawn tesseract: {}",
+                    e
+                ));
+            }
+        };
+
+        // 在单独的线程写入图片，避免输出缓冲区写满时阻塞
+        let mut stdin = child.stdin.take().unwrap();
+        let writer = std::thread::spawn(move || stdin.write_all(&image_data));
+
+        let output = match child.wait_with_output() {
+            Ok(output) => output,
+            Err(e) => {
+                return Err(format!(
+                    "[TesseractOcrEngine::detect] Failed to wait tesseract: {}",
+                    e
+                ));
+            }
+        };
+
+        if let Ok(Err(e)) = writer.join() {
+            return Err(format!(
+                "[TesseractOcrEngine::detect] Failed to write image to tesseract: {}",
+                e
+            ));
+        }
+
+        if !output.status.success() {
+            return Err(format!(
+                "[TesseractOcrEngine::detect] Failed to detect text: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(Self::parse_tsv(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tsv() {
+        let output = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+            1\t1\t0\t0\t0\t0\t0\t0\t200\t100\t-1\t\n\
+            4\t1\t1\t1\t1\t0\t10\t10\t120\t20\t-1\t\n\
+            5\t1\t1\t1\t1\t1\t10\t10\t50\t20\t90\tHello\n\
+            5\t1\t1\t1\t1\t2\t70\t12\t60\t18\t80\tworld\n\
+            5\t1\t1\t1\t2\t1\t10\t40\t30\t20\t70\tfoo\n\
+            5\t1\t1\t1\t2\t2\t50\t40\t30\t20\t-1\t \n";
+
+        let text_blocks = TesseractOcrEngine::parse_tsv(output);
+
+        assert_eq!(text_blocks.len(), 2);
+        assert_eq!(text_blocks[0].text, "Hello world");
+        assert!((text_blocks[0].text_score - 0.85).abs() < 0.001);
+        assert_eq!(text_blocks[0].box_points[0].x, 10);
+        assert_eq!(text_blocks[0].box_points[0].y, 10);
+        assert_eq!(text_blocks[0].box_points[2].x, 130);
+        assert_eq!(text_blocks[0].box_points[2].y, 30);
+        assert_eq!(text_blocks[1].text, "foo");
+    }
+
+    #[test]
+    fn test_parse_language_list() {
+        let output =
+            "List of available languages in \"/usr/share/tessdata/\" (3):\neng\njpn\nosd\n";
+
+        assert_eq!(
+            TesseractOcrEngine::parse_language_list(output),
+            vec!["eng", "jpn", "osd"]
+        );
+    }
+}
@@ -1,16 +1,18 @@
-use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use std::path::PathBuf;
 
-use num_cpus;
-use ort::session::builder::SessionBuilder;
-use paddle_ocr_rs::ocr_lite::OcrLite;
 use serde::{Deserialize, Serialize};
 
+use crate::ocr_engine::rapid_ocr::RapidOcrEngine;
+use crate::ocr_engine::rapid_ocr_model::{OcrRecModelConfig, RapidOcrRecModel};
+use crate::ocr_engine::tesseract::{TesseractConfig, TesseractOcrEngine};
+use crate::ocr_engine::{OcrEngine, OcrEngineType, OcrModelInfo};
+
 pub struct OcrService {
     hot_start: bool,
-    ocr_core: Option<OcrLite>,
-    det_model: Option<(PathBuf, Option<Vec<u8>>)>,
-    rec_model: Option<(PathBuf, Option<Vec<u8>>)>,
-    cls_model: Option<(PathBuf, Option<Vec<u8>>)>,
+    engines: HashMap<OcrEngineType, Box<dyn OcrEngine>>,
+    /// 自定义识别模型，重新初始化 RapidOCR 时重新注册
+    custom_rec_models: Vec<OcrRecModelConfig>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, PartialOrd, Serialize, Deserialize)]
@@ -23,93 +25,18 @@ impl OcrService {
     pub fn new() -> Self {
         Self {
             hot_start: false,
-            ocr_core: None,
-            det_model: None,
-            rec_model: None,
-            cls_model: None,
+            engines: HashMap::new(),
+            custom_rec_models: Vec::new(),
         }
     }
 
-    async fn read_model_data(
-        &self,
-        det_path: &Path,
-        cls_path: &Path,
-        rec_path: &Path,
-    ) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), String> {
-        let (det_result, cls_result, rec_result) = tokio::join!(
-            tokio::fs::read(det_path),
-            tokio::fs::read(cls_path),
-            tokio::fs::read(rec_path)
-        );
-
-        Ok((
-            det_result.map_err(|e| {
-                format!(
-                    "[OcrService::read_model_data] Failed to read det model data: {}",
-                    e
-                )
-            })?,
-            cls_result.map_err(|e| {
-                format!(
-                    "[OcrService::read_model_data] Failed to read cls model data: {}",
-                    e
-                )
-            })?,
-            rec_result.map_err(|e| {
-                format!(
-                    "[OcrService::read_model_data] Failed to read rec model data: {}",
-                    e
-                )
-            })?,
-        ))
-    }
-
-    fn build_session(builder: SessionBuilder) -> Result<SessionBuilder, ort::Error> {
-        let num_thread = num_cpus::get_physical();
-        Ok(builder
-            .with_inter_threads(num_thread)?
-            .with_intra_threads(num_thread)?
-            .with_optimization_level(ort::session::builder::GraphOptimizationLevel::Level3)?)
-    }
-
-    pub async fn init_session(&mut self) -> Result<(), String> {
-        let ((det_path, det_model_data), (cls_path, cls_model_data), (rec_path, rec_model_data)) = (
-            self.det_model
-                .as_ref()
-                .expect("[OcrService::init_ocr_core] Det model is not loaded"),
-            self.cls_model
-                .as_ref()
-                .expect("[OcrService::init_ocr_core] Cls model is not loaded"),
-            self.rec_model
-                .as_ref()
-                .expect("[OcrService::init_ocr_core] Rec model is not loaded"),
-        );
-
-        let mut ocr_core = OcrLite::new();
-
-        if let (Some(det_model_data), Some(cls_model_data), Some(rec_model_data)) =
-            (det_model_data, cls_model_data, rec_model_data)
-        {
-            ocr_core.init_models_from_memory_custom(
-                det_model_data,
-                cls_model_data,
-                rec_model_data,
-                Self::build_session,
-            )
-        } else {
-            let (det_model_data, cls_model_data, rec_model_data) =
-                self.read_model_data(det_path, cls_path, rec_path).await?;
-
-            ocr_core.init_models_from_memory_custom(
-                det_model_data.as_ref(),
-                cls_model_data.as_ref(),
-                rec_model_data.as_ref(),
-                Self::build_session,
-            )
+    /// 注册引擎，已存在的同类型引擎会被替换
+    fn register_engine(&mut self, mut engine: Box<dyn OcrEngine>) -> Result<(), String> {
+        if self.hot_start {
+            engine.init_session()?;
         }
-        .map_err(|e| format!("[OcrService::init_ocr_core] Failed to init models: {}", e))?;
 
-        self.ocr_core.replace(ocr_core);
+        self.engines.insert(engine.engine_type(), engine);
 
         Ok(())
     }
@@ -129,68 +56,111 @@ impl OcrService {
             ocr_model_write_to_memory
         );
 
-        // 加载模型到内存
-        let (det_model_path, cls_model_path, rec_model_path) = match model {
-            OcrModel::RapidOcrV4 => (
-                orc_plugin_path.join("ch_PP-OCRv4_det_infer.onnx"),
-                orc_plugin_path.join("ch_ppocr_mobile_v2.0_cls_infer.onnx"),
-                orc_plugin_path.join("ch_PP-OCRv4_rec_infer.onnx"),
-            ),
-            OcrModel::RapidOcrV5 => (
-                orc_plugin_path.join("ch_PP-OCRv4_det_infer.onnx"),
-                orc_plugin_path.join("ch_ppocr_mobile_v2.0_cls_infer.onnx"),
-                orc_plugin_path.join("ch_PP-OCRv5_rec_mobile_infer.onnx"),
-            ),
-        };
+        let mut engine =
+            RapidOcrEngine::new(&orc_plugin_path, model, ocr_model_write_to_memory).await?;
 
-        let (det_model_config, cls_model_config, rec_model_config) = if ocr_model_write_to_memory {
-            let (det_result, cls_result, rec_result) = self
-                .read_model_data(&det_model_path, &cls_model_path, &rec_model_path)
-                .await?;
-
-            (
-                Some((det_model_path, Some(det_result))),
-                Some((cls_model_path, Some(cls_result))),
-                Some((rec_model_path, Some(rec_result))),
-            )
-        } else {
-            (
-                Some((det_model_path, None)),
-                Some((cls_model_path, None)),
-                Some((rec_model_path, None)),
-            )
-        };
+        for config in &self.custom_rec_models {
+            if let Err(e) = engine.register_model(config.clone()) {
+                log::warn!(
+                    "[OcrService::init_models] Failed to register custom model {}: {}",
+                    config.id,
+                    e
+                );
+            }
+        }
 
-        self.det_model = det_model_config;
-        self.cls_model = cls_model_config;
-        self.rec_model = rec_model_config;
         self.hot_start = hot_start;
+        self.register_engine(Box::new(engine))
+    }
 
-        if self.hot_start {
-            self.init_session().await?;
-        } else {
-            self.ocr_core.take();
-        }
+    pub async fn init_tesseract(&mut self, config: TesseractConfig) -> Result<(), String> {
+        log::info!("[OcrService::init_tesseract] config: {:?}", config);
+
+        let mut engine = TesseractOcrEngine::new(config);
+        // 提前校验可执行文件和语言数据，便于前端提示
+        engine.init_session()?;
+
+        self.engines.insert(engine.engine_type(), Box::new(engine));
 
         Ok(())
     }
 
+    /// 注册自定义识别模型，加载时校验模型文件、字典和输入形状
+    pub async fn register_rec_model(
+        &mut self,
+        config: OcrRecModelConfig,
+    ) -> Result<OcrModelInfo, String> {
+        log::info!("[OcrService::register_rec_model] config: {:?}", config);
+
+        let info = match self.engines.get_mut(&OcrEngineType::RapidOcr) {
+            Some(engine) => engine.register_model(config.clone())?,
+            None => RapidOcrRecModel::load(&config, false)?.info,
+        };
+
+        self.custom_rec_models
+            .retain(|custom_model| custom_model.id != config.id);
+        self.custom_rec_models.push(config);
+
+        Ok(info)
+    }
+
+    pub fn get_models(&self) -> Vec<OcrModelInfo> {
+        let mut models: Vec<OcrModelInfo> = self
+            .engines
+            .values()
+            .flat_map(|engine| engine.get_models())
+            .collect();
+
+        // RapidOCR 未初始化时也返回已注册的自定义模型
+        if !self.engines.contains_key(&OcrEngineType::RapidOcr) {
+            models.extend(self.custom_rec_models.iter().map(|config| OcrModelInfo {
+                engine: OcrEngineType::RapidOcr,
+                id: config.id.clone(),
+                name: config.name.clone(),
+                languages: config.languages.clone(),
+                model_path: Some(config.rec_model_path.clone()),
+                dict_path: config.dict_path.clone(),
+                dict_size: None,
+                input_shape: None,
+                built_in: false,
+                loaded: false,
+            }));
+        }
+
+        models
+    }
+
     /// 释放 onnx session，并初始化新的 session
     pub async fn release_session(&mut self) -> Result<(), String> {
-        if self.hot_start {
-            self.init_session().await?;
-        } else {
-            self.ocr_core.take();
+        for engine in self.engines.values_mut() {
+            engine.release_session();
+
+            if self.hot_start {
+                engine.init_session()?;
+            }
         }
 
         Ok(())
     }
 
-    pub async fn get_session(&mut self) -> Result<&mut OcrLite, String> {
-        if self.ocr_core.is_none() {
-            self.init_session().await?;
+    pub async fn get_engine(
+        &mut self,
+        engine_type: OcrEngineType,
+    ) -> Result<&mut dyn OcrEngine, String> {
+        let engine = match self.engines.get_mut(&engine_type) {
+            Some(engine) => engine,
+            None => {
+                return Err(format!(
+                    "[OcrService::get_engine] Engine is not initialized: {:?}",
+                    engine_type
+                ));
+            }
+        };
+
+        if !engine.is_session_ready() {
+            engine.init_session()?;
         }
 
-        Ok(self.ocr_core.as_mut().unwrap())
+        Ok(engine.as_mut())
     }
 }
@@ -0,0 +1,452 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ab_glyph::{FontVec, PxScale};
+use device_query::{Keycode, MouseButton};
+use image::{Rgb, RgbImage};
+use imageproc::drawing::{draw_text_mut, text_size};
+use serde::{Deserialize, Serialize};
+
+use crate::device_event_handler_service::DeviceEventHandlerService;
+use crate::translation_render_service::load_font;
+
+/// 最多同时显示的点击动画数量
+const MAX_CLICK_EVENTS: usize = 16;
+
+/// device_query 中的鼠标右键，Linux 下沿用 X11 的编号，2 为中键
+#[cfg(target_os = "linux")]
+const RIGHT_MOUSE_BUTTON: MouseButton = 3;
+#[cfg(not(target_os = "linux"))]
+const RIGHT_MOUSE_BUTTON: MouseButton = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum KeystrokePosition {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+/// 录制时叠加的光标、点击和按键效果
+///
+/// 仅用于 NativeVideoRecordService，ffmpeg 录制不经过逐帧处理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordOverlayOptions {
+    /// 光标光圈
+    pub cursor_highlight: bool,
+    pub cursor_highlight_radius: u32,
+    pub cursor_highlight_color: [u8; 4],
+    /// 点击波纹
+    pub click_ripple: bool,
+    pub click_ripple_radius: u32,
+    pub click_ripple_duration_ms: u64,
+    pub left_click_color: [u8; 4],
+    pub right_click_color: [u8; 4],
+    /// 按键显示
+    pub keystroke: bool,
+    pub keystroke_position: KeystrokePosition,
+    pub keystroke_font_size: f32,
+    /// 按键显示距离画面边缘的距离
+    pub keystroke_margin: u32,
+    pub keystroke_duration_ms: u64,
+    pub keystroke_text_color: [u8; 3],
+    pub keystroke_background_color: [u8; 4],
+    /// 字体文件，为空时使用系统字体
+    pub keystroke_font_path: Option<PathBuf>,
+}
+
+impl Default for RecordOverlayOptions {
+    fn default() -> Self {
+        Self {
+            cursor_highlight: true,
+            cursor_highlight_radius: 24,
+            cursor_highlight_color: [255, 214, 0, 96],
+            click_ripple: true,
+            click_ripple_radius: 36,
+            click_ripple_duration_ms: 400,
+            left_click_color: [255, 64, 64, 200],
+            right_click_color: [64, 128, 255, 200],
+            keystroke: true,
+            keystroke_position: KeystrokePosition::BottomCenter,
+            keystroke_font_size: 28.0,
+            keystroke_margin: 48,
+            keystroke_duration_ms: 1500,
+            keystroke_text_color: [255, 255, 255],
+            keystroke_background_color: [0, 0, 0, 160],
+            keystroke_font_path: None,
+        }
+    }
+}
+
+struct ClickEvent {
+    x: i32,
+    y: i32,
+    button: MouseButton,
+    time: Instant,
+}
+
+struct KeystrokeEvent {
+    text: String,
+    time: Instant,
+}
+
+#[derive(Default)]
+struct RecordOverlayEvents {
+    clicks: VecDeque<ClickEvent>,
+    keystroke: Option<KeystrokeEvent>,
+    /// 当前按下的修饰键
+    modifiers: BTreeSet<&'static str>,
+}
+
+/// 修饰键的显示名称，非修饰键返回 None
+fn get_modifier_label(key_name: &str) -> Option<&'static str> {
+    match key_name {
+        "LControl" | "RControl" => Some("Ctrl"),
+        "LShift" | "RShift" => Some("Shift"),
+        "LAlt" | "RAlt" | "LOption" | "ROption" => Some("Alt"),
+        "LMeta" | "RMeta" | "Command" | "RCommand" => Some("Meta"),
+        _ => None,
+    }
+}
+
+/// 按键的显示名称，例如 Key1 显示为 1
+fn get_key_label(key_name: &str) -> String {
+    match key_name.strip_prefix("Key") {
+        Some(digit) if digit.len() == 1 && digit.chars().all(|c| c.is_ascii_digit()) => {
+            digit.to_string()
+        }
+        _ => key_name.to_string(),
+    }
+}
+
+/// 组合键文本，修饰键按 Ctrl、Shift、Alt、Meta 的顺序排列
+fn format_keystroke(modifiers: &BTreeSet<&'static str>, key_name: &str) -> String {
+    let mut parts: Vec<&str> = ["Ctrl", "Shift", "Alt", "Meta"]
+        .into_iter()
+        .filter(|modifier| modifiers.contains(modifier))
+        .collect();
+    let key_label = get_key_label(key_name);
+    parts.push(&key_label);
+
+    parts.join(" + ")
+}
+
+/// 录制期间监听输入事件，并将效果绘制到帧上
+///
+/// 释放时自动停止监听
+pub struct RecordOverlay {
+    _mouse_down_guard: Box<dyn std::any::Any + Send>,
+    _key_down_guard: Box<dyn std::any::Any + Send>,
+    _key_up_guard: Box<dyn std::any::Any + Send>,
+    // 需要在监听之后释放
+    _device_event_handler: DeviceEventHandlerService,
+    renderer: RecordOverlayRenderer,
+}
+
+impl RecordOverlay {
+    pub fn start(options: RecordOverlayOptions) -> Result<Self, String> {
+        let font = if options.keystroke {
+            Some(Arc::new(load_font(
+                options.keystroke_font_path.as_deref(),
+                None,
+            )?))
+        } else {
+            None
+        };
+
+        let events = Arc::new(Mutex::new(RecordOverlayEvents::default()));
+        let mut device_event_handler = DeviceEventHandlerService::new();
+
+        let mouse_down_events = events.clone();
+        let mouse_down_guard =
+            device_event_handler.on_mouse_down(move |button: &MouseButton| {
+                let (x, y) = match snow_shot_app_utils::get_device_mouse_position() {
+                    Ok(position) => position,
+                    Err(_) => return,
+                };
+
+                if let Ok(mut events) = mouse_down_events.lock() {
+                    if events.clicks.len() >= MAX_CLICK_EVENTS {
+                        events.clicks.pop_front();
+                    }
+                    events.clicks.push_back(ClickEvent {
+                        x,
+                        y,
+                        button: *button,
+                        time: Instant::now(),
+                    });
+                }
+            })?;
+
+        let key_down_events = events.clone();
+        let key_down_guard = device_event_handler.on_key_down(move |key: &Keycode| {
+            let key_name = key.to_string();
+
+            if let Ok(mut events) = key_down_events.lock() {
+                match get_modifier_label(&key_name) {
+                    Some(modifier) => {
+                        events.modifiers.insert(modifier);
+                    }
+                    None => {
+                        let text = format_keystroke(&events.modifiers, &key_name);
+                        events.keystroke = Some(KeystrokeEvent {
+                            text,
+                            time: Instant::now(),
+                        });
+                    }
+                }
+            }
+        })?;
+
+        let key_up_events = events.clone();
+        let key_up_guard = device_event_handler.on_key_up(move |key: &Keycode| {
+            if let Some(modifier) = get_modifier_label(&key.to_string()) {
+                if let Ok(mut events) = key_up_events.lock() {
+                    events.modifiers.remove(modifier);
+                }
+            }
+        })?;
+
+        Ok(Self {
+            _mouse_down_guard: Box::new(mouse_down_guard),
+            _key_down_guard: Box::new(key_down_guard),
+            _key_up_guard: Box::new(key_up_guard),
+            _device_event_handler: device_event_handler,
+            renderer: RecordOverlayRenderer {
+                options,
+                events,
+                font,
+            },
+        })
+    }
+
+    pub fn get_renderer(&self) -> RecordOverlayRenderer {
+        self.renderer.clone()
+    }
+}
+
+/// 将光标、点击和按键效果绘制到录制的帧上，可以在采集线程中使用
+#[derive(Clone)]
+pub struct RecordOverlayRenderer {
+    options: RecordOverlayOptions,
+    events: Arc<Mutex<RecordOverlayEvents>>,
+    font: Option<Arc<FontVec>>,
+}
+
+impl RecordOverlayRenderer {
+    /// 绘制效果
+    ///
+    /// # 参数
+    /// * `frame` - 录制的帧
+    /// * `origin_x` - 帧左上角的屏幕坐标
+    /// * `origin_y` - 帧左上角的屏幕坐标
+    pub fn render(&self, frame: &mut RgbImage, origin_x: i32, origin_y: i32) {
+        let now = Instant::now();
+
+        if self.options.cursor_highlight {
+            if let Ok((x, y)) = snow_shot_app_utils::get_device_mouse_position() {
+                fill_circle(
+                    frame,
+                    x - origin_x,
+                    y - origin_y,
+                    self.options.cursor_highlight_radius as f32,
+                    self.options.cursor_highlight_color,
+                );
+            }
+        }
+
+        let (clicks, keystroke) = match self.events.lock() {
+            Ok(mut events) => {
+                let click_duration = Duration::from_millis(self.options.click_ripple_duration_ms);
+                events
+                    .clicks
+                    .retain(|click| now.duration_since(click.time) < click_duration);
+
+                let clicks: Vec<(i32, i32, MouseButton, f32)> = events
+                    .clicks
+                    .iter()
+                    .map(|click| {
+                        (
+                            click.x,
+                            click.y,
+                            click.button,
+                            now.duration_since(click.time).as_secs_f32()
+                                / click_duration.as_secs_f32(),
+                        )
+                    })
+                    .collect();
+
+                let keystroke = events
+                    .keystroke
+                    .as_ref()
+                    .filter(|keystroke| {
+                        now.duration_since(keystroke.time)
+                            < Duration::from_millis(self.options.keystroke_duration_ms)
+                    })
+                    .map(|keystroke| keystroke.text.clone());
+
+                (clicks, keystroke)
+            }
+            Err(_) => return,
+        };
+
+        if self.options.click_ripple {
+            for (x, y, button, progress) in clicks {
+                let mut color = if button == RIGHT_MOUSE_BUTTON {
+                    self.options.right_click_color
+                } else {
+                    self.options.left_click_color
+                };
+                // 波纹逐渐扩大并淡出
+                color[3] = (color[3] as f32 * (1.0 - progress)) as u8;
+                let radius = self.options.click_ripple_radius as f32 * (0.3 + 0.7 * progress);
+
+                draw_ring(frame, x - origin_x, y - origin_y, radius, 3.0, color);
+            }
+        }
+
+        if let (Some(text), Some(font)) = (keystroke, &self.font) {
+            self.draw_keystroke(frame, font, &text);
+        }
+    }
+
+    fn draw_keystroke(&self, frame: &mut RgbImage, font: &FontVec, text: &str) {
+        let scale = PxScale::from(self.options.keystroke_font_size);
+        let (text_width, text_height) = text_size(scale, font, text);
+
+        let padding = (self.options.keystroke_font_size * 0.4) as i32;
+        let box_width = text_width as i32 + padding * 2;
+        let box_height = text_height as i32 + padding * 2;
+        let margin = self.options.keystroke_margin as i32;
+        let (frame_width, frame_height) = (frame.width() as i32, frame.height() as i32);
+
+        let box_x = match self.options.keystroke_position {
+            KeystrokePosition::TopLeft | KeystrokePosition::BottomLeft => margin,
+            KeystrokePosition::TopCenter | KeystrokePosition::BottomCenter => {
+                (frame_width - box_width) / 2
+            }
+            KeystrokePosition::TopRight | KeystrokePosition::BottomRight => {
+                frame_width - box_width - margin
+            }
+        };
+        let box_y = match self.options.keystroke_position {
+            KeystrokePosition::TopLeft
+            | KeystrokePosition::TopCenter
+            | KeystrokePosition::TopRight => margin,
+            KeystrokePosition::BottomLeft
+            | KeystrokePosition::BottomCenter
+            | KeystrokePosition::BottomRight => frame_height - box_height - margin,
+        };
+
+        fill_rect(
+            frame,
+            box_x,
+            box_y,
+            box_width,
+            box_height,
+            self.options.keystroke_background_color,
+        );
+        draw_text_mut(
+            frame,
+            Rgb(self.options.keystroke_text_color),
+            box_x + padding,
+            box_y + padding,
+            scale,
+            font,
+            text,
+        );
+    }
+}
+
+fn blend_pixel(frame: &mut RgbImage, x: i32, y: i32, color: [u8; 4], coverage: f32) {
+    if x < 0 || y < 0 || x >= frame.width() as i32 || y >= frame.height() as i32 {
+        return;
+    }
+
+    let alpha = color[3] as f32 / 255.0 * coverage.clamp(0.0, 1.0);
+    let pixel = frame.get_pixel_mut(x as u32, y as u32);
+    for channel in 0..3 {
+        pixel.0[channel] =
+            (pixel.0[channel] as f32 * (1.0 - alpha) + color[channel] as f32 * alpha) as u8;
+    }
+}
+
+/// 半透明的实心圆，边缘抗锯齿
+fn fill_circle(frame: &mut RgbImage, center_x: i32, center_y: i32, radius: f32, color: [u8; 4]) {
+    let range = radius.ceil() as i32 + 1;
+    for y in -range..=range {
+        for x in -range..=range {
+            let distance = ((x * x + y * y) as f32).sqrt();
+            blend_pixel(
+                frame,
+                center_x + x,
+                center_y + y,
+                color,
+                radius + 0.5 - distance,
+            );
+        }
+    }
+}
+
+/// 半透明的圆环，边缘抗锯齿
+fn draw_ring(
+    frame: &mut RgbImage,
+    center_x: i32,
+    center_y: i32,
+    radius: f32,
+    thickness: f32,
+    color: [u8; 4],
+) {
+    let range = (radius + thickness).ceil() as i32 + 1;
+    for y in -range..=range {
+        for x in -range..=range {
+            let distance = ((x * x + y * y) as f32).sqrt();
+            blend_pixel(
+                frame,
+                center_x + x,
+                center_y + y,
+                color,
+                thickness / 2.0 + 0.5 - (distance - radius).abs(),
+            );
+        }
+    }
+}
+
+fn fill_rect(frame: &mut RgbImage, x: i32, y: i32, width: i32, height: i32, color: [u8; 4]) {
+    for pixel_y in y..y + height {
+        for pixel_x in x..x + width {
+            blend_pixel(frame, pixel_x, pixel_y, color, 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_keystroke() {
+        let mut modifiers = BTreeSet::new();
+        assert_eq!(format_keystroke(&modifiers, "Key1"), "1");
+
+        modifiers.insert("Shift");
+        modifiers.insert("Ctrl");
+        assert_eq!(format_keystroke(&modifiers, "S"), "Ctrl + Shift + S");
+        assert_eq!(get_modifier_label("RShift"), Some("Shift"));
+        assert_eq!(get_modifier_label("Enter"), None);
+    }
+
+    #[test]
+    fn test_fill_circle() {
+        let mut frame = RgbImage::new(20, 20);
+        fill_circle(&mut frame, 10, 10, 4.0, [255, 255, 255, 255]);
+
+        assert_eq!(frame.get_pixel(10, 10).0, [255, 255, 255]);
+        assert_eq!(frame.get_pixel(0, 0).0, [0, 0, 0]);
+    }
+}
@@ -0,0 +1,402 @@
+use std::path::{Path, PathBuf};
+
+use ab_glyph::{FontVec, PxScale};
+use image::{DynamicImage, Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_rect_mut, draw_text_mut, text_size};
+use imageproc::rect::Rect;
+use paddle_ocr_rs::ocr_result::TextBlock;
+use serde::{Deserialize, Serialize};
+use snow_shot_app_shared::ElementRect;
+
+/// 最小字号，低于该字号时不再缩小
+const MIN_FONT_SIZE: f32 = 8.0;
+
+/// 常见的系统字体，需要支持中日韩文字
+#[cfg(target_os = "windows")]
+const SYSTEM_FONT_PATHS: [&str; 3] = [
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\simsun.ttc",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+#[cfg(target_os = "macos")]
+const SYSTEM_FONT_PATHS: [&str; 3] = [
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/System/Library/Fonts/Helvetica.ttc",
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const SYSTEM_FONT_PATHS: [&str; 3] = [
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranslationRenderOptions {
+    /// 字体文件，为空时使用系统字体
+    pub font_path: Option<PathBuf>,
+    /// ttc 字体集合中的字体索引
+    pub font_index: Option<u32>,
+    /// 文字颜色，为空时根据背景色自动选择黑色或白色
+    pub text_color: Option<[u8; 4]>,
+    /// 背景颜色，为空时使用文本框边缘的平均颜色
+    pub background_color: Option<[u8; 4]>,
+}
+
+/// 翻译后的文本框，坐标基于原图
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslatedTextBlock {
+    pub text: String,
+    pub translated_text: String,
+    pub rect: ElementRect,
+}
+
+impl TranslatedTextBlock {
+    /// `scale_factor` 为 text_block 相对原图的缩放比例
+    pub fn new(text_block: &TextBlock, translated_text: String, scale_factor: f32) -> Self {
+        let scale_factor = if scale_factor > 0.0 {
+            scale_factor
+        } else {
+            1.0
+        };
+        let min_x = text_block.box_points.iter().map(|point| point.x).min();
+        let min_y = text_block.box_points.iter().map(|point| point.y).min();
+        let max_x = text_block.box_points.iter().map(|point| point.x).max();
+        let max_y = text_block.box_points.iter().map(|point| point.y).max();
+
+        Self {
+            text: text_block.text.clone(),
+            translated_text,
+            rect: ElementRect {
+                min_x: (min_x.unwrap_or(0) as f32 / scale_factor).floor() as i32,
+                min_y: (min_y.unwrap_or(0) as f32 / scale_factor).floor() as i32,
+                max_x: (max_x.unwrap_or(0) as f32 / scale_factor).ceil() as i32,
+                max_y: (max_y.unwrap_or(0) as f32 / scale_factor).ceil() as i32,
+            },
+        }
+    }
+}
+
+/// 将译文绘制到原文字的位置
+pub struct TranslationRenderService {
+    font: FontVec,
+    options: TranslationRenderOptions,
+}
+
+impl TranslationRenderService {
+    pub fn new(options: TranslationRenderOptions) -> Result<Self, String> {
+        let font = load_font(options.font_path.as_deref(), options.font_index)?;
+
+        Ok(Self { font, options })
+    }
+
+    pub fn render(
+        &self,
+        image: &DynamicImage,
+        translated_text_blocks: &[TranslatedTextBlock],
+    ) -> RgbaImage {
+        let mut image = image.to_rgba8();
+        let (width, height) = image.dimensions();
+
+        for text_block in translated_text_blocks {
+            let min_x = text_block.rect.min_x.clamp(0, width as i32);
+            let min_y = text_block.rect.min_y.clamp(0, height as i32);
+            let max_x = text_block.rect.max_x.clamp(0, width as i32);
+            let max_y = text_block.rect.max_y.clamp(0, height as i32);
+            if min_x >= max_x || min_y >= max_y {
+                continue;
+            }
+
+            let box_width = (max_x - min_x) as u32;
+            let box_height = (max_y - min_y) as u32;
+
+            let background_color = match self.options.background_color {
+                Some(color) => Rgba(color),
+                None => get_border_color(
+                    &image,
+                    min_x as u32,
+                    min_y as u32,
+                    max_x as u32,
+                    max_y as u32,
+                ),
+            };
+            let text_color = match self.options.text_color {
+                Some(color) => Rgba(color),
+                None => get_contrast_color(&background_color),
+            };
+
+            draw_filled_rect_mut(
+                &mut image,
+                Rect::at(min_x, min_y).of_size(box_width, box_height),
+                background_color,
+            );
+
+            let text = text_block.translated_text.trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            // 从文本框高度开始缩小字号，直到文字宽度不超过文本框
+            let mut font_size = box_height as f32 * 0.85;
+            let (mut text_width, mut text_height) =
+                text_size(PxScale::from(font_size), &self.font, text);
+            while text_width > box_width && font_size > MIN_FONT_SIZE {
+                font_size = (font_size * box_width as f32 / text_width as f32)
+                    .min(font_size - 1.0)
+                    .max(MIN_FONT_SIZE);
+                (text_width, text_height) = text_size(PxScale::from(font_size), &self.font, text);
+            }
+
+            draw_text_mut(
+                &mut image,
+                text_color,
+                min_x,
+                min_y + (box_height as i32 - text_height as i32).max(0) / 2,
+                PxScale::from(font_size),
+                &self.font,
+                text,
+            );
+        }
+
+        image
+    }
+}
+
+/// 加载字体，font_path 为空时使用系统字体
+pub(crate) fn load_font(
+    font_path: Option<&Path>,
+    font_index: Option<u32>,
+) -> Result<FontVec, String> {
+    let font_path = match font_path {
+        Some(font_path) => font_path.to_path_buf(),
+        None => match SYSTEM_FONT_PATHS
+            .iter()
+            .map(Path::new)
+            .find(|font_path| font_path.is_file())
+        {
+            Some(font_path) => font_path.to_path_buf(),
+            None => {
+                return Err("[load_font] No system font found, font_path is required".to_string());
+            }
+        },
+    };
+
+    let font_data = std::fs::read(&font_path).map_err(|e| {
+        format!(
+            "[load_font] Failed to read font {}: {}",
+            font_path.display(),
+            e
+        )
+    })?;
+
+    FontVec::try_from_vec_and_index(font_data, font_index.unwrap_or(0))
+        .map_err(|e| format!("[load_font] Invalid font {}: {}", font_path.display(), e))
+}
+
+/// 文本框边缘的平均颜色，通常为文字的背景色
+fn get_border_color(image: &RgbaImage, min_x: u32, min_y: u32, max_x: u32, max_y: u32) -> Rgba<u8> {
+    let mut sum = [0u64; 4];
+    let mut count = 0u64;
+    let mut add_pixel = |x: u32, y: u32| {
+        let pixel = image.get_pixel(x, y);
+        for (index, value) in pixel.0.iter().enumerate() {
+            sum[index] += *value as u64;
+        }
+        count += 1;
+    };
+
+    for x in min_x..max_x {
+        add_pixel(x, min_y);
+        add_pixel(x, max_y - 1);
+    }
+    for y in min_y..max_y {
+        add_pixel(min_x, y);
+        add_pixel(max_x - 1, y);
+    }
+
+    if count == 0 {
+        return Rgba([255, 255, 255, 255]);
+    }
+
+    Rgba(sum.map(|value| (value / count) as u8))
+}
+
+fn get_contrast_color(background_color: &Rgba<u8>) -> Rgba<u8> {
+    let [r, g, b, _] = background_color.0;
+    let luminance = r as u32 * 299 + g as u32 * 587 + b as u32 * 114;
+
+    if luminance > 128 * 1000 {
+        Rgba([0, 0, 0, 255])
+    } else {
+        Rgba([255, 255, 255, 255])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use paddle_ocr_rs::ocr_result::Point;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    fn get_font() -> Option<FontVec> {
+        match load_font(None, None) {
+            Ok(font) => Some(font),
+            Err(e) => {
+                eprintln!("Skip render test: {}", e);
+                None
+            }
+        }
+    }
+
+    fn translated_text_block(translated_text: &str, rect: ElementRect) -> TranslatedTextBlock {
+        TranslatedTextBlock {
+            text: String::new(),
+            translated_text: translated_text.to_string(),
+            rect,
+        }
+    }
+
+    /// 白底图片，文本框内为黑色的原文字
+    fn get_image() -> DynamicImage {
+        let mut image = RgbaImage::from_pixel(200, 60, WHITE);
+        draw_filled_rect_mut(
+            &mut image,
+            Rect::at(20, 20).of_size(100, 20),
+            Rgba([0, 0, 0, 255]),
+        );
+        draw_filled_rect_mut(
+            &mut image,
+            Rect::at(160, 25).of_size(10, 10),
+            Rgba([255, 0, 0, 255]),
+        );
+        DynamicImage::ImageRgba8(image)
+    }
+
+    fn count_pixels(
+        image: &RgbaImage,
+        rect: &ElementRect,
+        inside: bool,
+        predicate: impl Fn(&Rgba<u8>) -> bool,
+    ) -> usize {
+        image
+            .enumerate_pixels()
+            .filter(|(x, y, _)| {
+                let x = *x as i32;
+                let y = *y as i32;
+                (x >= rect.min_x && x < rect.max_x && y >= rect.min_y && y < rect.max_y) == inside
+            })
+            .filter(|(_, _, pixel)| predicate(pixel))
+            .count()
+    }
+
+    #[test]
+    fn test_translated_text_block() {
+        let text_block = TextBlock {
+            box_points: vec![
+                Point { x: 20, y: 10 },
+                Point { x: 81, y: 10 },
+                Point { x: 81, y: 31 },
+                Point { x: 20, y: 31 },
+            ],
+            box_score: 1.0,
+            angle_index: 0,
+            angle_score: 1.0,
+            text: "hello".to_string(),
+            text_score: 1.0,
+        };
+
+        let translated_text_block = TranslatedTextBlock::new(&text_block, "你好".to_string(), 2.0);
+        assert_eq!(translated_text_block.rect.min_x, 10);
+        assert_eq!(translated_text_block.rect.min_y, 5);
+        assert_eq!(translated_text_block.rect.max_x, 41);
+        assert_eq!(translated_text_block.rect.max_y, 16);
+    }
+
+    #[test]
+    fn test_render() {
+        let font = match get_font() {
+            Some(font) => font,
+            None => return,
+        };
+        let service = TranslationRenderService {
+            font,
+            options: TranslationRenderOptions::default(),
+        };
+
+        let text_rect = ElementRect {
+            min_x: 10,
+            min_y: 10,
+            max_x: 130,
+            max_y: 50,
+        };
+        let empty_rect = ElementRect {
+            min_x: 150,
+            min_y: 15,
+            max_x: 180,
+            max_y: 45,
+        };
+        let image = service.render(
+            &get_image(),
+            &[
+                // 译文过长时缩小字号，不超出文本框
+                translated_text_block("hello translated world", text_rect),
+                translated_text_block("", empty_rect),
+                // 超出图片的文本框被忽略
+                translated_text_block(
+                    "ignored",
+                    ElementRect {
+                        min_x: 300,
+                        min_y: 0,
+                        max_x: 400,
+                        max_y: 20,
+                    },
+                ),
+            ],
+        );
+
+        // 背景为文本框边缘的白色，原文字被覆盖，译文为对比色黑色
+        let is_dark = |pixel: &Rgba<u8>| pixel.0[0] < 128 && pixel.0[1] < 128 && pixel.0[2] < 128;
+        assert_eq!(image.get_pixel(10, 10), &WHITE);
+        assert!(count_pixels(&image, &text_rect, true, is_dark) > 0);
+        assert!(count_pixels(&image, &text_rect, true, is_dark) < 100 * 20);
+        assert_eq!(count_pixels(&image, &text_rect, false, is_dark), 0);
+
+        // 译文为空时只填充背景
+        assert_eq!(image.get_pixel(165, 30), &WHITE);
+    }
+
+    #[test]
+    fn test_render_with_colors() {
+        let font = match get_font() {
+            Some(font) => font,
+            None => return,
+        };
+        let background_color = [0, 0, 255, 255];
+        let text_color = [255, 255, 0, 255];
+        let service = TranslationRenderService {
+            font,
+            options: TranslationRenderOptions {
+                text_color: Some(text_color),
+                background_color: Some(background_color),
+                ..TranslationRenderOptions::default()
+            },
+        };
+
+        let text_rect = ElementRect {
+            min_x: 10,
+            min_y: 10,
+            max_x: 130,
+            max_y: 50,
+        };
+        let image = service.render(&get_image(), &[translated_text_block("hello", text_rect)]);
+
+        assert_eq!(image.get_pixel(10, 10), &Rgba(background_color));
+        assert_eq!(image.get_pixel(0, 0), &WHITE);
+        // 抗锯齿边缘为混合色，只统计接近文字颜色的像素
+        let is_text_color =
+            |pixel: &Rgba<u8>| pixel.0[0] > 200 && pixel.0[1] > 200 && pixel.0[2] < 60;
+        assert!(count_pixels(&image, &text_rect, true, is_text_color) > 0);
+    }
+}
@@ -0,0 +1,461 @@
+use std::path::{Path, PathBuf};
+
+use ffmpeg_sidecar::command::FfmpegCommand;
+use ffmpeg_sidecar::event::{FfmpegEvent, LogLevel};
+use serde::{Deserialize, Serialize};
+use snow_shot_app_shared::ElementRect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VideoExportFormat {
+    Mp4,
+    Webm,
+    Gif,
+    Apng,
+    Webp,
+}
+
+impl VideoExportFormat {
+    pub fn extension(&self) -> &str {
+        match self {
+            VideoExportFormat::Mp4 => "mp4",
+            VideoExportFormat::Webm => "webm",
+            VideoExportFormat::Gif => "gif",
+            VideoExportFormat::Apng => "png",
+            VideoExportFormat::Webp => "webp",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoExportOptions {
+    pub input_file: PathBuf,
+    pub output_file: PathBuf,
+    pub format: VideoExportFormat,
+    /// 裁剪的开始时间，秒
+    pub start_time: Option<f64>,
+    /// 裁剪的结束时间，秒
+    pub end_time: Option<f64>,
+    /// 裁剪区域，基于原视频的像素坐标
+    pub crop: Option<ElementRect>,
+    pub frame_rate: Option<u32>,
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+}
+
+/// 导出进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoExportProgress {
+    pub output_file: PathBuf,
+    /// 0 - 1，无法获取视频时长时为 None
+    pub progress: Option<f64>,
+    /// 已处理的时长，秒
+    pub time: f64,
+    pub speed: f32,
+    pub finished: bool,
+    pub error: Option<String>,
+}
+
+pub struct VideoExportService;
+
+impl VideoExportService {
+    /// 解析 ffmpeg 输出的时间，例如: 00:01:02.50
+    fn parse_time(time: &str) -> Option<f64> {
+        let mut seconds = 0.0;
+        for part in time.trim().split(':') {
+            seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+        }
+
+        Some(seconds)
+    }
+
+    fn get_video_filters(options: &VideoExportOptions) -> Vec<String> {
+        let mut filters = Vec::new();
+
+        if let Some(crop) = options.crop {
+            filters.push(format!(
+                "crop={}:{}:{}:{}",
+                (crop.max_x - crop.min_x) & !1,
+                (crop.max_y - crop.min_y) & !1,
+                crop.min_x,
+                crop.min_y
+            ));
+        }
+
+        if let Some(frame_rate) = options.frame_rate {
+            filters.push(format!("fps={}", frame_rate));
+        }
+
+        if options.max_width.is_some() || options.max_height.is_some() {
+            // 只缩小不放大，并保持宽高为偶数
+            filters.push(format!(
+                "scale='min(iw,{})':'min(ih,{})':force_original_aspect_ratio=decrease:force_divisible_by=2:flags=lanczos",
+                options.max_width.unwrap_or(u32::MAX / 2),
+                options.max_height.unwrap_or(u32::MAX / 2)
+            ));
+        }
+
+        filters
+    }
+
+    /// 检查裁剪时间和区域，避免生成无效的 ffmpeg 参数
+    fn validate_options(options: &VideoExportOptions) -> Result<(), String> {
+        let start_time = options.start_time.unwrap_or(0.0);
+        if !start_time.is_finite() || start_time < 0.0 {
+            return Err(format!(
+                "[VideoExportService::export] Invalid start time: {}",
+                start_time
+            ));
+        }
+
+        match options.end_time {
+            Some(end_time) if !end_time.is_finite() || end_time <= start_time => {
+                return Err(format!(
+                    "[VideoExportService::export] End time {} must be greater than start time {}",
+                    end_time, start_time
+                ));
+            }
+            _ => {}
+        }
+
+        if let Some(crop) = options.crop {
+            // 裁剪的宽高会向下取偶数，至少需要 2 像素
+            if crop.min_x < 0
+                || crop.min_y < 0
+                || crop.max_x - crop.min_x < 2
+                || crop.max_y - crop.min_y < 2
+            {
+                return Err(format!(
+                    "[VideoExportService::export] Invalid crop area: {:?}",
+                    crop
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 生成 ffmpeg 参数
+    fn get_args(options: &VideoExportOptions) -> Vec<String> {
+        let mut args: Vec<String> = Vec::new();
+
+        // 放在输入之前可以快速定位
+        if let Some(start_time) = options.start_time {
+            args.extend(["-ss".to_string(), start_time.to_string()]);
+        }
+        args.extend([
+            "-i".to_string(),
+            options.input_file.to_string_lossy().to_string(),
+        ]);
+        if let Some(end_time) = options.end_time {
+            let duration = end_time - options.start_time.unwrap_or(0.0);
+            args.extend(["-t".to_string(), duration.to_string()]);
+        }
+
+        let filters = Self::get_video_filters(options).join(",");
+        let with_filters = |filter: &str| {
+            if filters.is_empty() {
+                filter.to_string()
+            } else if filter.is_empty() {
+                filters.clone()
+            } else {
+                format!("{},{}", filters, filter)
+            }
+        };
+
+        match options.format {
+            VideoExportFormat::Mp4 | VideoExportFormat::Webm => {
+                let video_filter = with_filters("");
+                if !video_filter.is_empty() {
+                    args.extend(["-vf".to_string(), video_filter]);
+                }
+
+                let codec_args: &[&str] = if options.format == VideoExportFormat::Mp4 {
+                    &[
+                        "-c:v",
+                        "libx264",
+                        "-preset",
+                        "medium",
+                        "-crf",
+                        "23",
+                        "-pix_fmt",
+                        "yuv420p",
+                        "-c:a",
+                        "aac",
+                        "-b:a",
+                        "128k",
+                        "-movflags",
+                        "+faststart",
+                    ]
+                } else {
+                    &[
+                        "-c:v",
+                        "libvpx-vp9",
+                        "-b:v",
+                        "0",
+                        "-crf",
+                        "32",
+                        "-row-mt",
+                        "1",
+                        "-c:a",
+                        "libopus",
+                        "-b:a",
+                        "96k",
+                    ]
+                };
+                args.extend(codec_args.iter().map(|arg| arg.to_string()));
+            }
+            VideoExportFormat::Gif => {
+                args.extend([
+                    "-filter_complex".to_string(),
+                    format!(
+                        "[0:v]{}split[s0][s1];[s0]palettegen=max_colors=192:stats_mode=diff[p];[s1][p]paletteuse=dither=floyd_steinberg:diff_mode=rectangle",
+                        with_filters("")
+                            .split_terminator(',')
+                            .map(|filter| format!("{},", filter))
+                            .collect::<String>()
+                    ),
+                    "-loop".to_string(),
+                    "0".to_string(),
+                    "-an".to_string(),
+                ]);
+            }
+            VideoExportFormat::Apng => {
+                let video_filter = with_filters("");
+                if !video_filter.is_empty() {
+                    args.extend(["-vf".to_string(), video_filter]);
+                }
+                args.extend(
+                    [
+                        "-f",
+                        "apng",
+                        "-plays",
+                        "0",
+                        "-compression_level",
+                        "6",
+                        "-pred",
+                        "mixed",
+                        "-an",
+                    ]
+                    .iter()
+                    .map(|arg| arg.to_string()),
+                );
+            }
+            VideoExportFormat::Webp => {
+                let video_filter = with_filters("");
+                if !video_filter.is_empty() {
+                    args.extend(["-vf".to_string(), video_filter]);
+                }
+                args.extend(
+                    [
+                        "-f",
+                        "webp",
+                        "-lossless",
+                        "0",
+                        "-quality",
+                        "85",
+                        "-compression_level",
+                        "4",
+                        "-method",
+                        "4",
+                        "-loop",
+                        "0",
+                        "-an",
+                    ]
+                    .iter()
+                    .map(|arg| arg.to_string()),
+                );
+            }
+        }
+
+        args.extend([
+            "-y".to_string(),
+            options.output_file.to_string_lossy().to_string(),
+        ]);
+
+        args
+    }
+
+    /// 导出视频，阻塞直到 ffmpeg 退出
+    ///
+    /// # 参数
+    /// * `ffmpeg_path` - ffmpeg 可执行文件
+    /// * `options` - 导出参数
+    /// * `on_progress` - 进度回调
+    pub fn export(
+        ffmpeg_path: &Path,
+        options: &VideoExportOptions,
+        mut on_progress: impl FnMut(VideoExportProgress),
+    ) -> Result<PathBuf, String> {
+        Self::validate_options(options)?;
+
+        if let Some(parent_dir) = options.output_file.parent() {
+            std::fs::create_dir_all(parent_dir).map_err(|e| {
+                format!(
+                    "[VideoExportService::export] Failed to create output directory: {}",
+                    e
+                )
+            })?;
+        }
+
+        let mut command = FfmpegCommand::new_with_path(ffmpeg_path);
+        command.args(Self::get_args(options));
+
+        log::info!("[VideoExportService::export] command: {:?}", command);
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("[VideoExportService::export] Failed to spawn ffmpeg: {}", e))?;
+        let events = child
+            .iter()
+            .map_err(|e| format!("[VideoExportService::export] Failed to iter ffmpeg: {}", e))?;
+
+        let start_time = options.start_time.unwrap_or(0.0);
+        let mut duration = options
+            .end_time
+            .map(|end_time| end_time - start_time)
+            .filter(|duration| *duration > 0.0);
+        let mut last_error = None;
+
+        for event in events {
+            match event {
+                FfmpegEvent::ParsedDuration(input_duration) => {
+                    if duration.is_none() && input_duration.duration > 0.0 {
+                        duration = Some((input_duration.duration - start_time).max(0.0));
+                    }
+                }
+                FfmpegEvent::Progress(progress) => {
+                    let time = Self::parse_time(&progress.time).unwrap_or(0.0);
+                    on_progress(VideoExportProgress {
+                        output_file: options.output_file.clone(),
+                        progress: duration.map(|duration| (time / duration).clamp(0.0, 1.0)),
+                        time,
+                        speed: progress.speed,
+                        finished: false,
+                        error: None,
+                    });
+                }
+                FfmpegEvent::Log(LogLevel::Error | LogLevel::Fatal, message)
+                | FfmpegEvent::Error(message) => {
+                    log::error!("[VideoExportService::export] {}", message);
+                    last_error = Some(message);
+                }
+                _ => {}
+            }
+        }
+
+        let status = child
+            .wait()
+            .map_err(|e| format!("[VideoExportService::export] Failed to wait ffmpeg: {}", e))?;
+
+        let result = if status.success() && options.output_file.exists() {
+            Ok(options.output_file.clone())
+        } else {
+            Err(format!(
+                "[VideoExportService::export] Failed to export video: {}",
+                last_error.unwrap_or_else(|| status.to_string())
+            ))
+        };
+
+        on_progress(VideoExportProgress {
+            output_file: options.output_file.clone(),
+            progress: Some(1.0),
+            time: duration.unwrap_or(0.0),
+            speed: 0.0,
+            finished: true,
+            error: result.as_ref().err().cloned(),
+        });
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_options(format: VideoExportFormat) -> VideoExportOptions {
+        VideoExportOptions {
+            input_file: PathBuf::from("input.mp4"),
+            output_file: PathBuf::from("output"),
+            format,
+            start_time: Some(1.5),
+            end_time: Some(4.0),
+            crop: Some(ElementRect {
+                min_x: 10,
+                min_y: 20,
+                max_x: 111,
+                max_y: 220,
+            }),
+            frame_rate: Some(15),
+            max_width: None,
+            max_height: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(VideoExportService::parse_time("00:01:02.50"), Some(62.5));
+        assert_eq!(VideoExportService::parse_time("N/A"), None);
+    }
+
+    #[test]
+    fn test_get_args() {
+        let args = VideoExportService::get_args(&get_options(VideoExportFormat::Mp4));
+        assert_eq!(
+            &args[..8],
+            &[
+                "-ss",
+                "1.5",
+                "-i",
+                "input.mp4",
+                "-t",
+                "2.5",
+                "-vf",
+                "crop=100:200:10:20,fps=15"
+            ]
+        );
+
+        let args = VideoExportService::get_args(&get_options(VideoExportFormat::Gif));
+        assert_eq!(args[6], "-filter_complex");
+        assert!(args[7].starts_with("[0:v]crop=100:200:10:20,fps=15,split[s0][s1];"));
+        assert_eq!(args.last().unwrap(), "output");
+    }
+
+    #[test]
+    fn test_validate_options() {
+        assert!(VideoExportService::validate_options(&get_options(VideoExportFormat::Mp4)).is_ok());
+
+        let mut options = get_options(VideoExportFormat::Mp4);
+        options.end_time = Some(1.5);
+        assert!(VideoExportService::validate_options(&options).is_err());
+
+        let mut options = get_options(VideoExportFormat::Mp4);
+        options.start_time = Some(-1.0);
+        assert!(VideoExportService::validate_options(&options).is_err());
+
+        let mut options = get_options(VideoExportFormat::Mp4);
+        options.crop = Some(ElementRect {
+            min_x: -10,
+            min_y: 0,
+            max_x: 100,
+            max_y: 100,
+        });
+        assert!(VideoExportService::validate_options(&options).is_err());
+
+        let mut options = get_options(VideoExportFormat::Mp4);
+        options.crop = Some(ElementRect {
+            min_x: 10,
+            min_y: 10,
+            max_x: 11,
+            max_y: 100,
+        });
+        assert!(VideoExportService::validate_options(&options).is_err());
+
+        // 未跑到 ffmpeg 就返回错误
+        let mut options = get_options(VideoExportFormat::Mp4);
+        options.end_time = Some(0.5);
+        assert!(
+            VideoExportService::export(Path::new("ffmpeg-not-exists"), &options, |_| {}).is_err()
+        );
+    }
+}
@@ -6,6 +6,7 @@ use snow_shot_app_utils::monitor_info::MonitorList;
 use std::{
     io::Result,
     path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Copy)]
@@ -30,6 +31,139 @@ impl VideoFormat {
     }
 }
 
+/// Windows 下可以采集系统音频的 dshow 设备
+#[cfg(target_os = "windows")]
+const WINDOWS_LOOPBACK_DEVICE_KEYWORDS: [&str; 4] = [
+    "virtual-audio-capturer",
+    "Stereo Mix",
+    "立体声混音",
+    "CABLE Output",
+];
+
+/// macOS 下可以采集系统音频的虚拟声卡
+#[cfg(target_os = "macos")]
+const MACOS_LOOPBACK_DEVICE_KEYWORDS: [&str; 2] = ["BlackHole", "Soundflower"];
+
+/// Linux 下的屏幕采集方式
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
+pub enum LinuxCaptureBackend {
+    /// X11 使用 x11grab，display 为空时读取 DISPLAY 环境变量
+    X11Grab { display: Option<String> },
+    /// Wayland 通过 xdg-desktop-portal 获取 PipeWire 视频流
+    ///
+    /// 依赖 pipewiregrab 滤镜，目前没有正式发布的 ffmpeg 版本包含该滤镜，需要自行编译
+    PipeWire,
+}
+
+impl LinuxCaptureBackend {
+    /// 根据当前会话类型选择采集方式
+    pub fn detect() -> Self {
+        let is_wayland = std::env::var("XDG_SESSION_TYPE")
+            .map(|session_type| session_type.eq_ignore_ascii_case("wayland"))
+            .unwrap_or(false)
+            || std::env::var_os("WAYLAND_DISPLAY").is_some();
+
+        if is_wayland {
+            LinuxCaptureBackend::PipeWire
+        } else {
+            LinuxCaptureBackend::X11Grab { display: None }
+        }
+    }
+}
+
+/// 音频混音参数
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct VideoRecordAudioOptions {
+    pub microphone_volume: f32,
+    pub system_audio_volume: f32,
+    /// 系统音频的采集设备，为空时自动选择
+    pub system_audio_device_name: Option<String>,
+    /// 麦克风和系统音频分别保存为独立的音轨，否则混合为一条音轨
+    pub separate_tracks: bool,
+}
+
+impl Default for VideoRecordAudioOptions {
+    fn default() -> Self {
+        Self {
+            microphone_volume: 1.0,
+            system_audio_volume: 1.0,
+            system_audio_device_name: None,
+            separate_tracks: false,
+        }
+    }
+}
+
+/// 录制限制，超出后自动停止录制
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct VideoRecordLimits {
+    /// 最长录制时长，秒，不包含暂停的时间
+    pub max_duration_secs: Option<u64>,
+    /// 所有片段的最大总大小，MB
+    pub max_file_size_mb: Option<u64>,
+    /// 输出目录所在磁盘的最小剩余空间，MB
+    pub min_free_disk_space_mb: Option<u64>,
+}
+
+/// 回放缓冲参数，开启后持续录制并只保留最近的片段
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct VideoRecordReplayOptions {
+    /// 单个片段的时长，秒，保存回放时以片段为单位截取
+    pub segment_duration_secs: u32,
+    /// 保留的回放时长，秒
+    pub buffer_duration_secs: u32,
+}
+
+impl Default for VideoRecordReplayOptions {
+    fn default() -> Self {
+        Self {
+            segment_duration_secs: 5,
+            buffer_duration_secs: 60,
+        }
+    }
+}
+
+impl VideoRecordReplayOptions {
+    /// 片段列表中保留的片段数量
+    fn get_list_size(&self) -> u32 {
+        let segment_duration_secs = self.segment_duration_secs.max(1);
+        self.buffer_duration_secs.div_ceil(segment_duration_secs) + 1
+    }
+
+    /// 片段文件名循环使用的数量，需要比列表多出正在写入的片段
+    fn get_wrap_size(&self) -> u32 {
+        self.get_list_size() + 2
+    }
+}
+
+/// 自动停止录制的原因
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum VideoRecordStopReason {
+    MaxDuration,
+    MaxFileSize,
+    LowDiskSpace,
+}
+
+/// 自动停止录制后发送给前端的事件
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
+pub struct VideoRecordAutoStopEvent {
+    pub reason: VideoRecordStopReason,
+    pub output_file: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 录制的音频输入
+#[derive(Debug, Clone, PartialEq)]
+struct AudioInput {
+    /// ffmpeg 中的音频流，例如: 1:a
+    stream: String,
+    volume: f32,
+    /// 麦克风需要降噪
+    denoise: bool,
+}
+
 // 录制参数结构体，用于在暂停后恢复录制时重用参数
 #[derive(Clone, Debug)]
 struct RecordingParams {
@@ -41,14 +175,16 @@ struct RecordingParams {
     format: VideoFormat,
     frame_rate: u32,
     enable_microphone: bool,
-    #[allow(unused)]
     enable_system_audio: bool,
     microphone_device_name: String,
+    audio_options: VideoRecordAudioOptions,
     hwaccel: bool,
     encoder: String,
     encoder_preset: String,
     video_max_width: i32,
     video_max_height: i32,
+    limits: VideoRecordLimits,
+    replay_options: Option<VideoRecordReplayOptions>,
 }
 
 pub struct VideoRecordService {
@@ -59,7 +195,12 @@ pub struct VideoRecordService {
     segment_counter: u32,                      // 片段计数器
     recording_params: Option<RecordingParams>, // 录制参数，用于恢复录制
     record_video_size: Option<(i32, i32)>,     // 录制视频大小
+    record_id: u64,                            // 每次开始录制时递增，用于区分不同的录制
+    recorded_duration: Duration,               // 已结束片段的总时长
+    segment_started_at: Option<Instant>,       // 当前片段的开始时间
     ffmpeg_path: Option<PathBuf>,
+    #[cfg(target_os = "linux")]
+    linux_capture_backend: Option<LinuxCaptureBackend>, // 为空时自动检测
 }
 
 #[cfg(target_os = "macos")]
@@ -86,11 +227,27 @@ impl VideoRecordService {
             segment_counter: 0,
             recording_params: None,
             record_video_size: None,
+            record_id: 0,
+            recorded_duration: Duration::ZERO,
+            segment_started_at: None,
             ffmpeg_path: None,
+            #[cfg(target_os = "linux")]
+            linux_capture_backend: None,
         }
     }
 
-    pub fn init(&mut self, ffmpeg_plugin_dir: &Path) {
+    pub fn init(
+        &mut self,
+        ffmpeg_plugin_dir: &Path,
+        linux_capture_backend: Option<LinuxCaptureBackend>,
+    ) {
+        #[cfg(target_os = "linux")]
+        {
+            self.linux_capture_backend = linux_capture_backend;
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = linux_capture_backend;
+
         if self.ffmpeg_path.is_none() {
             #[cfg(target_os = "windows")]
             {
@@ -125,9 +282,40 @@ impl VideoRecordService {
 
                 self.ffmpeg_path = Some(ffmpeg_path);
             }
+
+            #[cfg(target_os = "linux")]
+            {
+                use std::fs;
+                use std::os::unix::fs::PermissionsExt;
+
+                let ffmpeg_path = ffmpeg_plugin_dir.join("ffmpeg");
+
+                // 优先使用插件目录中的 ffmpeg，不存在时使用系统安装的 ffmpeg
+                if ffmpeg_path.is_file() {
+                    if let Ok(metadata) = fs::metadata(&ffmpeg_path) {
+                        let mut permissions = metadata.permissions();
+                        permissions.set_mode(0o755);
+
+                        if let Err(e) = fs::set_permissions(&ffmpeg_path, permissions) {
+                            log::warn!(
+                                "[VideoRecordService] Failed to set executable permissions for ffmpeg: {}",
+                                e
+                            );
+                        }
+                    }
+
+                    self.ffmpeg_path = Some(ffmpeg_path);
+                } else {
+                    self.ffmpeg_path = Some(PathBuf::from("ffmpeg"));
+                }
+            }
         }
     }
 
+    pub fn get_ffmpeg_path(&self) -> Option<&PathBuf> {
+        self.ffmpeg_path.as_ref()
+    }
+
     pub fn get_ffmpeg_command(&self) -> FfmpegCommand {
         FfmpegCommand::new_with_path(
             self.ffmpeg_path
@@ -186,6 +374,9 @@ impl VideoRecordService {
         encoder_preset: String,
         video_max_width: i32,
         video_max_height: i32,
+        audio_options: VideoRecordAudioOptions,
+        limits: VideoRecordLimits,
+        replay_options: Option<VideoRecordReplayOptions>,
     ) -> Result<()> {
         if self.state == VideoRecordState::Recording {
             return Err(std::io::Error::new(
@@ -194,6 +385,13 @@ impl VideoRecordService {
             ));
         }
 
+        if replay_options.is_some() && format != VideoFormat::Mp4 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Replay buffer only supports MP4",
+            ));
+        }
+
         // 保存录制参数
         self.recording_params = Some(RecordingParams {
             min_x,
@@ -206,17 +404,23 @@ impl VideoRecordService {
             enable_microphone,
             enable_system_audio,
             microphone_device_name,
+            audio_options,
             hwaccel,
             encoder,
             encoder_preset,
             video_max_width,
             video_max_height,
+            limits,
+            replay_options,
         });
 
         // 重置片段相关状态
         self.segments.clear();
         self.segment_counter = 0;
         self.record_video_size = None;
+        self.record_id += 1;
+        self.recorded_duration = Duration::ZERO;
+        self.segment_started_at = None;
 
         // 开始第一个片段的录制
         self.start_segment()
@@ -292,25 +496,50 @@ impl VideoRecordService {
                 .arg(params.frame_rate.to_string());
         }
 
-        let mut audio_input = String::new();
+        #[cfg(target_os = "linux")]
+        let linux_capture_backend = self
+            .linux_capture_backend
+            .clone()
+            .unwrap_or_else(LinuxCaptureBackend::detect);
+
+        #[cfg(target_os = "linux")]
+        if linux_capture_backend == LinuxCaptureBackend::PipeWire
+            && !self.has_ffmpeg_filter("pipewiregrab")
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Wayland recording requires an ffmpeg build with the pipewiregrab filter, which is not included in any released ffmpeg version",
+            ));
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            for arg in Self::get_linux_video_input_args(
+                &linux_capture_backend,
+                params.frame_rate,
+                params.min_x,
+                params.min_y,
+                width,
+                height,
+            ) {
+                command.arg(arg);
+            }
+        }
+
+        // 第 0 个输入为视频，Windows 和 Linux 下每个音频设备为一个单独的输入
+        let mut audio_inputs: Vec<AudioInput> = Vec::new();
 
         // 根据平台添加音频输入
         #[cfg(target_os = "windows")]
         {
-            // 添加系统音频输入
-            if params.enable_system_audio {
-                // command
-                //     .arg("-f")
-                //     .arg("dshow")
-                //     .arg("-i")
-                //     .arg("audio=virtual-audio-capturer");
-                // audio_inputs.push("1:a".to_string());
-            }
+            let device_names = if params.enable_microphone || params.enable_system_audio {
+                self.get_microphone_device_names()
+            } else {
+                Vec::new()
+            };
 
             // 添加麦克风音频输入
             if params.enable_microphone {
-                let device_names = self.get_microphone_device_names();
-
                 if device_names.len() > 0 {
                     command.arg("-f").arg("dshow").arg("-i").arg(format!(
                         "audio={}",
@@ -320,11 +549,92 @@ impl VideoRecordService {
                             device_names[0].clone()
                         }
                     ));
-                    audio_input = format!("{}:a", 1);
+                    audio_inputs.push(AudioInput {
+                        stream: format!("{}:a", audio_inputs.len() + 1),
+                        volume: params.audio_options.microphone_volume,
+                        denoise: true,
+                    });
+                }
+            }
+
+            // 添加系统音频输入，dshow 需要借助立体声混音或虚拟声卡采集扬声器的声音
+            if params.enable_system_audio {
+                let system_audio_device_name = match &params.audio_options.system_audio_device_name
+                {
+                    Some(device_name) if device_names.contains(device_name) => {
+                        Some(device_name.clone())
+                    }
+                    _ => device_names
+                        .iter()
+                        .find(|device_name| {
+                            WINDOWS_LOOPBACK_DEVICE_KEYWORDS
+                                .iter()
+                                .any(|keyword| device_name.contains(keyword))
+                        })
+                        .cloned(),
+                };
+
+                match system_audio_device_name {
+                    Some(device_name) => {
+                        command
+                            .arg("-f")
+                            .arg("dshow")
+                            .arg("-i")
+                            .arg(format!("audio={}", device_name));
+                        audio_inputs.push(AudioInput {
+                            stream: format!("{}:a", audio_inputs.len() + 1),
+                            volume: params.audio_options.system_audio_volume,
+                            denoise: false,
+                        });
+                    }
+                    None => {
+                        log::warn!(
+                            "[video_record_service::start_segment] No loopback device found for system audio"
+                        );
+                    }
                 }
             }
         }
 
+        // Linux 使用 PulseAudio 采集音频，PipeWire 提供兼容接口
+        #[cfg(target_os = "linux")]
+        {
+            if params.enable_microphone {
+                let device_names = self.get_microphone_device_names();
+
+                if device_names.len() > 0 {
+                    command.arg("-f").arg("pulse").arg("-i").arg(
+                        if device_names.contains(&params.microphone_device_name) {
+                            params.microphone_device_name.clone()
+                        } else {
+                            "default".to_string()
+                        },
+                    );
+                    audio_inputs.push(AudioInput {
+                        stream: format!("{}:a", audio_inputs.len() + 1),
+                        volume: params.audio_options.microphone_volume,
+                        denoise: true,
+                    });
+                }
+            }
+
+            // 系统音频来自输出设备的 monitor 源
+            if params.enable_system_audio {
+                command.arg("-f").arg("pulse").arg("-i").arg(
+                    params
+                        .audio_options
+                        .system_audio_device_name
+                        .clone()
+                        .unwrap_or_else(|| "@DEFAULT_MONITOR@".to_string()),
+                );
+                audio_inputs.push(AudioInput {
+                    stream: format!("{}:a", audio_inputs.len() + 1),
+                    volume: params.audio_options.system_audio_volume,
+                    denoise: false,
+                });
+            }
+        }
+
         #[cfg(target_os = "macos")]
         let monitor_list = MonitorList::all(true);
         #[cfg(target_os = "macos")]
@@ -376,19 +686,64 @@ impl VideoRecordService {
                 command
                     .arg("-i")
                     .arg(format!("{}:{}", target_monitor_index, audio_device.index));
-                audio_input = format!("{}:a", audio_device.index);
+                // 麦克风与屏幕在同一个输入中
+                audio_inputs.push(AudioInput {
+                    stream: "0:a".to_string(),
+                    volume: params.audio_options.microphone_volume,
+                    denoise: true,
+                });
             } else {
                 command.arg("-i").arg(format!("{}", target_monitor_index));
             }
+
+            // macOS 需要安装 BlackHole 等虚拟声卡才能采集系统音频
+            if params.enable_system_audio {
+                let system_audio_device = device_info_list.iter().find(|d| {
+                    d.device_type == DeviceType::Audio
+                        && match &params.audio_options.system_audio_device_name {
+                            Some(device_name) => &Self::format_device_name(d) == device_name,
+                            None => MACOS_LOOPBACK_DEVICE_KEYWORDS
+                                .iter()
+                                .any(|keyword| d.name.contains(keyword)),
+                        }
+                });
+
+                match system_audio_device {
+                    Some(system_audio_device) => {
+                        command
+                            .arg("-f")
+                            .arg("avfoundation")
+                            .arg("-i")
+                            .arg(format!(":{}", system_audio_device.index));
+                        audio_inputs.push(AudioInput {
+                            stream: "1:a".to_string(),
+                            volume: params.audio_options.system_audio_volume,
+                            denoise: false,
+                        });
+                    }
+                    None => {
+                        log::warn!(
+                            "[video_record_service::start_segment] No loopback device found for system audio"
+                        );
+                    }
+                }
+            }
         }
 
-        // 生成当前片段的文件名
-        let segment_filename = format!(
-            "{}_segment_{:03}.{}",
-            params.output_file,
-            self.segment_counter,
-            params.format.extension()
-        );
+        // 生成当前片段的文件名，回放模式下由 ffmpeg 按序号循环写入
+        let segment_filename = match params.replay_options {
+            Some(_) => format!(
+                "{}_replay_%03d.{}",
+                params.output_file,
+                params.format.extension()
+            ),
+            None => format!(
+                "{}_segment_{:03}.{}",
+                params.output_file,
+                self.segment_counter,
+                params.format.extension()
+            ),
+        };
 
         // 确保输出文件的目录存在
         if let Some(parent_dir) = std::path::Path::new(&segment_filename).parent() {
@@ -498,21 +853,75 @@ impl VideoRecordService {
                     command.arg("-crf").arg("23").arg("-pix_fmt").arg("uyvy422"); // 添加像素格式，确保兼容性
                 }
 
+                #[cfg(target_os = "linux")]
+                {
+                    let mut filters = Vec::new();
+
+                    // 门户提供的是用户选择的整个显示器的画面，需要裁剪出录制区域
+                    // Wayland 下无法获取显示器的全局坐标，录制区域按所选显示器的左上角计算
+                    if linux_capture_backend == LinuxCaptureBackend::PipeWire {
+                        filters.push(format!(
+                            "crop={}:{}:{}:{}",
+                            width,
+                            height,
+                            params.min_x.max(0),
+                            params.min_y.max(0)
+                        ));
+                    }
+                    if !video_filter.is_empty() {
+                        filters.push(video_filter.clone());
+                    }
+                    if !filters.is_empty() {
+                        command.arg("-vf").arg(filters.join(","));
+                    }
+
+                    command.arg("-crf").arg("23").arg("-pix_fmt").arg("yuv420p");
+                }
+
                 // 音频编码设置
-                if !audio_input.is_empty() {
+                if !audio_inputs.is_empty() {
                     command.arg("-c:a").arg("aac").arg("-b:a").arg("128k");
 
-                    // 音频处理，添加降噪
-                    let filter_complex =
-                        format!("[{}]anlmdn=s=10:p=0.001:r=0.005[aout]", audio_input);
+                    // 音频处理，调整音量、麦克风降噪并混音
+                    let (filter_complex, audio_outputs) = Self::get_audio_filter_complex(
+                        &audio_inputs,
+                        params.audio_options.separate_tracks,
+                    );
                     command.arg("-filter_complex").arg(filter_complex);
-                    command.arg("-map").arg("0:v").arg("-map").arg("[aout]");
+                    command.arg("-map").arg("0:v");
+                    for audio_output in audio_outputs {
+                        command.arg("-map").arg(audio_output);
+                    }
                 } else {
                     // 没有音频输入时，只映射视频
                     command.arg("-map").arg("0:v");
                 }
 
-                command.arg("-movflags").arg("+faststart"); // 优化MP4文件结构
+                if let Some(replay_options) = &params.replay_options {
+                    // 在片段边界强制关键帧，保证每个片段都能独立播放
+                    let segment_duration_secs = replay_options.segment_duration_secs.max(1);
+                    command
+                        .arg("-force_key_frames")
+                        .arg(format!("expr:gte(t,n_forced*{})", segment_duration_secs))
+                        .arg("-f")
+                        .arg("segment")
+                        .arg("-segment_format")
+                        .arg("mp4")
+                        .arg("-segment_time")
+                        .arg(segment_duration_secs.to_string())
+                        .arg("-reset_timestamps")
+                        .arg("1")
+                        .arg("-segment_wrap")
+                        .arg(replay_options.get_wrap_size().to_string())
+                        .arg("-segment_list")
+                        .arg(Self::get_replay_list_filename(&params.output_file))
+                        .arg("-segment_list_type")
+                        .arg("csv")
+                        .arg("-segment_list_size")
+                        .arg(replay_options.get_list_size().to_string());
+                } else {
+                    command.arg("-movflags").arg("+faststart"); // 优化MP4文件结构
+                }
             }
             VideoFormat::Gif => {
                 // GIF格式不包含音频
@@ -542,6 +951,7 @@ impl VideoRecordService {
                                 self.state = VideoRecordState::Recording;
                                 self.segments.push(segment_filename);
                                 self.segment_counter += 1;
+                                self.segment_started_at = Some(Instant::now());
                                 return Ok(());
                             }
                             _ => {}
@@ -565,6 +975,141 @@ impl VideoRecordService {
         }
     }
 
+    /// 生成 Linux 下的视频输入参数
+    #[cfg(target_os = "linux")]
+    fn get_linux_video_input_args(
+        linux_capture_backend: &LinuxCaptureBackend,
+        frame_rate: u32,
+        min_x: i32,
+        min_y: i32,
+        width: i32,
+        height: i32,
+    ) -> Vec<String> {
+        match linux_capture_backend {
+            LinuxCaptureBackend::X11Grab { display } => {
+                let display = display
+                    .clone()
+                    .or_else(|| std::env::var("DISPLAY").ok())
+                    .unwrap_or_else(|| ":0".to_string());
+
+                vec![
+                    "-f".to_string(),
+                    "x11grab".to_string(),
+                    "-framerate".to_string(),
+                    frame_rate.to_string(),
+                    "-video_size".to_string(),
+                    format!("{}x{}", width, height),
+                    "-i".to_string(),
+                    format!("{}+{},{}", display, min_x, min_y),
+                ]
+            }
+            LinuxCaptureBackend::PipeWire => vec![
+                "-f".to_string(),
+                "lavfi".to_string(),
+                "-i".to_string(),
+                format!("pipewiregrab=framerate={}", frame_rate),
+            ],
+        }
+    }
+
+    /// 解析 `ffmpeg -filters` 的输出，判断是否包含指定滤镜
+    ///
+    /// 每行格式为: ` T.. name  A->A  description`
+    #[cfg(target_os = "linux")]
+    fn parse_ffmpeg_filters(output: &str, filter_name: &str) -> bool {
+        output
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .any(|name| name == filter_name)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn has_ffmpeg_filter(&self, filter_name: &str) -> bool {
+        let ffmpeg_path = match self.ffmpeg_path.as_ref() {
+            Some(ffmpeg_path) => ffmpeg_path,
+            None => return false,
+        };
+
+        match std::process::Command::new(ffmpeg_path)
+            .args(["-hide_banner", "-filters"])
+            .output()
+        {
+            Ok(output) => {
+                Self::parse_ffmpeg_filters(&String::from_utf8_lossy(&output.stdout), filter_name)
+            }
+            Err(e) => {
+                log::warn!(
+                    "[VideoRecordService::has_ffmpeg_filter] Failed to run ffmpeg: {}",
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// 生成音频的 filter_complex，返回滤镜和需要映射的音频输出
+    fn get_audio_filter_complex(
+        audio_inputs: &[AudioInput],
+        separate_tracks: bool,
+    ) -> (String, Vec<String>) {
+        let mut filters = Vec::new();
+        let mut outputs = Vec::new();
+
+        for (index, audio_input) in audio_inputs.iter().enumerate() {
+            let output = format!("[a{}]", index);
+            let mut filter = format!("[{}]volume={}", audio_input.stream, audio_input.volume);
+            if audio_input.denoise {
+                filter.push_str(",anlmdn=s=10:p=0.001:r=0.005");
+            }
+            filters.push(format!("{}{}", filter, output));
+            outputs.push(output);
+        }
+
+        if !separate_tracks && outputs.len() > 1 {
+            // normalize=0 避免混音后音量被平均
+            filters.push(format!(
+                "{}amix=inputs={}:duration=longest:normalize=0[aout]",
+                outputs.join(""),
+                outputs.len()
+            ));
+            outputs = vec!["[aout]".to_string()];
+        }
+
+        (filters.join(";"), outputs)
+    }
+
+    /// 解析 `pactl list short sources` 的输出
+    ///
+    /// monitor 为 true 时返回扬声器的 monitor 设备，否则返回麦克风等输入设备
+    #[cfg(target_os = "linux")]
+    fn parse_pactl_sources(output: &str, monitor: bool) -> Vec<String> {
+        output
+            .lines()
+            .filter_map(|line| line.split('\t').nth(1))
+            .filter(|name| name.ends_with(".monitor") == monitor)
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    #[cfg(target_os = "linux")]
+    fn get_pactl_sources(monitor: bool) -> Vec<String> {
+        match std::process::Command::new("pactl")
+            .args(["list", "short", "sources"])
+            .output()
+        {
+            Ok(output) => {
+                Self::parse_pactl_sources(&String::from_utf8_lossy(&output.stdout), monitor)
+            }
+            Err(e) => {
+                log::warn!(
+                    "[VideoRecordService::get_pactl_sources] Failed to run pactl: {}",
+                    e
+                );
+                Vec::new()
+            }
+        }
+    }
+
     #[cfg(target_os = "macos")]
     pub fn get_device_info_list(&self) -> Vec<DeviceInfo> {
         let mut device_info_list = Vec::new();
@@ -737,6 +1282,11 @@ impl VideoRecordService {
             }
         }
 
+        #[cfg(target_os = "linux")]
+        {
+            device_names = Self::get_pactl_sources(false);
+        }
+
         println!(
             "[get_microphone_device_names] Total found devices: {}",
             device_names.len()
@@ -744,6 +1294,19 @@ impl VideoRecordService {
         device_names
     }
 
+    /// 可用于采集系统音频的设备
+    pub fn get_system_audio_device_names(&self) -> Vec<String> {
+        #[cfg(target_os = "linux")]
+        {
+            Self::get_pactl_sources(true)
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            self.get_microphone_device_names()
+        }
+    }
+
     /// 根据设备名称获取设备索引
     /// 返回 Option<u32>，如果找不到设备则返回 None
     pub fn get_microphone_device_index(&self, device_name: &str) -> Option<u32> {
@@ -775,6 +1338,10 @@ impl VideoRecordService {
             let _ = child.kill();
         }
 
+        if self.is_replay() {
+            self.remove_replay_files();
+        }
+
         self.cleanup();
         Ok(())
     }
@@ -804,6 +1371,13 @@ impl VideoRecordService {
             let _ = child.wait();
         }
 
+        // 回放模式只保留缓冲，停止后直接丢弃
+        if self.is_replay() {
+            self.remove_replay_files();
+            self.cleanup();
+            return Ok(None);
+        }
+
         // 如果只有一个片段，直接重命名
         let mut final_filename = self.get_final_filename();
         if self.segments.len() == 1 {
@@ -834,18 +1408,62 @@ impl VideoRecordService {
         Ok(Some(final_filename))
     }
 
+    /// 发送停止信号并取出当前录制，服务立即回到空闲状态
+    ///
+    /// 等待 ffmpeg 退出和合并片段较慢，对返回的录制调用 stop 完成收尾，避免长时间占用服务
+    pub fn detach_recording(&mut self) -> Option<VideoRecordService> {
+        if self.state != VideoRecordState::Recording && self.state != VideoRecordState::Paused {
+            return None;
+        }
+
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.quit();
+        }
+
+        let mut idle_service = Self::new();
+        idle_service.ffmpeg_path = self.ffmpeg_path.clone();
+        #[cfg(target_os = "linux")]
+        {
+            idle_service.linux_capture_backend = self.linux_capture_backend.clone();
+        }
+        // 保留录制标识，避免新录制与旧的标识重复
+        idle_service.record_id = self.record_id;
+
+        Some(std::mem::replace(self, idle_service))
+    }
+
     fn merge_segments(&mut self, final_filename: String) -> Result<()> {
         let params = self.recording_params.as_ref().unwrap();
 
         // 创建临时的文件列表
         let list_filename = format!("{}_segments.txt", params.output_file);
-        let mut list_content = String::new();
+        self.concat_files(&self.segments, &list_filename, &final_filename)?;
 
+        // 删除所有片段文件
         for segment in &self.segments {
-            list_content.push_str(&format!("file '{}'\n", segment));
+            if let Err(e) = std::fs::remove_file(segment) {
+                println!("Warning: Failed to delete segment file {}: {}", segment, e);
+            }
+        }
+
+        println!("Segments merged successfully");
+        Ok(())
+    }
+
+    /// 使用 concat 无损拼接多个视频文件
+    fn concat_files(
+        &self,
+        files: &[String],
+        list_filename: &str,
+        final_filename: &str,
+    ) -> Result<()> {
+        let mut list_content = String::new();
+
+        for file in files {
+            list_content.push_str(&format!("file '{}'\n", file));
         }
 
-        if let Err(e) = std::fs::write(&list_filename, list_content) {
+        if let Err(e) = std::fs::write(list_filename, list_content) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::Other,
                 format!("Failed to create segment list: {}", e),
@@ -860,29 +1478,17 @@ impl VideoRecordService {
             .arg("-safe")
             .arg("0")
             .arg("-i")
-            .arg(&list_filename)
+            .arg(list_filename)
             .arg("-c")
             .arg("copy")
             .arg("-y")
-            .arg(&final_filename);
+            .arg(final_filename);
 
         println!("Merging segments with command: {:?}", command);
 
-        match command.spawn() {
+        let result = match command.spawn() {
             Ok(mut child) => {
                 let _ = child.wait();
-
-                // 删除临时文件列表
-                let _ = std::fs::remove_file(&list_filename);
-
-                // 删除所有片段文件
-                for segment in &self.segments {
-                    if let Err(e) = std::fs::remove_file(segment) {
-                        println!("Warning: Failed to delete segment file {}: {}", segment, e);
-                    }
-                }
-
-                println!("Segments merged successfully");
                 Ok(())
             }
             Err(e) => {
@@ -892,7 +1498,145 @@ impl VideoRecordService {
                     format!("Failed to merge segments: {}", e),
                 ))
             }
+        };
+
+        // 删除临时文件列表
+        let _ = std::fs::remove_file(list_filename);
+
+        result
+    }
+
+    fn is_replay(&self) -> bool {
+        self.recording_params
+            .as_ref()
+            .is_some_and(|params| params.replay_options.is_some())
+    }
+
+    fn get_replay_list_filename(output_file: &str) -> String {
+        format!("{}_replay.csv", output_file)
+    }
+
+    /// 解析 ffmpeg 输出的 csv 片段列表，返回已写完的片段及其时长
+    ///
+    /// 每行格式为: 文件名,开始时间,结束时间，文件名为相对片段列表所在目录的路径
+    fn parse_replay_segment_list(content: &str, list_dir: &Path) -> Vec<(String, f64)> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.trim().rsplitn(3, ',');
+                let end_time = parts.next()?.parse::<f64>().ok()?;
+                let start_time = parts.next()?.parse::<f64>().ok()?;
+                let filename = parts.next()?.trim_matches('"').replace("\"\"", "\"");
+
+                let path = Path::new(&filename);
+                let path = if path.is_absolute() {
+                    path.to_path_buf()
+                } else {
+                    list_dir.join(path)
+                };
+
+                Some((
+                    path.to_string_lossy().to_string(),
+                    (end_time - start_time).max(0.0),
+                ))
+            })
+            .collect()
+    }
+
+    /// 从最新的片段往前选取，直到覆盖指定的时长
+    fn select_replay_segments(segments: &[(String, f64)], duration_secs: f64) -> Vec<String> {
+        let mut selected_duration = 0.0;
+        let mut selected = Vec::new();
+        for (segment, segment_duration) in segments.iter().rev() {
+            if selected_duration >= duration_secs {
+                break;
+            }
+
+            selected.push(segment.clone());
+            selected_duration += segment_duration;
         }
+
+        selected.reverse();
+        selected
+    }
+
+    /// 将回放缓冲中最近的片段保存为视频，实际时长会按片段向上取整
+    ///
+    /// # 参数
+    /// * `duration_secs` - 需要保存的时长
+    /// * `output_file` - 输出文件，不包含扩展名
+    pub fn save_replay(&self, duration_secs: u32, output_file: &str) -> Result<String> {
+        let params = match &self.recording_params {
+            Some(params) if params.replay_options.is_some() => params,
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "Replay buffer is not running",
+                ));
+            }
+        };
+
+        let list_filename = Self::get_replay_list_filename(&params.output_file);
+        let list_content = std::fs::read_to_string(&list_filename).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Failed to read replay segment list: {}", e),
+            )
+        })?;
+        let list_dir = Path::new(&list_filename)
+            .parent()
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+
+        let segments = Self::select_replay_segments(
+            &Self::parse_replay_segment_list(&list_content, &list_dir),
+            duration_secs as f64,
+        );
+        if segments.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "No replay segment has been recorded yet",
+            ));
+        }
+
+        let final_filename = format!("{}.{}", output_file, params.format.extension());
+        if let Some(parent_dir) = Path::new(&final_filename).parent() {
+            std::fs::create_dir_all(parent_dir)?;
+        }
+
+        if segments.len() == 1 {
+            // 片段会被 ffmpeg 循环覆盖，需要复制而不是移动
+            std::fs::copy(&segments[0], &final_filename)?;
+        } else {
+            self.concat_files(
+                &segments,
+                &format!("{}_replay_segments.txt", output_file),
+                &final_filename,
+            )?;
+        }
+
+        Ok(final_filename)
+    }
+
+    fn remove_replay_files(&self) {
+        let params = match &self.recording_params {
+            Some(params) => params,
+            None => return,
+        };
+        let replay_options = match &params.replay_options {
+            Some(replay_options) => replay_options,
+            None => return,
+        };
+
+        for index in 0..replay_options.get_wrap_size() {
+            let _ = std::fs::remove_file(format!(
+                "{}_replay_{:03}.{}",
+                params.output_file,
+                index,
+                params.format.extension()
+            ));
+        }
+        let _ = std::fs::remove_file(Self::get_replay_list_filename(&params.output_file));
     }
 
     fn convert_to_gif(
@@ -1064,6 +1808,102 @@ impl VideoRecordService {
         self.segments.clear();
         self.segment_counter = 0;
         self.recording_params = None;
+        self.recorded_duration = Duration::ZERO;
+        self.segment_started_at = None;
+    }
+
+    /// 当前录制的标识，录制结束或重新开始后会变化
+    pub fn get_record_id(&self) -> Option<u64> {
+        match self.state {
+            VideoRecordState::Idle => None,
+            _ => Some(self.record_id),
+        }
+    }
+
+    /// 已录制的时长，不包含暂停的时间
+    pub fn get_recorded_duration(&self) -> Duration {
+        self.recorded_duration
+            + self
+                .segment_started_at
+                .map(|segment_started_at| segment_started_at.elapsed())
+                .unwrap_or_default()
+    }
+
+    /// 已录制片段的总大小，字节
+    pub fn get_recorded_size(&self) -> u64 {
+        self.segments
+            .iter()
+            .filter_map(|segment| std::fs::metadata(segment).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    fn get_exceeded_limit(
+        limits: &VideoRecordLimits,
+        recorded_duration: Duration,
+        recorded_size: u64,
+        free_disk_space: Option<u64>,
+    ) -> Option<VideoRecordStopReason> {
+        const MB: u64 = 1024 * 1024;
+
+        if let (Some(min_free_disk_space_mb), Some(free_disk_space)) =
+            (limits.min_free_disk_space_mb, free_disk_space)
+        {
+            if free_disk_space < min_free_disk_space_mb * MB {
+                return Some(VideoRecordStopReason::LowDiskSpace);
+            }
+        }
+
+        if let Some(max_file_size_mb) = limits.max_file_size_mb {
+            if recorded_size >= max_file_size_mb * MB {
+                return Some(VideoRecordStopReason::MaxFileSize);
+            }
+        }
+
+        if let Some(max_duration_secs) = limits.max_duration_secs {
+            if recorded_duration >= Duration::from_secs(max_duration_secs) {
+                return Some(VideoRecordStopReason::MaxDuration);
+            }
+        }
+
+        None
+    }
+
+    /// 检查录制是否超出限制，暂停时不检查
+    pub fn check_limits(&self) -> Option<VideoRecordStopReason> {
+        if self.state != VideoRecordState::Recording {
+            return None;
+        }
+
+        let params = self.recording_params.as_ref()?;
+        let limits = &params.limits;
+
+        let free_disk_space = match limits.min_free_disk_space_mb {
+            Some(_) => {
+                let output_dir = Path::new(&params.output_file)
+                    .parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())
+                    .unwrap_or(Path::new("."));
+                match fs4::available_space(output_dir) {
+                    Ok(free_disk_space) => Some(free_disk_space),
+                    Err(e) => {
+                        log::warn!(
+                            "[VideoRecordService::check_limits] Failed to get available space: {}",
+                            e
+                        );
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        Self::get_exceeded_limit(
+            limits,
+            self.get_recorded_duration(),
+            self.get_recorded_size(),
+            free_disk_space,
+        )
     }
 
     pub fn pause(&mut self) -> Result<()> {
@@ -1074,6 +1914,14 @@ impl VideoRecordService {
             ));
         }
 
+        // 恢复后 ffmpeg 会从头覆盖循环片段，回放模式不支持暂停
+        if self.is_replay() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Replay buffer can not be paused",
+            ));
+        }
+
         println!("[FFmpeg] Pausing recording - stopping current segment");
 
         // 停止当前片段的录制
@@ -1082,6 +1930,10 @@ impl VideoRecordService {
             let _ = child.wait();
         }
 
+        if let Some(segment_started_at) = self.segment_started_at.take() {
+            self.recorded_duration += segment_started_at.elapsed();
+        }
+
         self.state = VideoRecordState::Paused;
         Ok(())
     }
@@ -1100,3 +1952,320 @@ impl VideoRecordService {
         self.start_segment()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_get_linux_video_input_args() {
+        let args = VideoRecordService::get_linux_video_input_args(
+            &LinuxCaptureBackend::X11Grab {
+                display: Some(":99".to_string()),
+            },
+            30,
+            10,
+            20,
+            320,
+            240,
+        );
+        assert_eq!(
+            args,
+            vec![
+                "-f",
+                "x11grab",
+                "-framerate",
+                "30",
+                "-video_size",
+                "320x240",
+                "-i",
+                ":99+10,20"
+            ]
+        );
+
+        let args = VideoRecordService::get_linux_video_input_args(
+            &LinuxCaptureBackend::PipeWire,
+            60,
+            0,
+            0,
+            320,
+            240,
+        );
+        assert_eq!(args, vec!["-f", "lavfi", "-i", "pipewiregrab=framerate=60"]);
+    }
+
+    #[test]
+    fn test_get_audio_filter_complex() {
+        let audio_inputs = vec![
+            AudioInput {
+                stream: "1:a".to_string(),
+                volume: 1.5,
+                denoise: true,
+            },
+            AudioInput {
+                stream: "2:a".to_string(),
+                volume: 0.5,
+                denoise: false,
+            },
+        ];
+
+        assert_eq!(
+            VideoRecordService::get_audio_filter_complex(&audio_inputs, false),
+            (
+                "[1:a]volume=1.5,anlmdn=s=10:p=0.001:r=0.005[a0];[2:a]volume=0.5[a1];[a0][a1]amix=inputs=2:duration=longest:normalize=0[aout]".to_string(),
+                vec!["[aout]".to_string()]
+            )
+        );
+        assert_eq!(
+            VideoRecordService::get_audio_filter_complex(&audio_inputs, true).1,
+            vec!["[a0]".to_string(), "[a1]".to_string()]
+        );
+        assert_eq!(
+            VideoRecordService::get_audio_filter_complex(&audio_inputs[1..], false),
+            ("[2:a]volume=0.5[a0]".to_string(), vec!["[a0]".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_select_replay_segments() {
+        let content = "record_replay_003.mp4,15.000000,20.000000\n\
+            record_replay_004.mp4,20.000000,25.000000\n\
+            \"record,replay_005.mp4\",25.000000,29.500000\n";
+        let list_dir = Path::new("videos");
+        let segments = VideoRecordService::parse_replay_segment_list(content, list_dir);
+
+        assert_eq!(segments.len(), 3);
+        assert_eq!(
+            segments[2],
+            (
+                list_dir
+                    .join("record,replay_005.mp4")
+                    .to_string_lossy()
+                    .to_string(),
+                4.5
+            )
+        );
+
+        assert_eq!(
+            VideoRecordService::select_replay_segments(&segments, 8.0),
+            vec![segments[1].0.clone(), segments[2].0.clone()]
+        );
+        assert_eq!(
+            VideoRecordService::select_replay_segments(&segments, 60.0).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_get_exceeded_limit() {
+        let limits = VideoRecordLimits {
+            max_duration_secs: Some(60),
+            max_file_size_mb: Some(100),
+            min_free_disk_space_mb: Some(500),
+        };
+        const MB: u64 = 1024 * 1024;
+
+        assert_eq!(
+            VideoRecordService::get_exceeded_limit(
+                &limits,
+                Duration::from_secs(30),
+                10 * MB,
+                Some(1000 * MB)
+            ),
+            None
+        );
+        assert_eq!(
+            VideoRecordService::get_exceeded_limit(&limits, Duration::from_secs(60), 10 * MB, None),
+            Some(VideoRecordStopReason::MaxDuration)
+        );
+        assert_eq!(
+            VideoRecordService::get_exceeded_limit(
+                &limits,
+                Duration::from_secs(60),
+                100 * MB,
+                Some(1000 * MB)
+            ),
+            Some(VideoRecordStopReason::MaxFileSize)
+        );
+        assert_eq!(
+            VideoRecordService::get_exceeded_limit(
+                &limits,
+                Duration::from_secs(60),
+                100 * MB,
+                Some(100 * MB)
+            ),
+            Some(VideoRecordStopReason::LowDiskSpace)
+        );
+        assert_eq!(
+            VideoRecordService::get_exceeded_limit(
+                &VideoRecordLimits::default(),
+                Duration::from_secs(3600),
+                u64::MAX,
+                Some(0)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_detach_recording() {
+        let mut service = VideoRecordService::new();
+        assert!(service.detach_recording().is_none());
+
+        service.ffmpeg_path = Some(PathBuf::from("ffmpeg"));
+        service.state = VideoRecordState::Paused;
+        service.record_id = 5;
+        service.segments = vec!["segment_0.mp4".to_string()];
+
+        let detached = service.detach_recording().unwrap();
+        assert_eq!(detached.state, VideoRecordState::Paused);
+        assert_eq!(detached.segments, vec!["segment_0.mp4"]);
+
+        assert_eq!(service.state, VideoRecordState::Idle);
+        assert_eq!(service.get_record_id(), None);
+        assert!(service.segments.is_empty());
+        assert_eq!(service.record_id, 5);
+        assert_eq!(service.get_ffmpeg_path(), Some(&PathBuf::from("ffmpeg")));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_parse_pactl_sources() {
+        let output = "0\talsa_output.pci-0000_00_1f.3.analog-stereo.monitor\tPipeWire\ts32le 2ch 48000Hz\tSUSPENDED\n\
+            1\talsa_input.pci-0000_00_1f.3.analog-stereo\tPipeWire\ts32le 2ch 48000Hz\tRUNNING\n";
+
+        assert_eq!(
+            VideoRecordService::parse_pactl_sources(output, false),
+            vec!["alsa_input.pci-0000_00_1f.3.analog-stereo"]
+        );
+        assert_eq!(
+            VideoRecordService::parse_pactl_sources(output, true),
+            vec!["alsa_output.pci-0000_00_1f.3.analog-stereo.monitor"]
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_parse_ffmpeg_filters() {
+        let output = "Filters:\n  T.. = Timeline support\n  ---\n\
+            \x20T.C acompressor       A->A       Audio compressor.\n\
+            \x20... testsrc           |->V       Generate test pattern.\n";
+
+        assert!(VideoRecordService::parse_ffmpeg_filters(output, "testsrc"));
+        assert!(!VideoRecordService::parse_ffmpeg_filters(
+            output,
+            "pipewiregrab"
+        ));
+    }
+
+    /// 在 Xvfb 中录制 2 秒，中途暂停一次
+    #[cfg(target_os = "linux")]
+    fn record_with_xvfb(
+        display: &str,
+        enable_system_audio: bool,
+        audio_options: VideoRecordAudioOptions,
+    ) -> String {
+        let mut xvfb = std::process::Command::new("Xvfb")
+            .args([display, "-screen", "0", "640x480x24"])
+            .spawn()
+            .expect("Xvfb is required");
+        std::thread::sleep(std::time::Duration::from_millis(500));
+
+        let output_dir = std::env::temp_dir().join(format!(
+            "snow-shot-video-record-test-{}",
+            display.trim_start_matches(':')
+        ));
+        let _ = std::fs::remove_dir_all(&output_dir);
+
+        let mut service = VideoRecordService::new();
+        service.init(
+            &output_dir,
+            Some(LinuxCaptureBackend::X11Grab {
+                display: Some(display.to_string()),
+            }),
+        );
+
+        let result = (|| -> Result<Option<String>> {
+            service.start(
+                0,
+                0,
+                320,
+                240,
+                output_dir.join("record").to_string_lossy().to_string(),
+                VideoFormat::Mp4,
+                30,
+                false,
+                enable_system_audio,
+                String::new(),
+                false,
+                "libx264".to_string(),
+                "ultrafast".to_string(),
+                1920,
+                1080,
+                audio_options,
+                VideoRecordLimits::default(),
+                None,
+            )?;
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            service.pause()?;
+            service.resume()?;
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            service.stop(false, "gif", 10, 320, 240)
+        })();
+
+        let _ = xvfb.kill();
+
+        let final_filename = result.unwrap().expect("final file");
+        let metadata = std::fs::metadata(&final_filename).unwrap();
+        assert!(metadata.len() > 0);
+
+        final_filename
+    }
+
+    /// 需要安装 Xvfb 和 ffmpeg，运行: cargo test -p snow-shot-app-services -- --ignored
+    #[test]
+    #[ignore]
+    #[cfg(target_os = "linux")]
+    fn test_record_with_xvfb() {
+        record_with_xvfb(":99", false, VideoRecordAudioOptions::default());
+    }
+
+    /// 需要安装 Xvfb、ffmpeg 和 PulseAudio/PipeWire，使用 null sink 作为系统音频
+    #[test]
+    #[ignore]
+    #[cfg(target_os = "linux")]
+    fn test_record_system_audio_with_null_sink() {
+        let output = std::process::Command::new("pactl")
+            .args([
+                "load-module",
+                "module-null-sink",
+                "sink_name=snow_shot_test",
+            ])
+            .output()
+            .expect("pactl is required");
+        let module_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        let final_filename = record_with_xvfb(
+            ":98",
+            true,
+            VideoRecordAudioOptions {
+                system_audio_volume: 0.8,
+                system_audio_device_name: Some("snow_shot_test.monitor".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let _ = std::process::Command::new("pactl")
+            .args(["unload-module", &module_id])
+            .output();
+
+        // ffmpeg -i 会输出文件中的流信息
+        let output = std::process::Command::new("ffmpeg")
+            .arg("-i")
+            .arg(&final_filename)
+            .output()
+            .unwrap();
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Audio:"));
+    }
+}
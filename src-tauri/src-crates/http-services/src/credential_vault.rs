@@ -0,0 +1,571 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use log::{info, warn};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::post_upload::UrlShortenerProfile;
+use crate::upload_destination::UploadProfile;
+
+/// 系统密钥环中的服务名
+const KEYRING_SERVICE: &str = "snow-shot-upload-profile";
+const PROFILES_FILE_NAME: &str = "upload_profiles.json";
+const URL_SHORTENERS_FILE_NAME: &str = "url_shorteners.json";
+const SECRETS_FILE_NAME: &str = "upload_secrets.enc";
+const SECRETS_KEY_FILE_NAME: &str = "upload_secrets.key";
+const NONCE_SIZE: usize = 12;
+
+/// 读写文件时加锁，避免并发修改
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+type ProfileSecrets = HashMap<String, String>;
+
+/// 凭据库中保存的配置，配置和密钥分开保存
+trait VaultItem: Serialize + DeserializeOwned {
+    /// 保存配置的文件
+    const FILE_NAME: &'static str;
+    /// 用于日志和错误信息
+    const DISPLAY_NAME: &'static str;
+
+    fn id(&self) -> &str;
+
+    /// 密钥环和加密文件中的键，不同类型的配置使用不同前缀避免 id 冲突
+    fn get_secrets_id(id: &str) -> String;
+
+    fn take_secrets(&mut self) -> ProfileSecrets;
+
+    fn fill_empty_secrets(&mut self, secrets: &ProfileSecrets);
+
+    fn fill_secrets(&mut self, secrets: &ProfileSecrets);
+}
+
+impl VaultItem for UploadProfile {
+    const FILE_NAME: &'static str = PROFILES_FILE_NAME;
+    const DISPLAY_NAME: &'static str = "Upload profile";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 兼容已保存的密钥，上传配置直接使用 id
+    fn get_secrets_id(id: &str) -> String {
+        id.to_string()
+    }
+
+    fn take_secrets(&mut self) -> ProfileSecrets {
+        self.destination.take_secrets()
+    }
+
+    fn fill_empty_secrets(&mut self, secrets: &ProfileSecrets) {
+        self.destination.fill_empty_secrets(secrets);
+    }
+
+    fn fill_secrets(&mut self, secrets: &ProfileSecrets) {
+        self.destination.fill_secrets(secrets);
+    }
+}
+
+impl VaultItem for UrlShortenerProfile {
+    const FILE_NAME: &'static str = URL_SHORTENERS_FILE_NAME;
+    const DISPLAY_NAME: &'static str = "Url shortener";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn get_secrets_id(id: &str) -> String {
+        format!("url_shortener:{}", id)
+    }
+
+    fn take_secrets(&mut self) -> ProfileSecrets {
+        self.shortener.take_secrets()
+    }
+
+    fn fill_empty_secrets(&mut self, secrets: &ProfileSecrets) {
+        self.shortener.fill_empty_secrets(secrets);
+    }
+
+    fn fill_secrets(&mut self, secrets: &ProfileSecrets) {
+        self.shortener.fill_secrets(secrets);
+    }
+}
+
+/// 上传配置和短链接配置的凭据库
+///
+/// 配置本身保存在 JSON 文件中，密钥优先保存到系统密钥环
+/// （Windows 凭据管理器、macOS 钥匙串、Linux Secret Service），
+/// 密钥环不可用时保存到加密文件中。加密文件的密钥与其保存在同一目录，
+/// 只能避免密钥以明文形式保存，无法防御能读取用户目录的程序
+pub struct CredentialVault {
+    data_dir: PathBuf,
+    /// 为 false 时只使用加密文件
+    use_keyring: bool,
+}
+
+impl CredentialVault {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            data_dir: data_dir.to_path_buf(),
+            use_keyring: true,
+        }
+    }
+
+    /// 不访问系统密钥环，密钥只保存到加密文件
+    pub fn new_file_only(data_dir: &Path) -> Self {
+        Self {
+            data_dir: data_dir.to_path_buf(),
+            use_keyring: false,
+        }
+    }
+
+    /// 获取所有上传配置，不包含密钥
+    pub fn list_profiles(&self) -> Result<Vec<UploadProfile>> {
+        self.list_items()
+    }
+
+    /// 获取包含密钥的完整上传配置
+    pub fn get_profile(&self, profile_id: &str) -> Result<UploadProfile> {
+        self.get_item(profile_id)
+    }
+
+    /// 保存上传配置，同 id 的配置会被替换
+    ///
+    /// 为空的密钥字段使用已保存的密钥，`list_profiles` 返回的配置可以直接保存
+    pub fn save_profile(&self, profile: UploadProfile) -> Result<()> {
+        self.save_item(profile)
+    }
+
+    /// 删除上传配置及其密钥
+    pub fn delete_profile(&self, profile_id: &str) -> Result<()> {
+        self.delete_item::<UploadProfile>(profile_id)
+    }
+
+    /// 获取所有短链接配置，不包含密钥
+    pub fn list_url_shorteners(&self) -> Result<Vec<UrlShortenerProfile>> {
+        self.list_items()
+    }
+
+    /// 获取包含密钥的完整短链接配置
+    pub fn get_url_shortener(&self, shortener_id: &str) -> Result<UrlShortenerProfile> {
+        self.get_item(shortener_id)
+    }
+
+    /// 保存短链接配置，为空的密钥字段使用已保存的密钥
+    pub fn save_url_shortener(&self, shortener: UrlShortenerProfile) -> Result<()> {
+        self.save_item(shortener)
+    }
+
+    /// 删除短链接配置及其密钥
+    pub fn delete_url_shortener(&self, shortener_id: &str) -> Result<()> {
+        self.delete_item::<UrlShortenerProfile>(shortener_id)
+    }
+
+    fn list_items<T: VaultItem>(&self) -> Result<Vec<T>> {
+        let _lock = FILE_LOCK.lock().unwrap();
+        self.read_items()
+    }
+
+    fn get_item<T: VaultItem>(&self, id: &str) -> Result<T> {
+        let _lock = FILE_LOCK.lock().unwrap();
+
+        let mut item = self
+            .read_items::<T>()?
+            .into_iter()
+            .find(|item| item.id() == id)
+            .with_context(|| format!("{} not found: {}", T::DISPLAY_NAME, id))?;
+
+        let secrets_id = T::get_secrets_id(id);
+        let secrets = match self.get_keyring_secrets(&secrets_id) {
+            Some(secrets) => secrets,
+            None => self
+                .read_file_secrets()?
+                .remove(&secrets_id)
+                .unwrap_or_default(),
+        };
+        item.fill_secrets(&secrets);
+
+        Ok(item)
+    }
+
+    fn save_item<T: VaultItem>(&self, mut item: T) -> Result<()> {
+        let _lock = FILE_LOCK.lock().unwrap();
+
+        let secrets_id = T::get_secrets_id(item.id());
+        let mut file_secrets = self.read_file_secrets()?;
+        let saved_secrets = match self.get_keyring_secrets(&secrets_id) {
+            Some(secrets) => secrets,
+            None => file_secrets.get(&secrets_id).cloned().unwrap_or_default(),
+        };
+        item.fill_empty_secrets(&saved_secrets);
+        let secrets = item.take_secrets();
+
+        match self.set_keyring_secrets(&secrets_id, &secrets) {
+            Ok(()) => {
+                file_secrets.remove(&secrets_id);
+            }
+            Err(e) => {
+                warn!(
+                    "Save secrets to keyring failed, fallback to encrypted file: {:#}",
+                    e
+                );
+                file_secrets.insert(secrets_id, secrets);
+            }
+        }
+        self.write_file_secrets(&file_secrets)?;
+
+        let mut items = self.read_items::<T>()?;
+        match items
+            .iter_mut()
+            .find(|saved_item| saved_item.id() == item.id())
+        {
+            Some(saved_item) => *saved_item = item,
+            None => items.push(item),
+        }
+        self.write_items(&items)?;
+
+        Ok(())
+    }
+
+    fn delete_item<T: VaultItem>(&self, id: &str) -> Result<()> {
+        let _lock = FILE_LOCK.lock().unwrap();
+
+        let secrets_id = T::get_secrets_id(id);
+        if self.use_keyring {
+            if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, &secrets_id) {
+                match entry.delete_credential() {
+                    Ok(()) | Err(keyring::Error::NoEntry) => {}
+                    Err(e) => warn!("Delete secrets from keyring failed: {}", e),
+                }
+            }
+        }
+
+        let mut file_secrets = self.read_file_secrets()?;
+        if file_secrets.remove(&secrets_id).is_some() {
+            self.write_file_secrets(&file_secrets)?;
+        }
+
+        let mut items = self.read_items::<T>()?;
+        items.retain(|item| item.id() != id);
+        self.write_items(&items)?;
+
+        info!("{} deleted: {}", T::DISPLAY_NAME, id);
+        Ok(())
+    }
+
+    fn read_items<T: VaultItem>(&self) -> Result<Vec<T>> {
+        let path = self.data_dir.join(T::FILE_NAME);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Read {} failed: {}", T::DISPLAY_NAME, path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Parse {} failed", T::DISPLAY_NAME))
+    }
+
+    fn write_items<T: VaultItem>(&self, items: &[T]) -> Result<()> {
+        std::fs::create_dir_all(&self.data_dir)?;
+        std::fs::write(
+            self.data_dir.join(T::FILE_NAME),
+            serde_json::to_string_pretty(items)?,
+        )
+        .with_context(|| format!("Write {} failed", T::DISPLAY_NAME))
+    }
+
+    fn get_keyring_secrets(&self, profile_id: &str) -> Option<ProfileSecrets> {
+        if !self.use_keyring {
+            return None;
+        }
+
+        let entry = keyring::Entry::new(KEYRING_SERVICE, profile_id).ok()?;
+        match entry.get_password() {
+            Ok(content) => serde_json::from_str(&content).ok(),
+            Err(keyring::Error::NoEntry) => None,
+            Err(e) => {
+                warn!("Read secrets from keyring failed: {}", e);
+                None
+            }
+        }
+    }
+
+    fn set_keyring_secrets(&self, profile_id: &str, secrets: &ProfileSecrets) -> Result<()> {
+        if !self.use_keyring {
+            anyhow::bail!("Keyring is disabled");
+        }
+
+        let entry = keyring::Entry::new(KEYRING_SERVICE, profile_id)
+            .context("Create keyring entry failed")?;
+        entry
+            .set_password(&serde_json::to_string(secrets)?)
+            .context("Write keyring entry failed")?;
+
+        // 部分环境下写入成功但无法读取，读取验证后才认为可用
+        match self.get_keyring_secrets(profile_id) {
+            Some(saved_secrets) if &saved_secrets == secrets => Ok(()),
+            _ => anyhow::bail!("Keyring entry is not readable"),
+        }
+    }
+
+    /// 读取加密文件的密钥，`create` 为 true 且密钥不存在时创建
+    ///
+    /// 密钥无效时返回错误，重新生成会导致已保存的密钥无法解密
+    fn get_file_key(&self, create: bool) -> Result<Key> {
+        let path = self.data_dir.join(SECRETS_KEY_FILE_NAME);
+        match std::fs::read(&path) {
+            Ok(key) if key.len() == 32 => return Ok(*Key::from_slice(&key)),
+            Ok(_) => anyhow::bail!("Invalid secrets key file: {}", path.display()),
+            Err(e) if create && e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Read secrets key failed: {}", path.display()))
+            }
+        }
+
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        std::fs::create_dir_all(&self.data_dir)?;
+        std::fs::write(&path, key.as_slice()).context("Write secrets key failed")?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+
+        Ok(key)
+    }
+
+    fn read_file_secrets(&self) -> Result<HashMap<String, ProfileSecrets>> {
+        let path = self.data_dir.join(SECRETS_FILE_NAME);
+        let content = match std::fs::read(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Read secrets file failed: {}", path.display()))
+            }
+        };
+
+        let content = decrypt(&self.get_file_key(false)?, &content)?;
+        serde_json::from_slice(&content).context("Parse secrets file failed")
+    }
+
+    fn write_file_secrets(&self, secrets: &HashMap<String, ProfileSecrets>) -> Result<()> {
+        let path = self.data_dir.join(SECRETS_FILE_NAME);
+        if secrets.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path).context("Remove secrets file failed")?;
+            }
+            return Ok(());
+        }
+
+        let content = encrypt(&self.get_file_key(true)?, &serde_json::to_vec(secrets)?)?;
+        std::fs::create_dir_all(&self.data_dir)?;
+        std::fs::write(&path, content).context("Write secrets file failed")
+    }
+}
+
+/// 加密后的格式为: nonce + 密文
+fn encrypt(key: &Key, content: &[u8]) -> Result<Vec<u8>> {
+    let cipher = ChaCha20Poly1305::new(key);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, content)
+        .map_err(|e| anyhow::anyhow!("Encrypt secrets failed: {}", e))?;
+
+    let mut result = nonce.to_vec();
+    result.extend(ciphertext);
+    Ok(result)
+}
+
+fn decrypt(key: &Key, content: &[u8]) -> Result<Vec<u8>> {
+    if content.len() < NONCE_SIZE {
+        anyhow::bail!("Invalid secrets file");
+    }
+
+    let (nonce, ciphertext) = content.split_at(NONCE_SIZE);
+    ChaCha20Poly1305::new(key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|e| anyhow::anyhow!("Decrypt secrets failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post_upload::UrlShortenerConfig;
+    use crate::upload_destination::UploadDestinationConfig;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let content = encrypt(&key, b"secret").unwrap();
+
+        assert_ne!(&content[NONCE_SIZE..], b"secret");
+        assert_eq!(decrypt(&key, &content).unwrap(), b"secret");
+        assert!(decrypt(&ChaCha20Poly1305::generate_key(&mut OsRng), &content).is_err());
+    }
+
+    #[test]
+    fn test_take_and_fill_secrets() {
+        let mut destination = UploadDestinationConfig::WebDav {
+            endpoint: "https://cloud.example.com/remote.php/dav/files/user/".to_string(),
+            username: "user".to_string(),
+            password: "password".to_string(),
+            path_prefix: None,
+            public_url_prefix: None,
+        };
+
+        let secrets = destination.take_secrets();
+        assert_eq!(
+            secrets.get("password").map(String::as_str),
+            Some("password")
+        );
+        assert!(!serde_json::to_string(&destination)
+            .unwrap()
+            .contains("\"password\":\"password\""));
+
+        destination.fill_secrets(&secrets);
+        match destination {
+            UploadDestinationConfig::WebDav { password, .. } => assert_eq!(password, "password"),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_file_secrets() {
+        let data_dir = std::env::temp_dir().join("snow-shot-credential-vault-test");
+        let _ = std::fs::remove_dir_all(&data_dir);
+
+        let vault = CredentialVault::new(&data_dir);
+        let mut secrets = HashMap::new();
+        secrets.insert(
+            "profile".to_string(),
+            HashMap::from([("token".to_string(), "secret".to_string())]),
+        );
+        vault.write_file_secrets(&secrets).unwrap();
+
+        let content = std::fs::read(data_dir.join(SECRETS_FILE_NAME)).unwrap();
+        assert!(!String::from_utf8_lossy(&content).contains("secret"));
+        assert_eq!(vault.read_file_secrets().unwrap(), secrets);
+
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn test_invalid_file_key() {
+        let data_dir = std::env::temp_dir().join("snow-shot-credential-vault-key-test");
+        let _ = std::fs::remove_dir_all(&data_dir);
+        std::fs::create_dir_all(&data_dir).unwrap();
+        std::fs::write(data_dir.join(SECRETS_KEY_FILE_NAME), b"invalid").unwrap();
+
+        let vault = CredentialVault::new(&data_dir);
+        let secrets = HashMap::from([("profile".to_string(), HashMap::new())]);
+        assert!(vault.write_file_secrets(&secrets).is_err());
+        // 无效的密钥不会被覆盖
+        assert_eq!(
+            std::fs::read(data_dir.join(SECRETS_KEY_FILE_NAME)).unwrap(),
+            b"invalid"
+        );
+
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn test_save_stripped_profile() {
+        let data_dir = std::env::temp_dir().join("snow-shot-credential-vault-profile-test");
+        let _ = std::fs::remove_dir_all(&data_dir);
+
+        let vault = CredentialVault::new_file_only(&data_dir);
+        save_stripped_profile(&vault);
+        // 删除配置后加密文件中不再有密钥
+        assert!(!data_dir.join(SECRETS_FILE_NAME).exists());
+
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
+    /// 会读写系统密钥环，需要手动运行: cargo test -- --ignored
+    #[test]
+    #[ignore]
+    fn test_save_stripped_profile_with_keyring() {
+        let data_dir = std::env::temp_dir().join("snow-shot-credential-vault-keyring-profile-test");
+        let _ = std::fs::remove_dir_all(&data_dir);
+
+        save_stripped_profile(&CredentialVault::new(&data_dir));
+
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn test_save_url_shortener() {
+        let data_dir = std::env::temp_dir().join("snow-shot-credential-vault-shortener-test");
+        let _ = std::fs::remove_dir_all(&data_dir);
+
+        let vault = CredentialVault::new_file_only(&data_dir);
+        vault
+            .save_url_shortener(UrlShortenerProfile {
+                id: "shlink".to_string(),
+                name: "Shlink".to_string(),
+                shortener: UrlShortenerConfig::Shlink {
+                    api_url: "https://s.example.com".to_string(),
+                    api_key: "api-key".to_string(),
+                    domain: None,
+                },
+            })
+            .unwrap();
+
+        let content = std::fs::read_to_string(data_dir.join(URL_SHORTENERS_FILE_NAME)).unwrap();
+        assert!(!content.contains("api-key"));
+        // 与短链接配置同 id 的上传配置不存在
+        assert!(vault.get_profile("shlink").is_err());
+
+        match vault.get_url_shortener("shlink").unwrap().shortener {
+            UrlShortenerConfig::Shlink { api_key, .. } => assert_eq!(api_key, "api-key"),
+            _ => unreachable!(),
+        }
+
+        vault.delete_url_shortener("shlink").unwrap();
+        assert!(vault.list_url_shorteners().unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
+    /// 保存配置后使用不包含密钥的配置修改，密钥保持不变，最后删除配置
+    fn save_stripped_profile(vault: &CredentialVault) {
+        let profile_id = format!("snow-shot-test-{}", std::process::id());
+
+        vault
+            .save_profile(UploadProfile {
+                id: profile_id.clone(),
+                name: "WebDAV".to_string(),
+                destination: UploadDestinationConfig::WebDav {
+                    endpoint: "https://cloud.example.com/dav/".to_string(),
+                    username: "user".to_string(),
+                    password: "password".to_string(),
+                    path_prefix: None,
+                    public_url_prefix: None,
+                },
+            })
+            .unwrap();
+
+        // 使用不包含密钥的配置修改名称
+        let mut profile = vault.list_profiles().unwrap().remove(0);
+        match &profile.destination {
+            UploadDestinationConfig::WebDav { password, .. } => assert!(password.is_empty()),
+            _ => unreachable!(),
+        }
+        profile.name = "Renamed".to_string();
+        vault.save_profile(profile).unwrap();
+
+        let profile = vault.get_profile(&profile_id).unwrap();
+        assert_eq!(profile.name, "Renamed");
+        match profile.destination {
+            UploadDestinationConfig::WebDav { password, .. } => assert_eq!(password, "password"),
+            _ => unreachable!(),
+        }
+
+        vault.delete_profile(&profile_id).unwrap();
+    }
+}
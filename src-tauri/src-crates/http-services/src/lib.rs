@@ -1,4 +1,27 @@
+pub mod credential_vault;
+pub mod post_upload;
 pub mod s3;
+pub mod translation;
+pub mod upload_destination;
+pub mod upload_history;
+pub mod upload_queue;
 
 // 重新导出常用类型
-pub use s3::{S3Config, S3Service};
+pub use credential_vault::CredentialVault;
+pub use post_upload::{
+    PostUploadOptions, PostUploadResult, UploadUrlFormat, UrlShortener, UrlShortenerConfig,
+    UrlShortenerProfile,
+};
+pub use s3::{
+    S3Config, S3MultipartOptions, S3MultipartUploadState, S3Service, S3ShareOptions,
+    S3UploadProgress, S3UploadedPart,
+};
+pub use translation::{TranslationBackendConfig, TranslationService};
+pub use upload_destination::{
+    create_upload_destination, UploadDestination, UploadDestinationConfig, UploadProfile,
+    UploadResult, UploadStatusError,
+};
+pub use upload_history::{UploadHistoryQuery, UploadHistoryRecord, UploadHistoryStore};
+pub use upload_queue::{
+    is_retryable_error, UploadQueueJob, UploadQueueJobStatus, UploadQueueOptions, UploadQueueStore,
+};
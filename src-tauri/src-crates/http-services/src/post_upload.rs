@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::upload_destination::{create_http_client, get_json_path_string, join_url};
+
+/// 短链接服务配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum UrlShortenerConfig {
+    /// YOURLS，例如: https://sho.rt/yourls-api.php
+    Yourls {
+        api_url: String,
+        /// 密钥签名，在 YOURLS 后台的 Tools 页面获取
+        signature: String,
+    },
+    /// Shlink，例如: https://s.example.com
+    Shlink {
+        api_url: String,
+        api_key: String,
+        /// 多域名时指定短链接的域名
+        domain: Option<String>,
+    },
+    /// 通用的 GET 接口，长链接通过查询参数传入
+    Custom {
+        url: String,
+        /// 长链接的查询参数名，默认为 url
+        url_param_name: Option<String>,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        /// 返回 JSON 中短链接的路径，为空时使用整个响应文本
+        url_json_path: Option<String>,
+    },
+}
+
+impl UrlShortenerConfig {
+    /// 取出配置中的密钥字段并清空，用于单独保存
+    pub fn take_secrets(&mut self) -> HashMap<String, String> {
+        let mut secrets = HashMap::new();
+        let mut take = |name: &str, value: &mut String| {
+            if !value.is_empty() {
+                secrets.insert(name.to_string(), std::mem::take(value));
+            }
+        };
+
+        match self {
+            UrlShortenerConfig::Yourls { signature, .. } => take("signature", signature),
+            UrlShortenerConfig::Shlink { api_key, .. } => take("api_key", api_key),
+            // 请求头中通常包含令牌，全部作为密钥保存
+            UrlShortenerConfig::Custom { headers, .. } => {
+                for (name, value) in headers.iter_mut() {
+                    take(&format!("header:{}", name), value);
+                }
+            }
+        }
+
+        secrets
+    }
+
+    /// 只填充为空的密钥字段，用于保存不包含密钥的配置时保留已保存的密钥
+    pub fn fill_empty_secrets(&mut self, secrets: &HashMap<String, String>) {
+        let fill = |name: &str, value: &mut String| {
+            if value.is_empty() {
+                if let Some(secret) = secrets.get(name) {
+                    *value = secret.clone();
+                }
+            }
+        };
+
+        match self {
+            UrlShortenerConfig::Yourls { signature, .. } => fill("signature", signature),
+            UrlShortenerConfig::Shlink { api_key, .. } => fill("api_key", api_key),
+            // 只处理配置中仍然存在的请求头，已删除的请求头不再恢复
+            UrlShortenerConfig::Custom { headers, .. } => {
+                for (name, value) in headers.iter_mut() {
+                    fill(&format!("header:{}", name), value);
+                }
+            }
+        }
+    }
+
+    /// 将单独保存的密钥填回配置
+    pub fn fill_secrets(&mut self, secrets: &HashMap<String, String>) {
+        let fill = |name: &str, value: &mut String| {
+            if let Some(secret) = secrets.get(name) {
+                *value = secret.clone();
+            }
+        };
+
+        match self {
+            UrlShortenerConfig::Yourls { signature, .. } => fill("signature", signature),
+            UrlShortenerConfig::Shlink { api_key, .. } => fill("api_key", api_key),
+            UrlShortenerConfig::Custom { headers, .. } => {
+                for (name, value) in secrets {
+                    if let Some(header_name) = name.strip_prefix("header:") {
+                        headers.insert(header_name.to_string(), value.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 短链接配置，与上传配置一样保存在凭据库中，通过 id 选择
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlShortenerProfile {
+    pub id: String,
+    pub name: String,
+    pub shortener: UrlShortenerConfig,
+}
+
+/// 复制到剪贴板的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UploadUrlFormat {
+    Raw,
+    Markdown,
+    Html,
+    BbCode,
+}
+
+impl UploadUrlFormat {
+    /// 按格式生成文本，图片使用图片语法
+    pub fn format(&self, url: &str, filename: &str, content_type: Option<&str>) -> String {
+        let is_image = content_type.is_some_and(|content_type| content_type.starts_with("image/"));
+
+        match self {
+            UploadUrlFormat::Raw => url.to_string(),
+            UploadUrlFormat::Markdown => {
+                let alt = filename.replace(['[', ']'], "");
+                let url = url.replace(' ', "%20").replace(')', "%29");
+                if is_image {
+                    format!("![{}]({})", alt, url)
+                } else {
+                    format!("[{}]({})", alt, url)
+                }
+            }
+            UploadUrlFormat::Html => {
+                let url = escape_html(url);
+                let filename = escape_html(filename);
+                if is_image {
+                    format!("<img src=\"{}\" alt=\"{}\">", url, filename)
+                } else {
+                    format!("<a href=\"{}\">{}</a>", url, filename)
+                }
+            }
+            UploadUrlFormat::BbCode => {
+                if is_image {
+                    format!("[img]{}[/img]", url)
+                } else {
+                    format!("[url={}]{}[/url]", url, filename)
+                }
+            }
+        }
+    }
+}
+
+/// 上传完成后执行的操作，按 缩短链接 -> 复制 -> 通知 的顺序执行
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostUploadOptions {
+    /// 短链接配置的 id，密钥从凭据库读取，不随任务保存
+    pub shortener_id: Option<String>,
+    /// 为空时不复制
+    pub copy_format: Option<UploadUrlFormat>,
+    pub notification: bool,
+}
+
+/// 上传后处理的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostUploadResult {
+    /// 上传返回的地址
+    pub url: String,
+    pub short_url: Option<String>,
+    /// 复制到剪贴板的文本
+    pub copied_text: Option<String>,
+    /// 执行失败的操作，不影响其它操作
+    pub errors: Vec<String>,
+}
+
+impl PostUploadResult {
+    /// 优先使用短链接
+    pub fn get_final_url(&self) -> &str {
+        self.short_url.as_deref().unwrap_or(&self.url)
+    }
+}
+
+pub struct UrlShortener {
+    config: UrlShortenerConfig,
+    client: reqwest::Client,
+}
+
+impl UrlShortener {
+    pub fn new(config: UrlShortenerConfig) -> Result<Self> {
+        Ok(Self {
+            config,
+            client: create_http_client()?,
+        })
+    }
+
+    pub async fn shorten(&self, url: &str) -> Result<String> {
+        info!("Shorten url: {}", url);
+
+        let short_url = match &self.config {
+            UrlShortenerConfig::Yourls { api_url, signature } => {
+                let response: Value = self
+                    .client
+                    .get(api_url)
+                    .query(&[
+                        ("signature", signature.as_str()),
+                        ("action", "shorturl"),
+                        ("format", "json"),
+                        ("url", url),
+                    ])
+                    .send()
+                    .await
+                    .context("Send YOURLS request failed")?
+                    .json()
+                    .await
+                    .context("Parse YOURLS response failed")?;
+
+                // 链接已存在时 YOURLS 返回错误状态，但同样包含 shorturl
+                match get_json_path_string(&response, "shorturl") {
+                    Some(short_url) => short_url,
+                    None => bail!(
+                        "YOURLS shorten failed: {}",
+                        get_json_path_string(&response, "message").unwrap_or_default()
+                    ),
+                }
+            }
+            UrlShortenerConfig::Shlink {
+                api_url,
+                api_key,
+                domain,
+            } => {
+                let mut body = json!({ "longUrl": url, "findIfExists": true });
+                if let Some(domain) = domain {
+                    body["domain"] = json!(domain);
+                }
+
+                let response = self
+                    .client
+                    .post(join_url(api_url, "rest/v3/short-urls"))
+                    .header("X-Api-Key", api_key)
+                    .json(&body)
+                    .send()
+                    .await
+                    .context("Send Shlink request failed")?;
+                let status = response.status();
+                let response: Value = response
+                    .json()
+                    .await
+                    .context("Parse Shlink response failed")?;
+                if !status.is_success() {
+                    bail!(
+                        "Shlink shorten failed with status {}: {}",
+                        status,
+                        get_json_path_string(&response, "detail").unwrap_or_default()
+                    );
+                }
+
+                get_json_path_string(&response, "shortUrl")
+                    .context("Shlink response has no shortUrl")?
+            }
+            UrlShortenerConfig::Custom {
+                url: api_url,
+                url_param_name,
+                headers,
+                url_json_path,
+            } => {
+                let mut request = self
+                    .client
+                    .get(api_url)
+                    .query(&[(url_param_name.as_deref().unwrap_or("url"), url)]);
+                for (name, value) in headers {
+                    request = request.header(name, value);
+                }
+
+                let response = request
+                    .send()
+                    .await
+                    .context("Send shortener request failed")?
+                    .error_for_status()
+                    .context("Shortener request failed")?
+                    .text()
+                    .await
+                    .context("Read shortener response failed")?;
+
+                match url_json_path {
+                    Some(path) => {
+                        let response: Value = serde_json::from_str(&response)
+                            .context("Parse shortener response failed")?;
+                        get_json_path_string(&response, path)
+                            .with_context(|| format!("Shortener response has no {}", path))?
+                    }
+                    None => response.trim().to_string(),
+                }
+            }
+        };
+
+        if short_url.is_empty() {
+            bail!("Shortener returned an empty url");
+        }
+
+        Ok(short_url)
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// 模拟短链接服务，按路径返回结果，并记录收到的请求行和请求头
+    async fn start_server() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let server_requests = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let requests = server_requests.clone();
+
+                tokio::spawn(async move {
+                    let mut reader = BufReader::new(stream);
+                    let mut request = String::new();
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).await.unwrap();
+                        if line.trim().is_empty() {
+                            break;
+                        }
+
+                        if let Some(value) =
+                            line.to_ascii_lowercase().strip_prefix("content-length: ")
+                        {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                        request.push_str(&line);
+                    }
+                    let mut body = vec![0; content_length];
+                    reader.read_exact(&mut body).await.unwrap();
+                    request.push_str(&String::from_utf8_lossy(&body));
+
+                    let path = request.split_whitespace().nth(1).unwrap_or_default();
+                    let body = if path.starts_with("/yourls-api.php") {
+                        r#"{"status":"fail","message":"already exists","shorturl":"https://sho.rt/yourls"}"#
+                    } else if path.starts_with("/rest/v3/short-urls") {
+                        r#"{"shortUrl":"https://s.example.com/shlink"}"#
+                    } else {
+                        "https://c.example.com/custom\n"
+                    };
+                    requests.lock().unwrap().push(request);
+
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    reader
+                        .get_mut()
+                        .write_all(response.as_bytes())
+                        .await
+                        .unwrap();
+                });
+            }
+        });
+
+        (format!("http://{}", address), requests)
+    }
+
+    #[tokio::test]
+    async fn test_shorten() {
+        let (server_url, requests) = start_server().await;
+        let long_url = "https://cdn.example.com/a.png";
+
+        let shortener = UrlShortener::new(UrlShortenerConfig::Yourls {
+            api_url: format!("{}/yourls-api.php", server_url),
+            signature: "signature".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            shortener.shorten(long_url).await.unwrap(),
+            "https://sho.rt/yourls"
+        );
+
+        let shortener = UrlShortener::new(UrlShortenerConfig::Shlink {
+            api_url: server_url.clone(),
+            api_key: "api-key".to_string(),
+            domain: Some("s.example.com".to_string()),
+        })
+        .unwrap();
+        assert_eq!(
+            shortener.shorten(long_url).await.unwrap(),
+            "https://s.example.com/shlink"
+        );
+
+        let shortener = UrlShortener::new(UrlShortenerConfig::Custom {
+            url: format!("{}/custom", server_url),
+            url_param_name: Some("long".to_string()),
+            headers: HashMap::from([("Authorization".to_string(), "Bearer token".to_string())]),
+            url_json_path: None,
+        })
+        .unwrap();
+        assert_eq!(
+            shortener.shorten(long_url).await.unwrap(),
+            "https://c.example.com/custom"
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("GET /yourls-api.php?signature=signature&action=shorturl"));
+        assert!(requests[1].starts_with("POST /rest/v3/short-urls"));
+        assert!(requests[1]
+            .to_ascii_lowercase()
+            .contains("x-api-key: api-key"));
+        assert!(requests[1].contains(r#""domain":"s.example.com""#));
+        assert!(requests[2].starts_with("GET /custom?long=https%3A%2F%2Fcdn.example.com%2Fa.png"));
+        assert!(requests[2]
+            .to_ascii_lowercase()
+            .contains("authorization: bearer token"));
+    }
+
+    #[test]
+    fn test_format_url() {
+        let url = "https://cdn.example.com/a b.png";
+
+        assert_eq!(UploadUrlFormat::Raw.format(url, "a b.png", None), url);
+        assert_eq!(
+            UploadUrlFormat::Markdown.format(url, "a b.png", Some("image/png")),
+            "![a b.png](https://cdn.example.com/a%20b.png)"
+        );
+        assert_eq!(
+            UploadUrlFormat::Html.format("https://x.com/?a=1&b=2", "a.png", Some("image/png")),
+            "<img src=\"https://x.com/?a=1&amp;b=2\" alt=\"a.png\">"
+        );
+        assert_eq!(
+            UploadUrlFormat::BbCode.format(url, "a b.png", Some("image/png")),
+            "[img]https://cdn.example.com/a b.png[/img]"
+        );
+        assert_eq!(
+            UploadUrlFormat::BbCode.format("https://x.com/a.txt", "a.txt", Some("text/plain")),
+            "[url=https://x.com/a.txt]a.txt[/url]"
+        );
+    }
+}
@@ -1,8 +1,36 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
 use anyhow::{Context, Result};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use s3::creds::Credentials;
+use s3::serde_types::Part;
 use s3::{Bucket, Region};
 use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::upload_destination::UploadStatusError;
+
+/// S3 要求除最后一个分片外，每个分片至少 5MB
+const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+/// S3 单次分片上传最多 10000 个分片
+const MAX_PART_COUNT: u64 = 10000;
+/// 预签名地址的最长有效期为 7 天
+const MAX_PRESIGN_EXPIRY_SECS: u32 = 7 * 24 * 60 * 60;
+
+/// 分享参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3ShareOptions {
+    /// 返回预签名地址的有效期，秒，为空时返回公开地址，私有存储桶需要设置
+    pub presign_expiry_secs: Option<u32>,
+    /// 上传时设置的 Content-Disposition，例如: inline; filename="image.png"
+    pub content_disposition: Option<String>,
+    /// 上传时设置的 Cache-Control，例如: public, max-age=31536000
+    pub cache_control: Option<String>,
+}
 
 /// S3 配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +51,9 @@ pub struct S3Config {
     /// true: https://endpoint/bucket/key
     /// false: https://bucket.endpoint/key
     pub force_path_style: bool,
+    /// 分享参数
+    #[serde(default)]
+    pub share_options: S3ShareOptions,
 }
 
 impl Default for S3Config {
@@ -35,13 +66,118 @@ impl Default for S3Config {
             bucket: String::new(),
             path_prefix: None,
             force_path_style: false,
+            share_options: S3ShareOptions::default(),
         }
     }
 }
 
+/// 分片上传参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3MultipartOptions {
+    /// 超过该大小时使用分片上传，字节
+    pub multipart_threshold: u64,
+    /// 分片大小，字节，最小 5MB
+    pub part_size: u64,
+    /// 单个分片失败后的最大重试次数
+    pub max_retries: u32,
+    /// 首次重试的等待时间，之后每次翻倍
+    pub retry_backoff_ms: u64,
+}
+
+impl Default for S3MultipartOptions {
+    fn default() -> Self {
+        Self {
+            multipart_threshold: 16 * 1024 * 1024,
+            part_size: 8 * 1024 * 1024,
+            max_retries: 3,
+            retry_backoff_ms: 500,
+        }
+    }
+}
+
+impl S3MultipartOptions {
+    /// 根据文件大小计算实际的分片大小，保证分片数量不超过上限
+    fn get_part_size(&self, total_size: u64) -> u64 {
+        self.part_size
+            .max(MIN_PART_SIZE)
+            .max(total_size.div_ceil(MAX_PART_COUNT))
+    }
+
+    fn get_retry_backoff(&self, attempt: u32) -> Duration {
+        Duration::from_millis(self.retry_backoff_ms.saturating_mul(1 << attempt.min(16)))
+    }
+}
+
+/// 已上传的分片
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct S3UploadedPart {
+    pub part_number: u32,
+    pub etag: String,
+}
+
+/// 分片上传的状态，保存后可用于断点续传
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3MultipartUploadState {
+    pub upload_id: String,
+    /// 完整的对象键名，包含路径前缀
+    pub key: String,
+    pub part_size: u64,
+    pub total_size: u64,
+    pub completed_parts: Vec<S3UploadedPart>,
+}
+
+/// 上传进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3UploadProgress {
+    pub uploaded_bytes: u64,
+    pub total_bytes: u64,
+    pub state: S3MultipartUploadState,
+}
+
+/// 分片上传的数据来源
+enum UploadSource<'a> {
+    Bytes(&'a [u8]),
+    File(&'a Path),
+}
+
+impl UploadSource<'_> {
+    async fn read_part(&self, offset: u64, size: u64) -> Result<Vec<u8>> {
+        match self {
+            UploadSource::Bytes(data) => {
+                Ok(data[offset as usize..(offset + size) as usize].to_vec())
+            }
+            UploadSource::File(path) => {
+                let mut file = tokio::fs::File::open(path)
+                    .await
+                    .with_context(|| format!("Open file failed: {}", path.display()))?;
+                file.seek(std::io::SeekFrom::Start(offset)).await?;
+
+                let mut buffer = vec![0; size as usize];
+                file.read_exact(&mut buffer)
+                    .await
+                    .context("Read file part failed")?;
+                Ok(buffer)
+            }
+        }
+    }
+}
+
+/// 按分片大小切分，返回 (分片编号, 偏移, 大小)
+fn get_part_ranges(total_size: u64, part_size: u64) -> Vec<(u32, u64, u64)> {
+    (0..total_size.div_ceil(part_size).max(1))
+        .map(|index| {
+            let offset = index * part_size;
+            (index as u32 + 1, offset, part_size.min(total_size - offset))
+        })
+        .collect()
+}
+
 /// S3 上传服务
 pub struct S3Service {
     bucket: Box<Bucket>,
+    /// 上传使用的存储桶，附带对象的元数据请求头
+    upload_bucket: Box<Bucket>,
     config: S3Config,
 }
 
@@ -110,10 +246,20 @@ impl S3Service {
             bucket = bucket.with_path_style();
         }
 
+        // 元数据请求头只用于上传，避免影响预签名地址的签名
+        let mut upload_bucket = bucket.clone();
+        if let Some(content_disposition) = &config.share_options.content_disposition {
+            upload_bucket.add_header("Content-Disposition", content_disposition);
+        }
+        if let Some(cache_control) = &config.share_options.cache_control {
+            upload_bucket.add_header("Cache-Control", cache_control);
+        }
+
         info!("S3 client initialized successfully");
 
         Ok(Self {
             bucket,
+            upload_bucket,
             config: config.clone(),
         })
     }
@@ -133,12 +279,25 @@ impl S3Service {
         object_key: String,
         content_type: Option<String>,
     ) -> Result<String> {
+        // 大文件使用分片上传
+        let options = S3MultipartOptions::default();
+        if data.len() as u64 > options.multipart_threshold {
+            return self
+                .upload_multipart(
+                    UploadSource::Bytes(data),
+                    data.len() as u64,
+                    &object_key,
+                    content_type,
+                    &options,
+                    None,
+                    &AtomicBool::new(false),
+                    |_| {},
+                )
+                .await;
+        }
+
         // 添加路径前缀
-        let full_key = if let Some(prefix) = &self.config.path_prefix {
-            format!("{}{}", prefix, object_key)
-        } else {
-            object_key
-        };
+        let full_key = self.get_full_key(&object_key);
 
         info!(
             "Start uploading data to: {}/{}",
@@ -151,7 +310,7 @@ impl S3Service {
 
         // 上传到 S3
         let response = self
-            .bucket
+            .upload_bucket
             .put_object_with_content_type(&full_key, &data, &ct)
             .await
             .context("Upload data to S3 failed")?;
@@ -161,19 +320,252 @@ impl S3Service {
                 "Upload failed, HTTP status code: {}",
                 response.status_code()
             );
-            anyhow::bail!(
-                "Upload failed, HTTP status code: {}",
-                response.status_code()
-            );
+            return Err(UploadStatusError {
+                status: response.status_code(),
+                message: format!(
+                    "Upload failed, HTTP status code: {}",
+                    response.status_code()
+                ),
+            }
+            .into());
         }
 
         // 构建访问 URL
-        let url = self.build_url(&full_key);
+        let url = self.get_object_url(&full_key).await?;
         info!("Data uploaded successfully: {}", url);
 
         Ok(url)
     }
 
+    /// 上传本地文件到 S3，超过阈值时使用分片上传
+    ///
+    /// # 参数
+    /// * `file_path` - 本地文件
+    /// * `object_key` - S3 对象键名
+    /// * `content_type` - MIME 类型（可选）
+    /// * `options` - 分片上传参数
+    /// * `resume_state` - 上次中断时的上传状态，用于断点续传
+    /// * `cancel` - 置为 true 后中止上传
+    /// * `on_progress` - 每个分片完成后回调
+    ///
+    /// # 返回
+    /// 上传成功后的 URL
+    pub async fn upload_file(
+        &self,
+        file_path: &Path,
+        object_key: String,
+        content_type: Option<String>,
+        options: &S3MultipartOptions,
+        resume_state: Option<S3MultipartUploadState>,
+        cancel: &AtomicBool,
+        on_progress: impl FnMut(S3UploadProgress),
+    ) -> Result<String> {
+        let total_size = tokio::fs::metadata(file_path)
+            .await
+            .with_context(|| format!("Read file metadata failed: {}", file_path.display()))?
+            .len();
+
+        if total_size <= options.multipart_threshold && resume_state.is_none() {
+            let data = tokio::fs::read(file_path)
+                .await
+                .with_context(|| format!("Read file failed: {}", file_path.display()))?;
+            return self.upload_bytes(&data, object_key, content_type).await;
+        }
+
+        self.upload_multipart(
+            UploadSource::File(file_path),
+            total_size,
+            &object_key,
+            content_type,
+            options,
+            resume_state,
+            cancel,
+            on_progress,
+        )
+        .await
+    }
+
+    async fn upload_multipart(
+        &self,
+        source: UploadSource<'_>,
+        total_size: u64,
+        object_key: &str,
+        content_type: Option<String>,
+        options: &S3MultipartOptions,
+        resume_state: Option<S3MultipartUploadState>,
+        cancel: &AtomicBool,
+        mut on_progress: impl FnMut(S3UploadProgress),
+    ) -> Result<String> {
+        let full_key = self.get_full_key(object_key);
+        let ct = content_type.unwrap_or_else(|| "application/octet-stream".to_string());
+
+        // 只有对象、大小和分片大小都一致时才能续传
+        let mut state = match resume_state.filter(|state| {
+            state.key == full_key
+                && state.total_size == total_size
+                && state.part_size == options.get_part_size(total_size)
+        }) {
+            Some(state) => {
+                info!(
+                    "Resume multipart upload: {}/{}, upload id: {}",
+                    self.config.bucket, full_key, state.upload_id
+                );
+                state
+            }
+            None => {
+                let response = self
+                    .upload_bucket
+                    .initiate_multipart_upload(&full_key, &ct)
+                    .await
+                    .context("Initiate multipart upload failed")?;
+                info!(
+                    "Start multipart upload: {}/{}, upload id: {}",
+                    self.config.bucket, full_key, response.upload_id
+                );
+
+                S3MultipartUploadState {
+                    upload_id: response.upload_id,
+                    key: full_key.clone(),
+                    part_size: options.get_part_size(total_size),
+                    total_size,
+                    completed_parts: Vec::new(),
+                }
+            }
+        };
+
+        let part_ranges = get_part_ranges(total_size, state.part_size);
+        for (part_number, offset, size) in part_ranges {
+            if state
+                .completed_parts
+                .iter()
+                .any(|part| part.part_number == part_number)
+            {
+                continue;
+            }
+
+            let chunk = source.read_part(offset, size).await?;
+
+            let mut attempt = 0;
+            let part = loop {
+                if cancel.load(Ordering::Relaxed) {
+                    self.abort_multipart_upload(&state).await;
+                    anyhow::bail!("Upload cancelled");
+                }
+
+                match self
+                    .upload_bucket
+                    .put_multipart_chunk(
+                        chunk.clone(),
+                        &full_key,
+                        part_number,
+                        &state.upload_id,
+                        &ct,
+                    )
+                    .await
+                {
+                    Ok(part) => break part,
+                    Err(e) if attempt < options.max_retries => {
+                        let backoff = options.get_retry_backoff(attempt);
+                        warn!(
+                            "Upload part {} failed, retry after {:?}: {}",
+                            part_number, backoff, e
+                        );
+                        tokio::time::sleep(backoff).await;
+                        attempt += 1;
+                    }
+                    Err(e) => {
+                        error!("Upload part {} failed: {}", part_number, e);
+                        return Err(e)
+                            .with_context(|| format!("Upload part {} failed", part_number));
+                    }
+                }
+            };
+
+            state.completed_parts.push(S3UploadedPart {
+                part_number: part.part_number,
+                etag: part.etag,
+            });
+
+            let uploaded_bytes = state
+                .completed_parts
+                .iter()
+                .map(|part| {
+                    (state.total_size - (part.part_number as u64 - 1) * state.part_size)
+                        .min(state.part_size)
+                })
+                .sum();
+            on_progress(S3UploadProgress {
+                uploaded_bytes,
+                total_bytes: total_size,
+                state: state.clone(),
+            });
+        }
+
+        let mut parts: Vec<Part> = state
+            .completed_parts
+            .iter()
+            .map(|part| Part {
+                part_number: part.part_number,
+                etag: part.etag.clone(),
+            })
+            .collect();
+        parts.sort_by_key(|part| part.part_number);
+
+        let response = self
+            .upload_bucket
+            .complete_multipart_upload(&full_key, &state.upload_id, parts)
+            .await
+            .context("Complete multipart upload failed")?;
+        if response.status_code() != 200 {
+            return Err(UploadStatusError {
+                status: response.status_code(),
+                message: format!(
+                    "Complete multipart upload failed, HTTP status code: {}",
+                    response.status_code()
+                ),
+            }
+            .into());
+        }
+
+        let url = self.get_object_url(&full_key).await?;
+        info!("Multipart upload finished: {}", url);
+
+        Ok(url)
+    }
+
+    /// 中止分片上传，释放已上传的分片
+    async fn abort_multipart_upload(&self, state: &S3MultipartUploadState) {
+        info!("Abort multipart upload, upload id: {}", state.upload_id);
+
+        if let Err(e) = self.bucket.abort_upload(&state.key, &state.upload_id).await {
+            warn!("Abort multipart upload failed: {}", e);
+        }
+    }
+
+    /// 删除对象
+    ///
+    /// # 参数
+    /// * `full_key` - 完整的对象键名，包含路径前缀
+    pub async fn delete_object(&self, full_key: &str) -> Result<()> {
+        info!("Delete object: {}/{}", self.config.bucket, full_key);
+
+        let response = self
+            .bucket
+            .delete_object(full_key)
+            .await
+            .context("Delete object failed")?;
+
+        // S3 删除不存在的对象同样返回 204
+        if !(200..300).contains(&response.status_code()) {
+            anyhow::bail!(
+                "Delete object failed, HTTP status code: {}",
+                response.status_code()
+            );
+        }
+
+        Ok(())
+    }
+
     /// 获取对象的预签名 URL
     ///
     /// # 参数
@@ -184,24 +576,62 @@ impl S3Service {
     /// 预签名 URL
     pub async fn get_presigned_url(&self, object_key: &str, expiry_secs: u32) -> Result<String> {
         // 添加路径前缀
-        let full_key = if let Some(prefix) = &self.config.path_prefix {
-            format!("{}{}", prefix, object_key)
-        } else {
-            object_key.to_string()
-        };
+        let full_key = self.get_full_key(object_key);
 
         info!(
             "Generate presigned URL: {}/{}, expiry time: {} seconds",
             self.config.bucket, full_key, expiry_secs
         );
 
-        let url = self
-            .bucket
-            .presign_get(&full_key, expiry_secs, None)
+        self.presign_get(&full_key, expiry_secs).await
+    }
+
+    /// 生成预签名地址，下载时的响应头使用分享参数覆盖
+    async fn presign_get(&self, full_key: &str, expiry_secs: u32) -> Result<String> {
+        if expiry_secs == 0 || expiry_secs > MAX_PRESIGN_EXPIRY_SECS {
+            anyhow::bail!(
+                "Invalid presigned URL expiry time: {} seconds, must be between 1 and {}",
+                expiry_secs,
+                MAX_PRESIGN_EXPIRY_SECS
+            );
+        }
+
+        let mut custom_queries = HashMap::new();
+        if let Some(content_disposition) = &self.config.share_options.content_disposition {
+            custom_queries.insert(
+                "response-content-disposition".to_string(),
+                content_disposition.clone(),
+            );
+        }
+        if let Some(cache_control) = &self.config.share_options.cache_control {
+            custom_queries.insert("response-cache-control".to_string(), cache_control.clone());
+        }
+
+        self.bucket
+            .presign_get(
+                full_key,
+                expiry_secs,
+                (!custom_queries.is_empty()).then_some(custom_queries),
+            )
             .await
-            .context("Generate presigned URL failed")?;
+            .context("Generate presigned URL failed")
+    }
 
-        Ok(url)
+    /// 上传完成后返回的地址，设置了有效期时返回预签名地址
+    async fn get_object_url(&self, full_key: &str) -> Result<String> {
+        match self.config.share_options.presign_expiry_secs {
+            Some(expiry_secs) => self.presign_get(full_key, expiry_secs).await,
+            None => Ok(self.build_url(full_key)),
+        }
+    }
+
+    /// 添加路径前缀
+    pub fn get_full_key(&self, object_key: &str) -> String {
+        if let Some(prefix) = &self.config.path_prefix {
+            format!("{}{}", prefix, object_key)
+        } else {
+            object_key.to_string()
+        }
     }
 
     /// 构建访问 URL
@@ -236,3 +666,127 @@ impl S3Service {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_get_part_ranges() {
+        assert_eq!(
+            get_part_ranges(12 * MB, 5 * MB),
+            vec![(1, 0, 5 * MB), (2, 5 * MB, 5 * MB), (3, 10 * MB, 2 * MB)]
+        );
+        assert_eq!(get_part_ranges(0, 5 * MB), vec![(1, 0, 0)]);
+    }
+
+    #[test]
+    fn test_get_part_size() {
+        let options = S3MultipartOptions {
+            part_size: MB,
+            ..Default::default()
+        };
+        assert_eq!(options.get_part_size(100 * MB), MIN_PART_SIZE);
+        assert_eq!(
+            options.get_part_size(100_000 * MB),
+            (100_000 * MB).div_ceil(MAX_PART_COUNT)
+        );
+        assert_eq!(options.get_retry_backoff(2), Duration::from_millis(2000));
+    }
+
+    #[tokio::test]
+    async fn test_presigned_url() {
+        let service = S3Service::new(S3Config {
+            endpoint: "http://127.0.0.1:9000".to_string(),
+            access_key_id: "access".to_string(),
+            secret_access_key: "secret".to_string(),
+            bucket: "private".to_string(),
+            force_path_style: true,
+            share_options: S3ShareOptions {
+                presign_expiry_secs: Some(3600),
+                content_disposition: Some("inline".to_string()),
+                cache_control: None,
+            },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let url = service.get_object_url("image.png").await.unwrap();
+        assert!(url.starts_with("http://127.0.0.1:9000/private/image.png?"));
+        assert!(url.contains("X-Amz-Expires=3600"));
+        assert!(url.contains("response-content-disposition=inline"));
+
+        assert!(service.get_presigned_url("image.png", 0).await.is_err());
+        assert!(service
+            .get_presigned_url("image.png", MAX_PRESIGN_EXPIRY_SECS + 1)
+            .await
+            .is_err());
+    }
+
+    /// 需要本地的 S3 兼容服务，例如:
+    /// docker run -p 9000:9000 minio/minio server /data
+    /// 并创建 snow-shot-test 存储桶，运行: cargo test -p snow-shot-http-services -- --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn test_multipart_upload_with_minio() {
+        let service = S3Service::new(S3Config {
+            endpoint: std::env::var("S3_TEST_ENDPOINT")
+                .unwrap_or_else(|_| "http://127.0.0.1:9000".to_string()),
+            region: "us-east-1".to_string(),
+            access_key_id: std::env::var("S3_TEST_ACCESS_KEY_ID")
+                .unwrap_or_else(|_| "minioadmin".to_string()),
+            secret_access_key: std::env::var("S3_TEST_SECRET_ACCESS_KEY")
+                .unwrap_or_else(|_| "minioadmin".to_string()),
+            bucket: "snow-shot-test".to_string(),
+            path_prefix: Some("multipart/".to_string()),
+            force_path_style: true,
+            share_options: S3ShareOptions::default(),
+        })
+        .await
+        .unwrap();
+
+        let file_path = std::env::temp_dir().join("snow-shot-multipart-test.bin");
+        let data: Vec<u8> = (0..12 * MB).map(|index| (index % 251) as u8).collect();
+        tokio::fs::write(&file_path, &data).await.unwrap();
+
+        let options = S3MultipartOptions {
+            multipart_threshold: MB,
+            part_size: 5 * MB,
+            ..Default::default()
+        };
+        let mut progress_list = Vec::new();
+        let url = service
+            .upload_file(
+                &file_path,
+                "test.bin".to_string(),
+                None,
+                &options,
+                None,
+                &AtomicBool::new(false),
+                |progress| progress_list.push(progress.uploaded_bytes),
+            )
+            .await
+            .unwrap();
+
+        assert!(url.ends_with("/snow-shot-test/multipart/test.bin"));
+        assert_eq!(progress_list, vec![5 * MB, 10 * MB, 12 * MB]);
+
+        let result = service
+            .upload_file(
+                &file_path,
+                "cancelled.bin".to_string(),
+                None,
+                &options,
+                None,
+                &AtomicBool::new(true),
+                |_| {},
+            )
+            .await;
+        assert!(result.is_err());
+
+        let _ = tokio::fs::remove_file(&file_path).await;
+    }
+}
@@ -0,0 +1,292 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// 默认的翻译提示词，要求模型按行返回 JSON 数组
+const DEFAULT_SYSTEM_PROMPT: &str = "You are a translation engine. Translate each item of the JSON array from the user from {source_language} into {target_language}. Keep the array length and order unchanged, do not merge or split items, and reply with a JSON array of strings only.";
+
+/// 翻译后端配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum TranslationBackendConfig {
+    /// LibreTranslate 及兼容接口，可以部署在本地
+    LibreTranslate {
+        /// 服务地址，例如: http://127.0.0.1:5000
+        endpoint: String,
+        api_key: Option<String>,
+    },
+    /// OpenAI 兼容的 Chat Completions 接口，例如 Ollama、LM Studio
+    OpenAiCompatible {
+        /// 服务地址，例如: http://127.0.0.1:11434/v1
+        endpoint: String,
+        api_key: Option<String>,
+        model: String,
+        /// 自定义提示词，`{source_language}` 和 `{target_language}` 会被替换为源语言和目标语言
+        system_prompt: Option<String>,
+    },
+}
+
+/// 翻译服务
+pub struct TranslationService {
+    client: reqwest::Client,
+    config: TranslationBackendConfig,
+}
+
+impl TranslationService {
+    pub fn new(config: TranslationBackendConfig, timeout_secs: Option<u64>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(timeout_secs.unwrap_or(60)))
+            .build()
+            .context("Create HTTP client failed")?;
+
+        Ok(Self { client, config })
+    }
+
+    /// 按行翻译，返回的结果与输入一一对应
+    ///
+    /// # 参数
+    /// * `lines` - 待翻译的文本
+    /// * `source_language` - 源语言，为空时自动检测
+    /// * `target_language` - 目标语言
+    pub async fn translate(
+        &self,
+        lines: &[String],
+        source_language: Option<&str>,
+        target_language: &str,
+    ) -> Result<Vec<String>> {
+        if lines.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        info!(
+            "Start translating {} lines to {}",
+            lines.len(),
+            target_language
+        );
+
+        let translated_lines = match &self.config {
+            TranslationBackendConfig::LibreTranslate { endpoint, api_key } => {
+                self.translate_with_libre_translate(
+                    endpoint,
+                    api_key.as_deref(),
+                    lines,
+                    source_language,
+                    target_language,
+                )
+                .await?
+            }
+            TranslationBackendConfig::OpenAiCompatible {
+                endpoint,
+                api_key,
+                model,
+                system_prompt,
+            } => {
+                self.translate_with_openai_compatible(
+                    endpoint,
+                    api_key.as_deref(),
+                    model,
+                    system_prompt.as_deref(),
+                    lines,
+                    source_language,
+                    target_language,
+                )
+                .await?
+            }
+        };
+
+        if translated_lines.len() != lines.len() {
+            anyhow::bail!(
+                "Translated line count mismatch, expected {}, got {}",
+                lines.len(),
+                translated_lines.len()
+            );
+        }
+
+        Ok(translated_lines)
+    }
+
+    async fn translate_with_libre_translate(
+        &self,
+        endpoint: &str,
+        api_key: Option<&str>,
+        lines: &[String],
+        source_language: Option<&str>,
+        target_language: &str,
+    ) -> Result<Vec<String>> {
+        let url = format!("{}/translate", endpoint.trim_end_matches('/'));
+        debug!("LibreTranslate URL: {}", url);
+
+        let mut body = json!({
+            "q": lines,
+            "source": source_language.unwrap_or("auto"),
+            "target": target_language,
+            "format": "text",
+        });
+        if let Some(api_key) = api_key {
+            body["api_key"] = json!(api_key);
+        }
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .context("Request LibreTranslate failed")?;
+
+        let status = response.status();
+        let response_body: Value = response
+            .json()
+            .await
+            .context("Parse LibreTranslate response failed")?;
+        if !status.is_success() {
+            anyhow::bail!(
+                "LibreTranslate request failed, HTTP status code: {}, body: {}",
+                status,
+                response_body
+            );
+        }
+
+        // q 为数组时 translatedText 也为数组
+        match &response_body["translatedText"] {
+            Value::Array(items) => Ok(items
+                .iter()
+                .map(|item| item.as_str().unwrap_or_default().to_string())
+                .collect()),
+            Value::String(text) => Ok(vec![text.clone()]),
+            _ => anyhow::bail!("Invalid LibreTranslate response: {}", response_body),
+        }
+    }
+
+    async fn translate_with_openai_compatible(
+        &self,
+        endpoint: &str,
+        api_key: Option<&str>,
+        model: &str,
+        system_prompt: Option<&str>,
+        lines: &[String],
+        source_language: Option<&str>,
+        target_language: &str,
+    ) -> Result<Vec<String>> {
+        let url = format!("{}/chat/completions", endpoint.trim_end_matches('/'));
+        debug!("OpenAI compatible URL: {}", url);
+
+        let system_prompt = get_system_prompt(system_prompt, source_language, target_language);
+        let body = json!({
+            "model": model,
+            "temperature": 0,
+            "messages": [
+                { "role": "system", "content": system_prompt },
+                { "role": "user", "content": serde_json::to_string(lines)? },
+            ],
+        });
+
+        let mut request = self.client.post(&url).json(&body);
+        if let Some(api_key) = api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .context("Request OpenAI compatible endpoint failed")?;
+
+        let status = response.status();
+        let response_body: Value = response
+            .json()
+            .await
+            .context("Parse OpenAI compatible response failed")?;
+        if !status.is_success() {
+            anyhow::bail!(
+                "OpenAI compatible request failed, HTTP status code: {}, body: {}",
+                status,
+                response_body
+            );
+        }
+
+        let content = response_body["choices"][0]["message"]["content"]
+            .as_str()
+            .context("Missing message content in OpenAI compatible response")?;
+
+        parse_translated_lines(content, lines.len())
+    }
+}
+
+/// 替换提示词中的语言，未指定源语言时由模型自动检测
+fn get_system_prompt(
+    system_prompt: Option<&str>,
+    source_language: Option<&str>,
+    target_language: &str,
+) -> String {
+    system_prompt
+        .unwrap_or(DEFAULT_SYSTEM_PROMPT)
+        .replace(
+            "{source_language}",
+            source_language.unwrap_or("the detected source language"),
+        )
+        .replace("{target_language}", target_language)
+}
+
+/// 解析模型返回的 JSON 数组，兼容 Markdown 代码块包裹的情况
+///
+/// 无法解析为数组时按行拆分
+fn parse_translated_lines(content: &str, line_count: usize) -> Result<Vec<String>> {
+    if let (Some(start), Some(end)) = (content.find('['), content.rfind(']')) {
+        if start < end {
+            if let Ok(lines) = serde_json::from_str::<Vec<String>>(&content[start..=end]) {
+                return Ok(lines);
+            }
+        }
+    }
+
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with("```"))
+        .map(|line| line.to_string())
+        .collect();
+    if lines.len() == line_count {
+        return Ok(lines);
+    }
+
+    anyhow::bail!("Invalid translation content: {}", content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_translated_lines() {
+        assert_eq!(
+            parse_translated_lines("```json\n[\"你好\", \"世界\"]\n```", 2).unwrap(),
+            vec!["你好", "世界"]
+        );
+        assert_eq!(
+            parse_translated_lines("你好\n世界", 2).unwrap(),
+            vec!["你好", "世界"]
+        );
+        assert!(parse_translated_lines("你好", 2).is_err());
+    }
+
+    #[test]
+    fn test_get_system_prompt() {
+        let prompt = get_system_prompt(None, Some("ja"), "zh");
+        assert!(prompt.contains("from ja into zh"));
+
+        let prompt = get_system_prompt(None, None, "zh");
+        assert!(prompt.contains("from the detected source language into zh"));
+
+        assert_eq!(
+            get_system_prompt(
+                Some("{source_language} -> {target_language}"),
+                Some("en"),
+                "de"
+            ),
+            "en -> de"
+        );
+    }
+}
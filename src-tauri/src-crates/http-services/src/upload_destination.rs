@@ -0,0 +1,412 @@
+pub mod custom_http;
+pub mod gist;
+pub mod imgur;
+pub mod s3;
+pub mod sftp;
+pub mod webdav;
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::S3Config;
+
+/// 上传目标的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum UploadDestinationConfig {
+    S3(S3Config),
+    /// WebDAV，例如 Nextcloud: https://cloud.example.com/remote.php/dav/files/<user>/
+    WebDav {
+        endpoint: String,
+        username: String,
+        password: String,
+        /// 上传目录，例如: screenshots/，不存在时自动创建
+        path_prefix: Option<String>,
+        /// 对外访问的地址前缀，为空时返回 WebDAV 地址
+        public_url_prefix: Option<String>,
+    },
+    Sftp {
+        host: String,
+        port: Option<u16>,
+        username: String,
+        password: Option<String>,
+        /// 私钥文件，优先于密码
+        private_key_path: Option<String>,
+        passphrase: Option<String>,
+        /// 上传目录，例如: /var/www/images
+        remote_dir: String,
+        /// 对外访问的地址前缀，例如: https://example.com/images/
+        public_url_prefix: Option<String>,
+        /// 服务器公钥的 SHA256 指纹，例如: SHA256:nThbg6kXUpJWGl7E1IGOCspRomTxdCARLviKw6E5SY8
+        ///
+        /// 为空时使用 ~/.ssh/known_hosts 校验
+        host_key_fingerprint: Option<String>,
+    },
+    /// 自定义 HTTP 上传，兼容 ShareX 自定义上传器的常用配置
+    CustomHttp {
+        url: String,
+        /// POST 或 PUT，默认为 POST
+        method: Option<String>,
+        /// 为空时直接将文件作为请求体
+        file_field_name: Option<String>,
+        #[serde(default)]
+        form_fields: HashMap<String, String>,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        /// 返回 JSON 中地址的路径，例如: data.link，为空时使用整个响应文本
+        url_json_path: Option<String>,
+        /// 返回 JSON 中删除地址的路径
+        delete_url_json_path: Option<String>,
+    },
+    Imgur {
+        client_id: String,
+        /// 兼容 Imgur 的接口地址，默认为 https://api.imgur.com
+        api_url: Option<String>,
+    },
+    /// GitHub Gist 及兼容接口，只支持文本内容
+    Gist {
+        token: String,
+        /// 默认为 https://api.github.com
+        api_url: Option<String>,
+        #[serde(default)]
+        public: bool,
+    },
+}
+
+/// 上传配置，通过 id 选择
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadProfile {
+    pub id: String,
+    pub name: String,
+    pub destination: UploadDestinationConfig,
+}
+
+impl UploadDestinationConfig {
+    /// 上传目标类型，与序列化时的 type 一致
+    pub fn destination_type(&self) -> &'static str {
+        match self {
+            UploadDestinationConfig::S3(_) => "S3",
+            UploadDestinationConfig::WebDav { .. } => "WebDav",
+            UploadDestinationConfig::Sftp { .. } => "Sftp",
+            UploadDestinationConfig::CustomHttp { .. } => "CustomHttp",
+            UploadDestinationConfig::Imgur { .. } => "Imgur",
+            UploadDestinationConfig::Gist { .. } => "Gist",
+        }
+    }
+
+    /// 取出配置中的密钥字段并清空，用于单独保存
+    pub fn take_secrets(&mut self) -> HashMap<String, String> {
+        let mut secrets = HashMap::new();
+        let mut take = |name: &str, value: &mut String| {
+            if !value.is_empty() {
+                secrets.insert(name.to_string(), std::mem::take(value));
+            }
+        };
+
+        match self {
+            UploadDestinationConfig::S3(config) => {
+                take("access_key_id", &mut config.access_key_id);
+                take("secret_access_key", &mut config.secret_access_key);
+            }
+            UploadDestinationConfig::WebDav { password, .. } => take("password", password),
+            UploadDestinationConfig::Sftp {
+                password,
+                passphrase,
+                ..
+            } => {
+                if let Some(password) = password {
+                    take("password", password);
+                }
+                if let Some(passphrase) = passphrase {
+                    take("passphrase", passphrase);
+                }
+            }
+            // 请求头中通常包含令牌，全部作为密钥保存
+            UploadDestinationConfig::CustomHttp { headers, .. } => {
+                for (name, value) in headers.iter_mut() {
+                    take(&format!("header:{}", name), value);
+                }
+            }
+            UploadDestinationConfig::Imgur { client_id, .. } => take("client_id", client_id),
+            UploadDestinationConfig::Gist { token, .. } => take("token", token),
+        }
+
+        secrets
+    }
+
+    /// 只填充为空的密钥字段，用于保存不包含密钥的配置时保留已保存的密钥
+    pub fn fill_empty_secrets(&mut self, secrets: &HashMap<String, String>) {
+        let fill = |name: &str, value: &mut String| {
+            if value.is_empty() {
+                if let Some(secret) = secrets.get(name) {
+                    *value = secret.clone();
+                }
+            }
+        };
+
+        match self {
+            UploadDestinationConfig::S3(config) => {
+                fill("access_key_id", &mut config.access_key_id);
+                fill("secret_access_key", &mut config.secret_access_key);
+            }
+            UploadDestinationConfig::WebDav { password, .. } => fill("password", password),
+            UploadDestinationConfig::Sftp {
+                password,
+                passphrase,
+                ..
+            } => {
+                for (name, value) in [("password", password), ("passphrase", passphrase)] {
+                    if value.as_deref().unwrap_or_default().is_empty() {
+                        if let Some(secret) = secrets.get(name) {
+                            *value = Some(secret.clone());
+                        }
+                    }
+                }
+            }
+            // 只处理配置中仍然存在的请求头，已删除的请求头不再恢复
+            UploadDestinationConfig::CustomHttp { headers, .. } => {
+                for (name, value) in headers.iter_mut() {
+                    fill(&format!("header:{}", name), value);
+                }
+            }
+            UploadDestinationConfig::Imgur { client_id, .. } => fill("client_id", client_id),
+            UploadDestinationConfig::Gist { token, .. } => fill("token", token),
+        }
+    }
+
+    /// 将单独保存的密钥填回配置
+    pub fn fill_secrets(&mut self, secrets: &HashMap<String, String>) {
+        let fill = |name: &str, value: &mut String| {
+            if let Some(secret) = secrets.get(name) {
+                *value = secret.clone();
+            }
+        };
+
+        match self {
+            UploadDestinationConfig::S3(config) => {
+                fill("access_key_id", &mut config.access_key_id);
+                fill("secret_access_key", &mut config.secret_access_key);
+            }
+            UploadDestinationConfig::WebDav { password, .. } => fill("password", password),
+            UploadDestinationConfig::Sftp {
+                password,
+                passphrase,
+                ..
+            } => {
+                if let Some(secret) = secrets.get("password") {
+                    *password = Some(secret.clone());
+                }
+                if let Some(secret) = secrets.get("passphrase") {
+                    *passphrase = Some(secret.clone());
+                }
+            }
+            UploadDestinationConfig::CustomHttp { headers, .. } => {
+                for (name, value) in secrets {
+                    if let Some(header_name) = name.strip_prefix("header:") {
+                        headers.insert(header_name.to_string(), value.clone());
+                    }
+                }
+            }
+            UploadDestinationConfig::Imgur { client_id, .. } => fill("client_id", client_id),
+            UploadDestinationConfig::Gist { token, .. } => fill("token", token),
+        }
+    }
+}
+
+/// 上传服务返回的错误状态码，上传队列根据状态码判断是否可以重试
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct UploadStatusError {
+    pub status: u16,
+    pub message: String,
+}
+
+/// 上传结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadResult {
+    pub url: String,
+    /// 在目标中的路径或对象键名
+    pub remote_path: String,
+    /// 部分服务返回的删除地址
+    pub delete_url: Option<String>,
+}
+
+/// 上传目标
+///
+/// 每种上传服务实现该 trait，通过 `create_upload_destination` 根据配置创建
+#[async_trait]
+pub trait UploadDestination: Send + Sync {
+    /// 上传数据
+    ///
+    /// # 参数
+    /// * `data` - 字节数据
+    /// * `filename` - 文件名，可以包含子目录
+    /// * `content_type` - MIME 类型（可选）
+    async fn upload(
+        &self,
+        data: &[u8],
+        filename: &str,
+        content_type: Option<&str>,
+    ) -> Result<UploadResult>;
+
+    /// 删除已上传的文件
+    ///
+    /// # 参数
+    /// * `remote_path` - 上传结果中的路径
+    /// * `delete_url` - 上传结果中的删除地址
+    async fn delete(&self, remote_path: &str, delete_url: Option<&str>) -> Result<()>;
+}
+
+/// 根据配置创建上传目标
+pub fn create_upload_destination(
+    config: UploadDestinationConfig,
+) -> Result<Box<dyn UploadDestination>> {
+    Ok(match config {
+        UploadDestinationConfig::S3(config) => Box::new(s3::S3Destination::new(config)),
+        UploadDestinationConfig::WebDav {
+            endpoint,
+            username,
+            password,
+            path_prefix,
+            public_url_prefix,
+        } => Box::new(webdav::WebDavDestination::new(
+            endpoint,
+            username,
+            password,
+            path_prefix,
+            public_url_prefix,
+        )?),
+        UploadDestinationConfig::Sftp {
+            host,
+            port,
+            username,
+            password,
+            private_key_path,
+            passphrase,
+            remote_dir,
+            public_url_prefix,
+            host_key_fingerprint,
+        } => Box::new(sftp::SftpDestination {
+            host,
+            port: port.unwrap_or(22),
+            username,
+            password,
+            private_key_path,
+            passphrase,
+            remote_dir,
+            public_url_prefix,
+            host_key_fingerprint,
+        }),
+        UploadDestinationConfig::CustomHttp {
+            url,
+            method,
+            file_field_name,
+            form_fields,
+            headers,
+            url_json_path,
+            delete_url_json_path,
+        } => Box::new(custom_http::CustomHttpDestination::new(
+            url,
+            method,
+            file_field_name,
+            form_fields,
+            headers,
+            url_json_path,
+            delete_url_json_path,
+        )?),
+        UploadDestinationConfig::Imgur { client_id, api_url } => {
+            Box::new(imgur::ImgurDestination::new(client_id, api_url)?)
+        }
+        UploadDestinationConfig::Gist {
+            token,
+            api_url,
+            public,
+        } => Box::new(gist::GistDestination::new(token, api_url, public)?),
+    })
+}
+
+pub(crate) fn create_http_client() -> Result<reqwest::Client> {
+    use anyhow::Context;
+
+    reqwest::Client::builder()
+        .user_agent(concat!("snow-shot/", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(300))
+        .build()
+        .context("Create HTTP client failed")
+}
+
+/// 按路径读取 JSON 中的字符串，例如: data.link、files.0.url
+pub(crate) fn get_json_path_string(value: &Value, path: &str) -> Option<String> {
+    let mut current = value;
+    for key in path.split('.').filter(|key| !key.is_empty()) {
+        current = match current {
+            Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+            Value::Object(object) => object.get(key)?,
+            _ => return None,
+        };
+    }
+
+    match current {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+/// 拼接对外访问的地址
+pub(crate) fn join_url(prefix: &str, path: &str) -> String {
+    format!(
+        "{}/{}",
+        prefix.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_get_json_path_string() {
+        let value = json!({
+            "data": { "link": "https://i.example.com/a.png", "id": 42 },
+            "files": [{ "url": "https://example.com/b.png" }],
+        });
+
+        assert_eq!(
+            get_json_path_string(&value, "data.link").as_deref(),
+            Some("https://i.example.com/a.png")
+        );
+        assert_eq!(
+            get_json_path_string(&value, "files.0.url").as_deref(),
+            Some("https://example.com/b.png")
+        );
+        assert_eq!(
+            get_json_path_string(&value, "data.id").as_deref(),
+            Some("42")
+        );
+        assert_eq!(get_json_path_string(&value, "data.missing"), None);
+    }
+
+    #[test]
+    fn test_create_upload_destination() {
+        let profile: UploadProfile = serde_json::from_value(json!({
+            "id": "sharex",
+            "name": "ShareX",
+            "destination": {
+                "type": "CustomHttp",
+                "url": "https://upload.example.com/api",
+                "file_field_name": "file",
+                "url_json_path": "data.url",
+            },
+        }))
+        .unwrap();
+
+        assert!(create_upload_destination(profile.destination).is_ok());
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::multipart::{Form, Part};
+use reqwest::Method;
+use serde_json::Value;
+
+use crate::upload_destination::{
+    create_http_client, get_json_path_string, UploadDestination, UploadResult, UploadStatusError,
+};
+
+/// 自定义 HTTP 上传
+pub struct CustomHttpDestination {
+    client: reqwest::Client,
+    url: String,
+    method: Method,
+    file_field_name: Option<String>,
+    form_fields: HashMap<String, String>,
+    headers: HashMap<String, String>,
+    url_json_path: Option<String>,
+    delete_url_json_path: Option<String>,
+}
+
+impl CustomHttpDestination {
+    pub fn new(
+        url: String,
+        method: Option<String>,
+        file_field_name: Option<String>,
+        form_fields: HashMap<String, String>,
+        headers: HashMap<String, String>,
+        url_json_path: Option<String>,
+        delete_url_json_path: Option<String>,
+    ) -> Result<Self> {
+        let method = match method {
+            Some(method) => Method::from_bytes(method.to_uppercase().as_bytes())
+                .with_context(|| format!("Invalid HTTP method: {}", method))?,
+            None => Method::POST,
+        };
+
+        Ok(Self {
+            client: create_http_client()?,
+            url,
+            method,
+            file_field_name,
+            form_fields,
+            headers,
+            url_json_path,
+            delete_url_json_path,
+        })
+    }
+}
+
+/// 替换配置中的 {filename} 占位符
+fn replace_placeholders(value: &str, filename: &str) -> String {
+    value.replace("{filename}", filename)
+}
+
+/// 判断两个地址的协议、主机和端口是否一致，无法解析时视为不一致
+fn is_same_origin(url: &str, other_url: &str) -> bool {
+    match (reqwest::Url::parse(url), reqwest::Url::parse(other_url)) {
+        (Ok(url), Ok(other_url)) => url.origin() == other_url.origin(),
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl UploadDestination for CustomHttpDestination {
+    async fn upload(
+        &self,
+        data: &[u8],
+        filename: &str,
+        content_type: Option<&str>,
+    ) -> Result<UploadResult> {
+        let url = replace_placeholders(&self.url, filename);
+        info!("Start uploading data to: {}", url);
+
+        let content_type = content_type.unwrap_or("application/octet-stream");
+        let mut request = self.client.request(self.method.clone(), &url);
+        for (name, value) in &self.headers {
+            request = request.header(name, replace_placeholders(value, filename));
+        }
+
+        request = match &self.file_field_name {
+            Some(file_field_name) => {
+                let mut form = Form::new().part(
+                    file_field_name.clone(),
+                    Part::bytes(data.to_vec())
+                        .file_name(filename.to_string())
+                        .mime_str(content_type)?,
+                );
+                for (name, value) in &self.form_fields {
+                    form = form.text(name.clone(), replace_placeholders(value, filename));
+                }
+                request.multipart(form)
+            }
+            None => request
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(data.to_vec()),
+        };
+
+        let response = request.send().await.context("Upload data failed")?;
+        let status = response.status();
+        let response_text = response.text().await.context("Read response failed")?;
+        debug!("Custom upload response: {}", response_text);
+        if !status.is_success() {
+            return Err(UploadStatusError {
+                status: status.as_u16(),
+                message: format!(
+                    "Upload failed, HTTP status code: {}, body: {}",
+                    status, response_text
+                ),
+            }
+            .into());
+        }
+
+        let response_body: Option<Value> = serde_json::from_str(&response_text).ok();
+        let get_response_string = |path: &str| {
+            response_body
+                .as_ref()
+                .and_then(|value| get_json_path_string(value, path))
+        };
+
+        let url = match &self.url_json_path {
+            Some(url_json_path) => get_response_string(url_json_path).with_context(|| {
+                format!("Missing {} in response: {}", url_json_path, response_text)
+            })?,
+            None => response_text.trim().to_string(),
+        };
+
+        Ok(UploadResult {
+            url,
+            remote_path: filename.to_string(),
+            delete_url: self
+                .delete_url_json_path
+                .as_deref()
+                .and_then(get_response_string),
+        })
+    }
+
+    /// ShareX 的删除地址通常为直接访问即可删除的链接
+    ///
+    /// 删除地址由服务端返回，只有与上传地址同源时才附带配置的请求头，避免泄露凭据
+    async fn delete(&self, _remote_path: &str, delete_url: Option<&str>) -> Result<()> {
+        let delete_url = delete_url.context("Upload result has no delete url")?;
+        info!("Request delete url: {}", delete_url);
+
+        let mut request = self.client.get(delete_url);
+        if is_same_origin(&replace_placeholders(&self.url, ""), delete_url) {
+            for (name, value) in &self.headers {
+                request = request.header(name, value);
+            }
+        } else if !self.headers.is_empty() {
+            info!("Delete url is not on the upload host, skip sending headers");
+        }
+
+        let response = request.send().await.context("Request delete url failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Delete failed, HTTP status code: {}", status);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_same_origin() {
+        assert!(is_same_origin(
+            "https://example.com/upload",
+            "https://example.com/delete/abc"
+        ));
+        assert!(is_same_origin(
+            "https://example.com/upload",
+            "https://example.com:443/delete/abc"
+        ));
+        assert!(!is_same_origin(
+            "https://example.com/upload",
+            "https://evil.example.org/delete/abc"
+        ));
+        assert!(!is_same_origin(
+            "https://example.com/upload",
+            "http://example.com/delete/abc"
+        ));
+        assert!(!is_same_origin("https://example.com/upload", "not a url"));
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use log::info;
+use serde_json::{json, Value};
+
+use crate::upload_destination::{
+    create_http_client, get_json_path_string, UploadDestination, UploadResult, UploadStatusError,
+};
+
+/// GitHub Gist 及兼容接口，例如 Gitea、Forgejo
+pub struct GistDestination {
+    client: reqwest::Client,
+    token: String,
+    api_url: String,
+    public: bool,
+}
+
+impl GistDestination {
+    pub fn new(token: String, api_url: Option<String>, public: bool) -> Result<Self> {
+        Ok(Self {
+            client: create_http_client()?,
+            token,
+            api_url: api_url.unwrap_or_else(|| "https://api.github.com".to_string()),
+            public,
+        })
+    }
+}
+
+#[async_trait]
+impl UploadDestination for GistDestination {
+    async fn upload(
+        &self,
+        data: &[u8],
+        filename: &str,
+        _content_type: Option<&str>,
+    ) -> Result<UploadResult> {
+        let content = std::str::from_utf8(data).context("Gist only supports text content")?;
+
+        // Gist 中的文件名不能包含目录
+        let filename = filename.rsplit('/').next().unwrap_or(filename);
+
+        let url = format!("{}/gists", self.api_url.trim_end_matches('/'));
+        info!("Start creating gist: {}", url);
+
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.token)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .json(&json!({
+                "public": self.public,
+                "files": { filename: { "content": content } },
+            }))
+            .send()
+            .await
+            .context("Create gist failed")?;
+
+        let status = response.status();
+        let response_body: Value = response
+            .json()
+            .await
+            .context("Parse gist response failed")?;
+        if !status.is_success() {
+            return Err(UploadStatusError {
+                status: status.as_u16(),
+                message: format!(
+                    "Create gist failed, HTTP status code: {}, body: {}",
+                    status, response_body
+                ),
+            }
+            .into());
+        }
+
+        // 优先返回原始文件地址，便于直接引用
+        let url = response_body["files"][filename]["raw_url"]
+            .as_str()
+            .map(|url| url.to_string())
+            .or_else(|| get_json_path_string(&response_body, "html_url"))
+            .with_context(|| format!("Missing gist url in response: {}", response_body))?;
+
+        Ok(UploadResult {
+            url,
+            remote_path: get_json_path_string(&response_body, "id").unwrap_or_default(),
+            delete_url: get_json_path_string(&response_body, "url"),
+        })
+    }
+
+    async fn delete(&self, remote_path: &str, delete_url: Option<&str>) -> Result<()> {
+        let url = match delete_url {
+            Some(delete_url) => delete_url.to_string(),
+            None => format!(
+                "{}/gists/{}",
+                self.api_url.trim_end_matches('/'),
+                remote_path
+            ),
+        };
+        info!("Delete gist: {}", url);
+
+        let response = self
+            .client
+            .delete(&url)
+            .bearer_auth(&self.token)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .send()
+            .await
+            .context("Delete gist failed")?;
+
+        let status = response.status();
+        if !status.is_success() && status != reqwest::StatusCode::NOT_FOUND {
+            anyhow::bail!("Delete gist failed, HTTP status code: {}", status);
+        }
+
+        Ok(())
+    }
+}
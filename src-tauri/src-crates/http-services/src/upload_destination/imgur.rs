@@ -0,0 +1,114 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use log::info;
+use reqwest::multipart::{Form, Part};
+use serde_json::Value;
+
+use crate::upload_destination::{
+    create_http_client, get_json_path_string, UploadDestination, UploadResult, UploadStatusError,
+};
+
+/// Imgur 及兼容接口的图床
+pub struct ImgurDestination {
+    client: reqwest::Client,
+    client_id: String,
+    api_url: String,
+}
+
+impl ImgurDestination {
+    pub fn new(client_id: String, api_url: Option<String>) -> Result<Self> {
+        Ok(Self {
+            client: create_http_client()?,
+            client_id,
+            api_url: api_url.unwrap_or_else(|| "https://api.imgur.com".to_string()),
+        })
+    }
+}
+
+#[async_trait]
+impl UploadDestination for ImgurDestination {
+    async fn upload(
+        &self,
+        data: &[u8],
+        filename: &str,
+        content_type: Option<&str>,
+    ) -> Result<UploadResult> {
+        let url = format!("{}/3/image", self.api_url.trim_end_matches('/'));
+        info!("Start uploading image to: {}", url);
+
+        let form = Form::new().part(
+            "image",
+            Part::bytes(data.to_vec())
+                .file_name(filename.to_string())
+                .mime_str(content_type.unwrap_or("image/png"))?,
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("Client-ID {}", self.client_id),
+            )
+            .multipart(form)
+            .send()
+            .await
+            .context("Upload image to Imgur failed")?;
+
+        let status = response.status();
+        let response_body: Value = response
+            .json()
+            .await
+            .context("Parse Imgur response failed")?;
+        if !status.is_success() {
+            return Err(UploadStatusError {
+                status: status.as_u16(),
+                message: format!(
+                    "Upload failed, HTTP status code: {}, body: {}",
+                    status, response_body
+                ),
+            }
+            .into());
+        }
+
+        let link = get_json_path_string(&response_body, "data.link")
+            .with_context(|| format!("Missing data.link in response: {}", response_body))?;
+        let delete_url =
+            get_json_path_string(&response_body, "data.deletehash").map(|delete_hash| {
+                format!(
+                    "{}/3/image/{}",
+                    self.api_url.trim_end_matches('/'),
+                    delete_hash
+                )
+            });
+
+        Ok(UploadResult {
+            url: link,
+            remote_path: get_json_path_string(&response_body, "data.id").unwrap_or_default(),
+            delete_url,
+        })
+    }
+
+    async fn delete(&self, _remote_path: &str, delete_url: Option<&str>) -> Result<()> {
+        let delete_url = delete_url.context("Upload result has no delete hash")?;
+        info!("Delete image: {}", delete_url);
+
+        let response = self
+            .client
+            .delete(delete_url)
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("Client-ID {}", self.client_id),
+            )
+            .send()
+            .await
+            .context("Delete image failed")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Delete failed, HTTP status code: {}", status);
+        }
+
+        Ok(())
+    }
+}
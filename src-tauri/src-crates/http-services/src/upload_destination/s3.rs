@@ -0,0 +1,49 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::upload_destination::{UploadDestination, UploadResult};
+use crate::{S3Config, S3Service};
+
+/// S3 兼容的对象存储
+pub struct S3Destination {
+    config: S3Config,
+}
+
+impl S3Destination {
+    pub fn new(config: S3Config) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl UploadDestination for S3Destination {
+    async fn upload(
+        &self,
+        data: &[u8],
+        filename: &str,
+        content_type: Option<&str>,
+    ) -> Result<UploadResult> {
+        let service = S3Service::new(self.config.clone()).await?;
+
+        let url = service
+            .upload_bytes(
+                data,
+                filename.to_string(),
+                content_type.map(|content_type| content_type.to_string()),
+            )
+            .await?;
+
+        Ok(UploadResult {
+            url,
+            remote_path: service.get_full_key(filename),
+            delete_url: None,
+        })
+    }
+
+    async fn delete(&self, remote_path: &str, _delete_url: Option<&str>) -> Result<()> {
+        S3Service::new(self.config.clone())
+            .await?
+            .delete_object(remote_path)
+            .await
+    }
+}
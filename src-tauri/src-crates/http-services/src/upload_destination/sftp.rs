@@ -0,0 +1,278 @@
+use std::io::Write;
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD_NO_PAD as BASE64, Engine};
+use log::info;
+use ssh2::{CheckResult, HashType, KnownHostFileKind, Session};
+
+use crate::upload_destination::{join_url, UploadDestination, UploadResult};
+
+/// SFTP 服务器
+#[derive(Clone)]
+pub struct SftpDestination {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: Option<String>,
+    pub private_key_path: Option<String>,
+    pub passphrase: Option<String>,
+    pub remote_dir: String,
+    pub public_url_prefix: Option<String>,
+    /// 服务器公钥的 SHA256 指纹，为空时使用 known_hosts 校验
+    pub host_key_fingerprint: Option<String>,
+}
+
+/// 获取 OpenSSH 格式的公钥指纹，例如: SHA256:nThbg6kXUpJWGl7E1IGOCspRomTxdCARLviKw6E5SY8
+fn format_fingerprint(hash: &[u8]) -> String {
+    format!("SHA256:{}", BASE64.encode(hash))
+}
+
+/// 比较指纹，忽略 SHA256: 前缀和 Base64 填充
+fn is_fingerprint_equal(expected: &str, actual: &str) -> bool {
+    let normalize = |fingerprint: &str| {
+        let fingerprint = fingerprint.trim();
+        fingerprint
+            .strip_prefix("SHA256:")
+            .unwrap_or(fingerprint)
+            .trim_end_matches('=')
+            .to_string()
+    };
+
+    normalize(expected) == normalize(actual)
+}
+
+fn get_known_hosts_path() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".ssh").join("known_hosts"))
+}
+
+/// 远程文件的各级父目录，例如: /a/b/c.png -> [/a, /a/b]
+///
+/// 远程路径始终使用 / 分隔，不能使用本地的 Path
+fn get_parent_dirs(remote_path: &str) -> Vec<String> {
+    let prefix = if remote_path.starts_with('/') {
+        "/"
+    } else {
+        ""
+    };
+    let segments: Vec<&str> = remote_path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    (1..segments.len())
+        .map(|depth| format!("{}{}", prefix, segments[..depth].join("/")))
+        .collect()
+}
+
+impl SftpDestination {
+    pub(crate) fn get_remote_path(&self, filename: &str) -> String {
+        join_url(&self.remote_dir, filename)
+    }
+
+    /// 建立连接并完成认证，ssh2 为阻塞接口，需要在阻塞线程中调用
+    pub(crate) fn connect(&self) -> Result<Session> {
+        let tcp = TcpStream::connect((self.host.as_str(), self.port))
+            .with_context(|| format!("Connect to {}:{} failed", self.host, self.port))?;
+
+        let mut session = Session::new().context("Create SSH session failed")?;
+        session.set_tcp_stream(tcp);
+        session.handshake().context("SSH handshake failed")?;
+        self.verify_host_key(&session)?;
+
+        match (&self.private_key_path, &self.password) {
+            (Some(private_key_path), _) => session
+                .userauth_pubkey_file(
+                    &self.username,
+                    None,
+                    Path::new(private_key_path),
+                    self.passphrase.as_deref(),
+                )
+                .context("SSH public key authentication failed")?,
+            (None, Some(password)) => session
+                .userauth_password(&self.username, password)
+                .context("SSH password authentication failed")?,
+            (None, None) => session
+                .userauth_agent(&self.username)
+                .context("SSH agent authentication failed")?,
+        }
+
+        Ok(session)
+    }
+
+    /// 校验服务器公钥，未知或不一致时拒绝连接
+    fn verify_host_key(&self, session: &Session) -> Result<()> {
+        let fingerprint = session
+            .host_key_hash(HashType::Sha256)
+            .map(format_fingerprint)
+            .context("Get SSH host key failed")?;
+
+        if let Some(expected) = &self.host_key_fingerprint {
+            if !is_fingerprint_equal(expected, &fingerprint) {
+                bail!(
+                    "SSH host key mismatch for {}:{}, expected: {}, actual: {}",
+                    self.host,
+                    self.port,
+                    expected,
+                    fingerprint
+                );
+            }
+
+            return Ok(());
+        }
+
+        let (host_key, _) = session.host_key().context("Get SSH host key failed")?;
+        let mut known_hosts = session
+            .known_hosts()
+            .context("Create SSH known hosts failed")?;
+        if let Some(known_hosts_path) = get_known_hosts_path() {
+            if known_hosts_path.is_file() {
+                known_hosts
+                    .read_file(&known_hosts_path, KnownHostFileKind::OpenSSH)
+                    .with_context(|| {
+                        format!(
+                            "Read SSH known hosts failed: {}",
+                            known_hosts_path.display()
+                        )
+                    })?;
+            }
+        }
+
+        match known_hosts.check_port(&self.host, self.port, host_key) {
+            CheckResult::Match => Ok(()),
+            CheckResult::Mismatch => bail!(
+                "SSH host key for {}:{} does not match known_hosts, actual: {}",
+                self.host,
+                self.port,
+                fingerprint
+            ),
+            CheckResult::NotFound | CheckResult::Failure => bail!(
+                "Unknown SSH host key for {}:{}, add it to known_hosts or set the host key fingerprint: {}",
+                self.host,
+                self.port,
+                fingerprint
+            ),
+        }
+    }
+
+    fn upload_blocking(&self, data: &[u8], remote_path: &str) -> Result<()> {
+        let session = self.connect()?;
+        let sftp = session.sftp().context("Start SFTP subsystem failed")?;
+
+        // 逐级创建目录，已存在时忽略错误
+        for parent_dir in get_parent_dirs(remote_path) {
+            let parent_dir = Path::new(&parent_dir);
+            if sftp.stat(parent_dir).is_err() {
+                let _ = sftp.mkdir(parent_dir, 0o755);
+            }
+        }
+
+        let mut file = sftp
+            .create(Path::new(remote_path))
+            .with_context(|| format!("Create remote file failed: {}", remote_path))?;
+        file.write_all(data).context("Write remote file failed")?;
+
+        Ok(())
+    }
+
+    fn delete_blocking(&self, remote_path: &str) -> Result<()> {
+        let session = self.connect()?;
+        let sftp = session.sftp().context("Start SFTP subsystem failed")?;
+
+        sftp.unlink(Path::new(remote_path))
+            .with_context(|| format!("Delete remote file failed: {}", remote_path))
+    }
+}
+
+#[async_trait]
+impl UploadDestination for SftpDestination {
+    async fn upload(
+        &self,
+        data: &[u8],
+        filename: &str,
+        _content_type: Option<&str>,
+    ) -> Result<UploadResult> {
+        let remote_path = self.get_remote_path(filename);
+        info!(
+            "Start uploading data to SFTP: {}@{}:{}",
+            self.username, self.host, remote_path
+        );
+
+        let destination = self.clone();
+        let data = data.to_vec();
+        let upload_path = remote_path.clone();
+        tokio::task::spawn_blocking(move || destination.upload_blocking(&data, &upload_path))
+            .await
+            .context("Join SFTP upload task failed")??;
+
+        let url = match &self.public_url_prefix {
+            Some(public_url_prefix) => join_url(public_url_prefix, filename),
+            None => format!(
+                "sftp://{}@{}:{}{}",
+                self.username, self.host, self.port, remote_path
+            ),
+        };
+
+        Ok(UploadResult {
+            url,
+            remote_path,
+            delete_url: None,
+        })
+    }
+
+    async fn delete(&self, remote_path: &str, _delete_url: Option<&str>) -> Result<()> {
+        info!(
+            "Delete SFTP file: {}@{}:{}",
+            self.username, self.host, remote_path
+        );
+
+        let destination = self.clone();
+        let remote_path = remote_path.to_string();
+        tokio::task::spawn_blocking(move || destination.delete_blocking(&remote_path))
+            .await
+            .context("Join SFTP delete task failed")?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_parent_dirs() {
+        assert_eq!(
+            get_parent_dirs("/var/www/images/2024/a.png"),
+            vec![
+                "/var",
+                "/var/www",
+                "/var/www/images",
+                "/var/www/images/2024"
+            ]
+        );
+        assert_eq!(get_parent_dirs("images//a.png"), vec!["images"]);
+        assert!(get_parent_dirs("a.png").is_empty());
+    }
+
+    #[test]
+    fn test_is_fingerprint_equal() {
+        let fingerprint = format_fingerprint(&[1u8; 32]);
+        assert_eq!(
+            fingerprint,
+            "SHA256:AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE"
+        );
+
+        assert!(is_fingerprint_equal(&fingerprint, &fingerprint));
+        assert!(is_fingerprint_equal(
+            "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=",
+            &fingerprint
+        ));
+        assert!(!is_fingerprint_equal(
+            &format_fingerprint(&[2u8; 32]),
+            &fingerprint
+        ));
+    }
+}
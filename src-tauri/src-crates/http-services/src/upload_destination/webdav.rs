@@ -0,0 +1,191 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use log::{debug, info};
+use reqwest::{Method, StatusCode, Url};
+
+use crate::upload_destination::{
+    create_http_client, join_url, UploadDestination, UploadResult, UploadStatusError,
+};
+
+/// WebDAV 服务，例如 Nextcloud、ownCloud
+pub struct WebDavDestination {
+    client: reqwest::Client,
+    endpoint: String,
+    username: String,
+    password: String,
+    path_prefix: Option<String>,
+    public_url_prefix: Option<String>,
+}
+
+impl WebDavDestination {
+    pub fn new(
+        endpoint: String,
+        username: String,
+        password: String,
+        path_prefix: Option<String>,
+        public_url_prefix: Option<String>,
+    ) -> Result<Self> {
+        Ok(Self {
+            client: create_http_client()?,
+            endpoint,
+            username,
+            password,
+            path_prefix,
+            public_url_prefix,
+        })
+    }
+
+    /// 文件在 WebDAV 中的路径，不包含 endpoint
+    pub(crate) fn get_remote_path(&self, filename: &str) -> String {
+        match &self.path_prefix {
+            Some(prefix) => join_url(prefix, filename),
+            None => filename.to_string(),
+        }
+        .trim_start_matches('/')
+        .to_string()
+    }
+
+    /// 拼接 WebDAV 地址，路径中的每一段都会被编码
+    pub(crate) fn get_url(&self, remote_path: &str) -> Result<Url> {
+        let mut url = Url::parse(&self.endpoint)
+            .with_context(|| format!("Invalid WebDAV endpoint: {}", self.endpoint))?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid WebDAV endpoint: {}", self.endpoint))?
+            .pop_if_empty()
+            .extend(remote_path.split('/').filter(|segment| !segment.is_empty()));
+
+        Ok(url)
+    }
+
+    pub(crate) fn request(&self, method: Method, url: Url) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, url)
+            .basic_auth(&self.username, Some(&self.password))
+    }
+
+    /// 逐级创建上传目录，目录已存在时服务端返回 405
+    async fn create_parent_dirs(&self, remote_path: &str) -> Result<()> {
+        let segments: Vec<&str> = remote_path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+
+        for depth in 1..segments.len() {
+            let mut url = self.get_url(&segments[..depth].join("/"))?;
+            url.path_segments_mut()
+                .map_err(|_| anyhow::anyhow!("Invalid WebDAV endpoint: {}", self.endpoint))?
+                .push("");
+
+            let response = self
+                .request(Method::from_bytes(b"MKCOL")?, url)
+                .send()
+                .await
+                .context("Create WebDAV directory failed")?;
+
+            let status = response.status();
+            if !status.is_success() && status != StatusCode::METHOD_NOT_ALLOWED {
+                return Err(UploadStatusError {
+                    status: status.as_u16(),
+                    message: format!(
+                        "Create WebDAV directory failed, HTTP status code: {}",
+                        status
+                    ),
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UploadDestination for WebDavDestination {
+    async fn upload(
+        &self,
+        data: &[u8],
+        filename: &str,
+        content_type: Option<&str>,
+    ) -> Result<UploadResult> {
+        let remote_path = self.get_remote_path(filename);
+        let url = self.get_url(&remote_path)?;
+        info!("Start uploading data to WebDAV: {}", url);
+
+        self.create_parent_dirs(&remote_path).await?;
+
+        let response = self
+            .request(Method::PUT, url.clone())
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                content_type.unwrap_or("application/octet-stream"),
+            )
+            .body(data.to_vec())
+            .send()
+            .await
+            .context("Upload data to WebDAV failed")?;
+
+        let status = response.status();
+        debug!("WebDAV response status: {}", status);
+        if !status.is_success() {
+            return Err(UploadStatusError {
+                status: status.as_u16(),
+                message: format!("Upload failed, HTTP status code: {}", status),
+            }
+            .into());
+        }
+
+        let url = match &self.public_url_prefix {
+            Some(public_url_prefix) => join_url(public_url_prefix, filename),
+            None => url.to_string(),
+        };
+
+        Ok(UploadResult {
+            url,
+            remote_path,
+            delete_url: None,
+        })
+    }
+
+    async fn delete(&self, remote_path: &str, _delete_url: Option<&str>) -> Result<()> {
+        let url = self.get_url(remote_path)?;
+        info!("Delete WebDAV file: {}", url);
+
+        let response = self
+            .request(Method::DELETE, url)
+            .send()
+            .await
+            .context("Delete WebDAV file failed")?;
+
+        // 文件已不存在时视为删除成功
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            anyhow::bail!("Delete failed, HTTP status code: {}", status);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_url() {
+        let destination = WebDavDestination::new(
+            "https://cloud.example.com/remote.php/dav/files/user/".to_string(),
+            "user".to_string(),
+            "password".to_string(),
+            Some("screenshots/".to_string()),
+            None,
+        )
+        .unwrap();
+
+        let remote_path = destination.get_remote_path("2025/a b.png");
+        assert_eq!(remote_path, "screenshots/2025/a b.png");
+        assert_eq!(
+            destination.get_url(&remote_path).unwrap().as_str(),
+            "https://cloud.example.com/remote.php/dav/files/user/screenshots/2025/a%20b.png"
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_QUERY_LIMIT: usize = 50;
+
+/// 上传记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadHistoryRecord {
+    /// 写入后由数据库生成
+    #[serde(default)]
+    pub id: i64,
+    /// 使用的上传配置，直接传入配置上传时为空
+    pub profile_id: Option<String>,
+    /// 上传目标类型，例如: S3、WebDav
+    pub destination_type: String,
+    /// 在目标中的路径或对象键名
+    pub remote_path: String,
+    pub url: String,
+    pub delete_url: Option<String>,
+    /// 文件大小，字节
+    pub size: u64,
+    pub content_type: Option<String>,
+    /// 上传时间，毫秒
+    pub uploaded_at: u64,
+    /// 本地源文件，直接上传数据时为空
+    pub source_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UploadHistoryQuery {
+    /// 关键词，匹配远程路径、地址和本地路径
+    pub text: String,
+    pub profile_id: Option<String>,
+    pub destination_type: Option<String>,
+    /// 上传时间范围，毫秒
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// 上传历史，保存在 SQLite 中
+pub struct UploadHistoryStore {
+    connection: Connection,
+}
+
+impl UploadHistoryStore {
+    pub fn open(database_path: &Path) -> Result<Self> {
+        info!("Open upload history database: {}", database_path.display());
+
+        if let Some(parent) = database_path.parent() {
+            std::fs::create_dir_all(parent).context("Create upload history dir failed")?;
+        }
+
+        let connection =
+            Connection::open(database_path).context("Open upload history database failed")?;
+        Self::from_connection(connection)
+    }
+
+    fn from_connection(connection: Connection) -> Result<Self> {
+        connection
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                CREATE TABLE IF NOT EXISTS upload_history (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id TEXT,
+                    destination_type TEXT NOT NULL,
+                    remote_path TEXT NOT NULL,
+                    url TEXT NOT NULL,
+                    delete_url TEXT,
+                    size INTEGER NOT NULL,
+                    content_type TEXT,
+                    uploaded_at INTEGER NOT NULL,
+                    source_path TEXT
+                );
+                CREATE INDEX IF NOT EXISTS upload_history_uploaded_at ON upload_history (uploaded_at);",
+            )
+            .context("Create upload history table failed")?;
+
+        Ok(Self { connection })
+    }
+
+    /// 写入上传记录，返回记录 id
+    pub fn add(&self, record: &UploadHistoryRecord) -> Result<i64> {
+        self.connection
+            .execute(
+                "INSERT INTO upload_history
+                    (profile_id, destination_type, remote_path, url, delete_url, size, content_type, uploaded_at, source_path)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    record.profile_id,
+                    record.destination_type,
+                    record.remote_path,
+                    record.url,
+                    record.delete_url,
+                    record.size as i64,
+                    record.content_type,
+                    record.uploaded_at as i64,
+                    record.source_path,
+                ],
+            )
+            .context("Insert upload history failed")?;
+
+        Ok(self.connection.last_insert_rowid())
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<UploadHistoryRecord>> {
+        self.connection
+            .query_row(
+                "SELECT * FROM upload_history WHERE id = ?1",
+                [id],
+                Self::read_record,
+            )
+            .optional()
+            .context("Query upload history failed")
+    }
+
+    pub fn remove(&self, id: i64) -> Result<()> {
+        self.connection
+            .execute("DELETE FROM upload_history WHERE id = ?1", [id])
+            .context("Remove upload history failed")?;
+
+        Ok(())
+    }
+
+    /// 按上传时间倒序查询
+    pub fn query(&self, query: &UploadHistoryQuery) -> Result<Vec<UploadHistoryRecord>> {
+        let mut statement = self
+            .connection
+            .prepare_cached(
+                "SELECT * FROM upload_history
+                WHERE (?1 = '' OR (remote_path || ' ' || url || ' ' || IFNULL(source_path, '')) LIKE ?1 ESCAPE '\\')
+                    AND (?2 IS NULL OR profile_id = ?2)
+                    AND (?3 IS NULL OR destination_type = ?3)
+                    AND uploaded_at BETWEEN ?4 AND ?5
+                ORDER BY uploaded_at DESC, id DESC
+                LIMIT ?6 OFFSET ?7",
+            )
+            .context("Prepare upload history query failed")?;
+
+        let records = statement
+            .query_map(
+                params![
+                    to_like_pattern(&query.text),
+                    query.profile_id,
+                    query.destination_type,
+                    query.start_time.map(|time| time as i64).unwrap_or(0),
+                    query.end_time.map(|time| time as i64).unwrap_or(i64::MAX),
+                    query.limit.unwrap_or(DEFAULT_QUERY_LIMIT) as i64,
+                    query.offset.unwrap_or(0) as i64,
+                ],
+                Self::read_record,
+            )
+            .context("Query upload history failed")?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Read upload history failed")?;
+
+        Ok(records)
+    }
+
+    fn read_record(row: &Row) -> rusqlite::Result<UploadHistoryRecord> {
+        Ok(UploadHistoryRecord {
+            id: row.get("id")?,
+            profile_id: row.get("profile_id")?,
+            destination_type: row.get("destination_type")?,
+            remote_path: row.get("remote_path")?,
+            url: row.get("url")?,
+            delete_url: row.get("delete_url")?,
+            size: row.get::<_, i64>("size")? as u64,
+            content_type: row.get("content_type")?,
+            uploaded_at: row.get::<_, i64>("uploaded_at")? as u64,
+            source_path: row.get("source_path")?,
+        })
+    }
+}
+
+/// 关键词以空白分隔，按顺序匹配
+fn to_like_pattern(text: &str) -> String {
+    let keywords: Vec<String> = text
+        .split_whitespace()
+        .map(|keyword| {
+            keyword
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        })
+        .collect();
+    if keywords.is_empty() {
+        return String::new();
+    }
+
+    format!("%{}%", keywords.join("%"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_record(remote_path: &str, uploaded_at: u64) -> UploadHistoryRecord {
+        UploadHistoryRecord {
+            id: 0,
+            profile_id: Some("s3".to_string()),
+            destination_type: "S3".to_string(),
+            remote_path: remote_path.to_string(),
+            url: format!("https://cdn.example.com/{}", remote_path),
+            delete_url: None,
+            size: 1024,
+            content_type: Some("image/png".to_string()),
+            uploaded_at,
+            source_path: None,
+        }
+    }
+
+    #[test]
+    fn test_upload_history() {
+        let store =
+            UploadHistoryStore::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+
+        let first_id = store.add(&get_record("images/first_1.png", 1000)).unwrap();
+        store.add(&get_record("images/second.png", 2000)).unwrap();
+
+        let records = store.query(&UploadHistoryQuery::default()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].remote_path, "images/second.png");
+
+        let records = store
+            .query(&UploadHistoryQuery {
+                text: "first_".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id, first_id);
+
+        let records = store
+            .query(&UploadHistoryQuery {
+                start_time: Some(1500),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(records.len(), 1);
+
+        store.remove(first_id).unwrap();
+        assert!(store.get(first_id).unwrap().is_none());
+    }
+}
@@ -0,0 +1,521 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use crate::post_upload::PostUploadOptions;
+use crate::upload_destination::UploadStatusError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UploadQueueJobStatus {
+    /// 等待上传，包括等待重试
+    Pending,
+    Uploading,
+    Completed,
+    /// 超过最大重试次数或无法重试
+    Failed,
+}
+
+impl UploadQueueJobStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UploadQueueJobStatus::Pending => "Pending",
+            UploadQueueJobStatus::Uploading => "Uploading",
+            UploadQueueJobStatus::Completed => "Completed",
+            UploadQueueJobStatus::Failed => "Failed",
+        }
+    }
+
+    fn parse(status: &str) -> Option<Self> {
+        match status {
+            "Pending" => Some(UploadQueueJobStatus::Pending),
+            "Uploading" => Some(UploadQueueJobStatus::Uploading),
+            "Completed" => Some(UploadQueueJobStatus::Completed),
+            "Failed" => Some(UploadQueueJobStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// 上传队列中的任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadQueueJob {
+    /// 写入后由数据库生成
+    #[serde(default)]
+    pub id: i64,
+    pub profile_id: String,
+    /// 待上传的本地文件
+    pub source_path: String,
+    pub filename: String,
+    pub content_type: Option<String>,
+    /// 上传完成或移除任务后删除本地文件，用于队列自己保存的数据
+    pub delete_after_upload: bool,
+    pub status: UploadQueueJobStatus,
+    /// 已尝试的次数
+    pub attempts: u32,
+    /// 下次尝试的时间，毫秒
+    pub next_attempt_at: u64,
+    pub last_error: Option<String>,
+    /// 上传完成后的地址，执行上传后处理时为处理后的地址
+    pub url: Option<String>,
+    /// 上传完成后执行的操作
+    #[serde(default)]
+    pub post_upload_options: Option<PostUploadOptions>,
+    /// 毫秒
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// 重试策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UploadQueueOptions {
+    pub max_attempts: u32,
+    /// 第一次重试的等待时间，之后每次翻倍
+    pub base_delay_secs: u64,
+    pub max_delay_secs: u64,
+}
+
+impl Default for UploadQueueOptions {
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            base_delay_secs: 5,
+            max_delay_secs: 30 * 60,
+        }
+    }
+}
+
+impl UploadQueueOptions {
+    /// 获取第 `attempts` 次失败后的重试等待时间，秒
+    pub fn get_retry_delay_secs(&self, attempts: u32) -> u64 {
+        let exponent = attempts.saturating_sub(1).min(32);
+        self.base_delay_secs
+            .saturating_mul(1u64 << exponent)
+            .min(self.max_delay_secs)
+    }
+
+    /// 获取下次尝试的时间，超过最大尝试次数时返回 None
+    pub fn get_next_attempt_at(&self, attempts: u32, now: u64) -> Option<u64> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+
+        Some(now + self.get_retry_delay_secs(attempts) * 1000)
+    }
+}
+
+/// 判断上传失败后是否可以重试
+///
+/// 连接失败、超时和服务器错误可以重试，配置错误、认证失败等其它错误重试也不会成功
+pub fn is_retryable_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<UploadStatusError>() {
+            return is_retryable_status(e.status);
+        }
+
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return e.is_connect()
+                || e.is_timeout()
+                || e.status()
+                    .is_some_and(|status| is_retryable_status(status.as_u16()));
+        }
+
+        if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+            return matches!(
+                e.kind(),
+                std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::NotConnected
+                    | std::io::ErrorKind::AddrNotAvailable
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::UnexpectedEof
+            );
+        }
+
+        false
+    })
+}
+
+/// 服务器错误、请求超时和限流时可以重试
+fn is_retryable_status(status: u16) -> bool {
+    status >= 500 || status == 408 || status == 429
+}
+
+/// 上传队列，保存在 SQLite 中，应用重启后继续上传
+pub struct UploadQueueStore {
+    connection: Connection,
+}
+
+impl UploadQueueStore {
+    pub fn open(database_path: &Path) -> Result<Self> {
+        info!("Open upload queue database: {}", database_path.display());
+
+        if let Some(parent) = database_path.parent() {
+            std::fs::create_dir_all(parent).context("Create upload queue dir failed")?;
+        }
+
+        let connection =
+            Connection::open(database_path).context("Open upload queue database failed")?;
+        Self::from_connection(connection)
+    }
+
+    fn from_connection(connection: Connection) -> Result<Self> {
+        connection
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                CREATE TABLE IF NOT EXISTS upload_queue (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id TEXT NOT NULL,
+                    source_path TEXT NOT NULL,
+                    filename TEXT NOT NULL,
+                    content_type TEXT,
+                    delete_after_upload INTEGER NOT NULL,
+                    status TEXT NOT NULL,
+                    attempts INTEGER NOT NULL,
+                    next_attempt_at INTEGER NOT NULL,
+                    last_error TEXT,
+                    url TEXT,
+                    post_upload_options TEXT,
+                    created_at INTEGER NOT NULL,
+                    updated_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS upload_queue_next_attempt_at ON upload_queue (status, next_attempt_at);
+                -- 上次退出时未完成的上传重新排队
+                UPDATE upload_queue SET status = 'Pending' WHERE status = 'Uploading';",
+            )
+            .context("Create upload queue table failed")?;
+
+        Ok(Self { connection })
+    }
+
+    /// 添加任务，返回任务 id
+    pub fn add(&self, job: &UploadQueueJob) -> Result<i64> {
+        let post_upload_options = match &job.post_upload_options {
+            Some(options) => Some(
+                serde_json::to_string(options).context("Serialize post upload options failed")?,
+            ),
+            None => None,
+        };
+
+        self.connection
+            .execute(
+                "INSERT INTO upload_queue
+                    (profile_id, source_path, filename, content_type, delete_after_upload, status, attempts, next_attempt_at, last_error, url, post_upload_options, created_at, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                params![
+                    job.profile_id,
+                    job.source_path,
+                    job.filename,
+                    job.content_type,
+                    job.delete_after_upload,
+                    job.status.as_str(),
+                    job.attempts,
+                    job.next_attempt_at as i64,
+                    job.last_error,
+                    job.url,
+                    post_upload_options,
+                    job.created_at as i64,
+                    job.updated_at as i64,
+                ],
+            )
+            .context("Insert upload queue job failed")?;
+
+        Ok(self.connection.last_insert_rowid())
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<UploadQueueJob>> {
+        self.connection
+            .query_row(
+                "SELECT * FROM upload_queue WHERE id = ?1",
+                [id],
+                Self::read_job,
+            )
+            .optional()
+            .context("Query upload queue job failed")
+    }
+
+    /// 按创建时间倒序列出所有任务
+    pub fn list(&self) -> Result<Vec<UploadQueueJob>> {
+        let mut statement = self
+            .connection
+            .prepare_cached("SELECT * FROM upload_queue ORDER BY created_at DESC, id DESC")
+            .context("Prepare upload queue query failed")?;
+
+        let jobs = statement
+            .query_map([], Self::read_job)
+            .context("Query upload queue failed")?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Read upload queue failed")?;
+
+        Ok(jobs)
+    }
+
+    /// 取出最早到期的任务并标记为上传中
+    pub fn take_next_due(&self, now: u64) -> Result<Option<UploadQueueJob>> {
+        let id: Option<i64> = self
+            .connection
+            .query_row(
+                "SELECT id FROM upload_queue
+                WHERE status = 'Pending' AND next_attempt_at <= ?1
+                ORDER BY next_attempt_at, id
+                LIMIT 1",
+                [now as i64],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("Query due upload queue job failed")?;
+
+        let id = match id {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        self.connection
+            .execute(
+                "UPDATE upload_queue SET status = 'Uploading', attempts = attempts + 1, updated_at = ?2 WHERE id = ?1",
+                params![id, now as i64],
+            )
+            .context("Update upload queue job failed")?;
+
+        self.get(id)
+    }
+
+    /// 最早的待上传任务的时间，没有待上传任务时返回 None
+    pub fn get_next_attempt_at(&self) -> Result<Option<u64>> {
+        let next_attempt_at: Option<i64> = self
+            .connection
+            .query_row(
+                "SELECT MIN(next_attempt_at) FROM upload_queue WHERE status = 'Pending'",
+                [],
+                |row| row.get(0),
+            )
+            .context("Query upload queue next attempt failed")?;
+
+        Ok(next_attempt_at.map(|time| time as u64))
+    }
+
+    pub fn complete(&self, id: i64, url: &str, now: u64) -> Result<()> {
+        self.connection
+            .execute(
+                "UPDATE upload_queue SET status = 'Completed', url = ?2, last_error = NULL, updated_at = ?3 WHERE id = ?1",
+                params![id, url, now as i64],
+            )
+            .context("Complete upload queue job failed")?;
+
+        Ok(())
+    }
+
+    /// 记录失败，`next_attempt_at` 为空时不再重试
+    pub fn fail(&self, id: i64, error: &str, next_attempt_at: Option<u64>, now: u64) -> Result<()> {
+        let status = if next_attempt_at.is_some() {
+            UploadQueueJobStatus::Pending
+        } else {
+            UploadQueueJobStatus::Failed
+        };
+
+        self.connection
+            .execute(
+                "UPDATE upload_queue
+                SET status = ?2, last_error = ?3, next_attempt_at = IFNULL(?4, next_attempt_at), updated_at = ?5
+                WHERE id = ?1",
+                params![
+                    id,
+                    status.as_str(),
+                    error,
+                    next_attempt_at.map(|time| time as i64),
+                    now as i64,
+                ],
+            )
+            .context("Fail upload queue job failed")?;
+
+        Ok(())
+    }
+
+    /// 立即重试失败或等待中的任务，并重置尝试次数
+    pub fn retry(&self, id: i64, now: u64) -> Result<()> {
+        self.connection
+            .execute(
+                "UPDATE upload_queue SET status = 'Pending', attempts = 0, next_attempt_at = ?2, updated_at = ?2
+                WHERE id = ?1 AND status IN ('Pending', 'Failed')",
+                params![id, now as i64],
+            )
+            .context("Retry upload queue job failed")?;
+
+        Ok(())
+    }
+
+    pub fn remove(&self, id: i64) -> Result<()> {
+        self.connection
+            .execute("DELETE FROM upload_queue WHERE id = ?1", [id])
+            .context("Remove upload queue job failed")?;
+
+        Ok(())
+    }
+
+    /// 删除已完成的任务
+    pub fn clear_completed(&self) -> Result<()> {
+        self.connection
+            .execute("DELETE FROM upload_queue WHERE status = 'Completed'", [])
+            .context("Clear upload queue failed")?;
+
+        Ok(())
+    }
+
+    fn read_job(row: &Row) -> rusqlite::Result<UploadQueueJob> {
+        let status: String = row.get("status")?;
+        let post_upload_options: Option<String> = row.get("post_upload_options")?;
+
+        Ok(UploadQueueJob {
+            id: row.get("id")?,
+            profile_id: row.get("profile_id")?,
+            source_path: row.get("source_path")?,
+            filename: row.get("filename")?,
+            content_type: row.get("content_type")?,
+            delete_after_upload: row.get("delete_after_upload")?,
+            status: UploadQueueJobStatus::parse(&status).unwrap_or(UploadQueueJobStatus::Failed),
+            attempts: row.get("attempts")?,
+            next_attempt_at: row.get::<_, i64>("next_attempt_at")? as u64,
+            last_error: row.get("last_error")?,
+            url: row.get("url")?,
+            post_upload_options: post_upload_options
+                .and_then(|options| serde_json::from_str(&options).ok()),
+            created_at: row.get::<_, i64>("created_at")? as u64,
+            updated_at: row.get::<_, i64>("updated_at")? as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn get_job(filename: &str, now: u64) -> UploadQueueJob {
+        UploadQueueJob {
+            id: 0,
+            profile_id: "s3".to_string(),
+            source_path: format!("/tmp/{}", filename),
+            filename: filename.to_string(),
+            content_type: Some("image/png".to_string()),
+            delete_after_upload: false,
+            status: UploadQueueJobStatus::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            url: None,
+            post_upload_options: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_get_retry_delay() {
+        let options = UploadQueueOptions::default();
+        assert_eq!(options.get_retry_delay_secs(1), 5);
+        assert_eq!(options.get_retry_delay_secs(3), 20);
+        assert_eq!(options.get_retry_delay_secs(100), 30 * 60);
+
+        assert_eq!(options.get_next_attempt_at(1, 1000), Some(6000));
+        assert_eq!(options.get_next_attempt_at(10, 1000), None);
+    }
+
+    #[test]
+    fn test_upload_queue() {
+        let store =
+            UploadQueueStore::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+
+        let first_id = store.add(&get_job("first.png", 1000)).unwrap();
+        let second_id = store
+            .add(&UploadQueueJob {
+                post_upload_options: Some(PostUploadOptions {
+                    notification: true,
+                    ..Default::default()
+                }),
+                ..get_job("second.png", 2000)
+            })
+            .unwrap();
+
+        assert!(store
+            .get(first_id)
+            .unwrap()
+            .unwrap()
+            .post_upload_options
+            .is_none());
+        assert!(
+            store
+                .get(second_id)
+                .unwrap()
+                .unwrap()
+                .post_upload_options
+                .unwrap()
+                .notification
+        );
+
+        assert!(store.take_next_due(500).unwrap().is_none());
+
+        let job = store.take_next_due(3000).unwrap().unwrap();
+        assert_eq!(job.id, first_id);
+        assert_eq!(job.status, UploadQueueJobStatus::Uploading);
+        assert_eq!(job.attempts, 1);
+
+        store
+            .fail(first_id, "connection refused", Some(8000), 3000)
+            .unwrap();
+        assert_eq!(store.get_next_attempt_at().unwrap(), Some(2000));
+
+        let job = store.take_next_due(3000).unwrap().unwrap();
+        assert_eq!(job.id, second_id);
+        store
+            .complete(second_id, "https://cdn.example.com/second.png", 3000)
+            .unwrap();
+
+        assert!(store.take_next_due(3000).unwrap().is_none());
+        assert_eq!(store.get_next_attempt_at().unwrap(), Some(8000));
+
+        store.take_next_due(8000).unwrap().unwrap();
+        store.fail(first_id, "not found", None, 8000).unwrap();
+        assert_eq!(
+            store.get(first_id).unwrap().unwrap().status,
+            UploadQueueJobStatus::Failed
+        );
+        assert_eq!(store.get_next_attempt_at().unwrap(), None);
+
+        store.retry(first_id, 9000).unwrap();
+        let job = store.get(first_id).unwrap().unwrap();
+        assert_eq!(job.status, UploadQueueJobStatus::Pending);
+        assert_eq!(job.attempts, 0);
+
+        store.clear_completed().unwrap();
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_is_retryable_error() {
+        let status_error = |status: u16| -> anyhow::Error {
+            UploadStatusError {
+                status,
+                message: format!("Upload failed, HTTP status code: {}", status),
+            }
+            .into()
+        };
+
+        assert!(is_retryable_error(&status_error(503)));
+        assert!(is_retryable_error(&status_error(429)));
+        assert!(!is_retryable_error(&status_error(401)));
+        assert!(!is_retryable_error(&status_error(403)));
+
+        let connect_error: anyhow::Error =
+            std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused").into();
+        assert!(is_retryable_error(
+            &connect_error.context("Connect to sftp.example.com:22 failed")
+        ));
+
+        assert!(!is_retryable_error(&anyhow!("Upload profile not found")));
+    }
+}
@@ -0,0 +1,736 @@
+use std::{
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use futures_util::StreamExt;
+use reqwest::{Client, Response, StatusCode, Url, header};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+/**
+ * 进度通知的最小间隔
+ */
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/**
+ * 下载进度
+ */
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DownloadProgress {
+    /**
+     * 已下载的字节数，包括之前中断时已下载的部分
+     */
+    pub downloaded_bytes: u64,
+    /**
+     * 服务器未返回文件大小时为 None
+     */
+    pub total_bytes: Option<u64>,
+    /**
+     * 下载速度，字节每秒
+     */
+    pub speed: f64,
+    /**
+     * 剩余时间，秒
+     */
+    pub eta_secs: Option<f64>,
+}
+
+/// 从 Content-Range 中获取文件总大小，例如: bytes 100-199/200
+fn parse_content_range_total(content_range: &str) -> Option<u64> {
+    content_range.rsplit('/').next()?.trim().parse().ok()
+}
+
+fn get_content_range_total(response: &Response) -> Option<u64> {
+    response
+        .headers()
+        .get(header::CONTENT_RANGE)
+        .and_then(|content_range| content_range.to_str().ok())
+        .and_then(parse_content_range_total)
+}
+
+/// 获取用于 If-Range 的校验值，优先使用 ETag，弱 ETag 不能用于 If-Range
+fn get_response_validator(response: &Response) -> Option<String> {
+    let get_header = |name: header::HeaderName| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+    };
+
+    match get_header(header::ETAG) {
+        Some(etag) if !etag.starts_with("W/") => Some(etag),
+        _ => get_header(header::LAST_MODIFIED),
+    }
+}
+
+/// 删除临时文件和对应的校验值，重新下载
+async fn remove_temp_files(temp_file_path: &Path, validator_file_path: &Path) {
+    for path in [temp_file_path, validator_file_path] {
+        match tokio::fs::remove_file(path).await {
+            Ok(_) => (),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+            Err(e) => {
+                log::warn!(
+                    "[download_file] Failed to remove temp file {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// 发送下载请求
+///
+/// # 参数
+/// * `resume` - 继续下载的起始位置和 If-Range 校验值
+async fn send_download_request(
+    client: &Client,
+    download_url: &Url,
+    resume: Option<(u64, &str)>,
+) -> Result<Response, String> {
+    let mut request = client.get(download_url.clone());
+    if let Some((resume_bytes, validator)) = resume {
+        log::info!(
+            "[download_file] Resume download {} from {} bytes",
+            download_url,
+            resume_bytes
+        );
+        request = request
+            .header(header::RANGE, format!("bytes={}-", resume_bytes))
+            .header(header::IF_RANGE, validator);
+    }
+
+    match request.send().await {
+        Ok(resp) => Ok(resp),
+        Err(e) => Err(format!(
+            "[download_file] Failed to send download request to {}: {}",
+            download_url, e
+        )),
+    }
+}
+
+/// 下载可选的小文件，例如插件清单，服务器返回 404 时视为文件未发布
+///
+/// 返回文件是否存在
+///
+/// # 参数
+/// * `client` - HTTP 客户端
+/// * `download_url` - 下载地址
+/// * `download_file_path` - 下载完成后的文件路径，已存在时跳过下载
+pub async fn download_optional_file(
+    client: &Client,
+    download_url: Url,
+    download_file_path: &Path,
+) -> Result<bool, String> {
+    if download_file_path.exists() && download_file_path.is_file() {
+        return Ok(true);
+    }
+
+    let response = send_download_request(client, &download_url, None).await?;
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        log::warn!(
+            "[download_optional_file] File not published: {}",
+            download_url
+        );
+        return Ok(false);
+    }
+    if !status.is_success() {
+        return Err(format!(
+            "[download_optional_file] Download request failed with status {} for URL: {}",
+            status, download_url
+        ));
+    }
+
+    let content = match response.bytes().await {
+        Ok(content) => content,
+        Err(e) => {
+            return Err(format!(
+                "[download_optional_file] Failed to read response from {}: {}",
+                download_url, e
+            ));
+        }
+    };
+
+    let download_dir = download_file_path.parent().unwrap();
+    if let Err(e) = tokio::fs::create_dir_all(download_dir).await {
+        return Err(format!(
+            "[download_optional_file] Failed to create download directory {}: {}",
+            download_dir.display(),
+            e
+        ));
+    }
+
+    match tokio::fs::write(download_file_path, &content).await {
+        Ok(_) => Ok(true),
+        Err(e) => Err(format!(
+            "[download_optional_file] Failed to write download file {}: {}",
+            download_file_path.display(),
+            e
+        )),
+    }
+}
+
+/// 下载文件，先写入临时文件，中断后再次下载时通过 Range 请求继续下载
+///
+/// 临时文件旁保存响应的 ETag 或 Last-Modified，继续下载时通过 If-Range 确认服务器上的文件未变化，
+/// 没有校验值时重新下载
+///
+/// # 参数
+/// * `client` - HTTP 客户端
+/// * `download_url` - 下载地址
+/// * `download_file_path` - 下载完成后的文件路径，已存在时跳过下载
+/// * `cancel` - 为 true 时取消下载，保留临时文件
+/// * `on_progress` - 进度回调
+pub async fn download_file(
+    client: &Client,
+    download_url: Url,
+    download_file_path: &Path,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(DownloadProgress),
+) -> Result<(), String> {
+    if download_file_path.exists() && download_file_path.is_file() {
+        return Ok(());
+    }
+
+    // 确保下载目录存在
+    let download_dir = download_file_path.parent().unwrap();
+    if !download_dir.exists() {
+        match tokio::fs::create_dir_all(download_dir).await {
+            Ok(_) => (),
+            Err(e) => {
+                return Err(format!(
+                    "[download_file] Failed to create download directory {}: {}",
+                    download_dir.display(),
+                    e
+                ));
+            }
+        }
+    }
+
+    // 写入临时文件避免文件传输中断
+    let temp_file_path = download_file_path.with_extension("temp");
+    let validator_file_path = download_file_path.with_extension("temp.validator");
+    let mut resume_bytes = match tokio::fs::metadata(&temp_file_path).await {
+        Ok(metadata) if metadata.is_file() => metadata.len(),
+        _ => 0,
+    };
+    let validator = match tokio::fs::read_to_string(&validator_file_path).await {
+        Ok(validator) if !validator.trim().is_empty() => Some(validator.trim().to_string()),
+        _ => None,
+    };
+    if resume_bytes > 0 && validator.is_none() {
+        log::warn!(
+            "[download_file] Temp file has no validator, restart download: {}",
+            download_url
+        );
+        remove_temp_files(&temp_file_path, &validator_file_path).await;
+        resume_bytes = 0;
+    }
+
+    let resume = match &validator {
+        Some(validator) if resume_bytes > 0 => Some((resume_bytes, validator.as_str())),
+        _ => None,
+    };
+    let mut response = send_download_request(client, &download_url, resume).await?;
+
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE && resume_bytes > 0 {
+        // 只有服务器上的文件大小与临时文件一致时才认为已经下载完整
+        let total_bytes = get_content_range_total(&response);
+        if total_bytes != Some(resume_bytes) {
+            log::warn!(
+                "[download_file] Temp file size {} does not match remote size {:?}, restart download: {}",
+                resume_bytes,
+                total_bytes,
+                download_url
+            );
+            remove_temp_files(&temp_file_path, &validator_file_path).await;
+            resume_bytes = 0;
+            response = send_download_request(client, &download_url, None).await?;
+        }
+    }
+
+    let status = response.status();
+    let (mut downloaded_bytes, total_bytes, append) =
+        if status == StatusCode::RANGE_NOT_SATISFIABLE && resume_bytes > 0 {
+            // 临时文件已经下载完整
+            (resume_bytes, Some(resume_bytes), true)
+        } else if !status.is_success() {
+            return Err(format!(
+                "[download_file] Download request failed with status {} for URL: {}",
+                status, download_url
+            ));
+        } else if status == StatusCode::PARTIAL_CONTENT {
+            let total_bytes = get_content_range_total(&response).or_else(|| {
+                response
+                    .content_length()
+                    .map(|length| length + resume_bytes)
+            });
+            (resume_bytes, total_bytes, true)
+        } else {
+            // 服务器不支持 Range 或文件已变化时重新下载
+            let result = match get_response_validator(&response) {
+                Some(validator) => tokio::fs::write(&validator_file_path, validator).await,
+                None => match tokio::fs::remove_file(&validator_file_path).await {
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                    result => result,
+                },
+            };
+            if let Err(e) = result {
+                return Err(format!(
+                    "[download_file] Failed to save download validator {}: {}",
+                    validator_file_path.display(),
+                    e
+                ));
+            }
+
+            (0, response.content_length(), false)
+        };
+
+    let mut file = match tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(&temp_file_path)
+        .await
+    {
+        Ok(file) => file,
+        Err(e) => {
+            return Err(format!(
+                "[download_file] Failed to create download file {}: {}",
+                temp_file_path.display(),
+                e
+            ));
+        }
+    };
+
+    let start_bytes = downloaded_bytes;
+    let start_time = Instant::now();
+    let mut last_progress_time: Option<Instant> = None;
+    let get_progress = |downloaded_bytes: u64| {
+        let elapsed = start_time.elapsed().as_secs_f64();
+        let speed = if elapsed > 0.0 {
+            (downloaded_bytes - start_bytes) as f64 / elapsed
+        } else {
+            0.0
+        };
+
+        DownloadProgress {
+            downloaded_bytes,
+            total_bytes,
+            speed,
+            eta_secs: match total_bytes {
+                Some(total_bytes) if speed > 0.0 => {
+                    Some(total_bytes.saturating_sub(downloaded_bytes) as f64 / speed)
+                }
+                _ => None,
+            },
+        }
+    };
+
+    on_progress(get_progress(downloaded_bytes));
+
+    if status.is_success() {
+        let mut stream = response.bytes_stream();
+        while let Some(chunk_result) = stream.next().await {
+            if cancel.load(Ordering::Relaxed) {
+                let _ = file.flush().await;
+                return Err(format!(
+                    "[download_file] Download cancelled: {}",
+                    download_url
+                ));
+            }
+
+            let chunk = match chunk_result {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    let _ = file.flush().await;
+                    return Err(format!(
+                        "[download_file] Failed to read chunk from download stream: {}",
+                        e
+                    ));
+                }
+            };
+
+            if let Err(e) = file.write_all(&chunk).await {
+                return Err(format!(
+                    "[download_file] Failed to write chunk to file {}: {}",
+                    temp_file_path.display(),
+                    e
+                ));
+            }
+
+            downloaded_bytes += chunk.len() as u64;
+            if last_progress_time.is_none_or(|time| time.elapsed() >= PROGRESS_INTERVAL) {
+                last_progress_time = Some(Instant::now());
+                on_progress(get_progress(downloaded_bytes));
+            }
+        }
+    }
+
+    // 确保文件写入完成
+    if let Err(e) = file.flush().await {
+        return Err(format!(
+            "[download_file] Failed to flush download file {}: {}",
+            temp_file_path.display(),
+            e
+        ));
+    }
+    drop(file);
+
+    if total_bytes.is_some_and(|total_bytes| total_bytes != downloaded_bytes) {
+        return Err(format!(
+            "[download_file] Download incomplete, expected: {}, actual: {}",
+            total_bytes.unwrap_or_default(),
+            downloaded_bytes
+        ));
+    }
+
+    on_progress(get_progress(downloaded_bytes));
+
+    match tokio::fs::rename(&temp_file_path, download_file_path).await {
+        Ok(_) => (),
+        Err(e) => {
+            return Err(format!(
+                "[download_file] Failed to rename download file {} to {}: {}",
+                temp_file_path.display(),
+                download_file_path.display(),
+                e
+            ));
+        }
+    }
+    let _ = tokio::fs::remove_file(&validator_file_path).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::net::TcpListener;
+
+    const ETAG: &str = "\"v1\"";
+
+    /// 启动支持 Range 和 If-Range 的 HTTP 服务，返回地址和收到的 Range 起始位置
+    async fn start_server(content: Arc<Vec<u8>>) -> (Url, Arc<Mutex<Vec<u64>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+
+        let server_ranges = ranges.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let content = content.clone();
+                let ranges = server_ranges.clone();
+
+                tokio::spawn(async move {
+                    let mut reader = BufReader::new(stream);
+                    let mut range_start = 0;
+                    let mut if_range = None;
+                    let mut not_found = false;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).await.unwrap();
+                        let line = line.trim().to_ascii_lowercase();
+                        if line.is_empty() {
+                            break;
+                        }
+
+                        if line.starts_with("get /not_found") {
+                            not_found = true;
+                        }
+                        if let Some(range) = line.strip_prefix("range: bytes=") {
+                            range_start = range.trim_end_matches('-').parse().unwrap();
+                        }
+                        if let Some(value) = line.strip_prefix("if-range: ") {
+                            if_range = Some(value.to_string());
+                        }
+                    }
+                    ranges.lock().unwrap().push(range_start);
+
+                    // 校验值不一致时返回完整文件
+                    if if_range.is_some_and(|if_range| if_range != ETAG) {
+                        range_start = 0;
+                    }
+
+                    let (header, body) = if not_found {
+                        (
+                            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                                .to_string(),
+                            &content[..0],
+                        )
+                    } else if range_start as usize >= content.len() {
+                        (
+                            format!(
+                                "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Length: 0\r\nContent-Range: bytes */{}\r\nConnection: close\r\n\r\n",
+                                content.len()
+                            ),
+                            &content[..0],
+                        )
+                    } else if range_start > 0 {
+                        let body = &content[range_start as usize..];
+                        (
+                            format!(
+                                "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nETag: {}\r\nConnection: close\r\n\r\n",
+                                body.len(),
+                                range_start,
+                                content.len() - 1,
+                                content.len(),
+                                ETAG
+                            ),
+                            body,
+                        )
+                    } else {
+                        (
+                            format!(
+                                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nETag: {}\r\nConnection: close\r\n\r\n",
+                                content.len(),
+                                ETAG
+                            ),
+                            &content[..],
+                        )
+                    };
+
+                    let mut stream = reader.into_inner();
+                    // 取消下载时客户端会提前断开连接
+                    let _ = stream.write_all(header.as_bytes()).await;
+                    let _ = stream.write_all(body).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+
+        (
+            Url::parse(&format!("http://{}/plugin.zip", address)).unwrap(),
+            ranges,
+        )
+    }
+
+    fn get_download_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "snow_shot_plugin_download_test_{}_{}",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_parse_content_range_total() {
+        assert_eq!(parse_content_range_total("bytes 100-199/200"), Some(200));
+        assert_eq!(parse_content_range_total("bytes 100-199/*"), None);
+    }
+
+    /// 模拟上次中断时已下载的部分和保存的校验值
+    async fn write_temp_file(download_file_path: &Path, content: &[u8], validator: Option<&str>) {
+        tokio::fs::create_dir_all(download_file_path.parent().unwrap())
+            .await
+            .unwrap();
+        tokio::fs::write(download_file_path.with_extension("temp"), content)
+            .await
+            .unwrap();
+        if let Some(validator) = validator {
+            tokio::fs::write(
+                download_file_path.with_extension("temp.validator"),
+                validator,
+            )
+            .await
+            .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_file_resume() {
+        let content: Arc<Vec<u8>> = Arc::new((0..300_000).map(|i| (i % 251) as u8).collect());
+        let (url, ranges) = start_server(content.clone()).await;
+
+        let download_dir = get_download_dir("resume");
+        let download_file_path = download_dir.join("plugin.zip");
+        write_temp_file(&download_file_path, &content[..1000], Some(ETAG)).await;
+
+        let mut last_progress = None;
+        download_file(
+            &Client::new(),
+            url,
+            &download_file_path,
+            &AtomicBool::new(false),
+            |progress| last_progress = Some(progress),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(*ranges.lock().unwrap(), vec![1000]);
+        assert_eq!(
+            tokio::fs::read(&download_file_path).await.unwrap(),
+            *content
+        );
+        let last_progress = last_progress.unwrap();
+        assert_eq!(last_progress.downloaded_bytes, content.len() as u64);
+        assert_eq!(last_progress.total_bytes, Some(content.len() as u64));
+        assert!(!download_file_path.with_extension("temp.validator").exists());
+
+        tokio::fs::remove_dir_all(&download_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_download_file_restart() {
+        let content: Arc<Vec<u8>> = Arc::new((0..300_000).map(|i| (i % 251) as u8).collect());
+        let (url, ranges) = start_server(content.clone()).await;
+        let stale_content = vec![0u8; 1000];
+
+        // 服务器上的文件已变化，If-Range 不一致时返回完整文件
+        let download_dir = get_download_dir("changed");
+        let download_file_path = download_dir.join("plugin.zip");
+        write_temp_file(&download_file_path, &stale_content, Some("\"v0\"")).await;
+        download_file(
+            &Client::new(),
+            url.clone(),
+            &download_file_path,
+            &AtomicBool::new(false),
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            tokio::fs::read(&download_file_path).await.unwrap(),
+            *content
+        );
+        tokio::fs::remove_dir_all(&download_dir).await.unwrap();
+
+        // 没有校验值时不继续下载
+        let download_dir = get_download_dir("no_validator");
+        let download_file_path = download_dir.join("plugin.zip");
+        write_temp_file(&download_file_path, &stale_content, None).await;
+        download_file(
+            &Client::new(),
+            url,
+            &download_file_path,
+            &AtomicBool::new(false),
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            tokio::fs::read(&download_file_path).await.unwrap(),
+            *content
+        );
+        tokio::fs::remove_dir_all(&download_dir).await.unwrap();
+
+        assert_eq!(*ranges.lock().unwrap(), vec![1000, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_download_file_range_not_satisfiable() {
+        let content: Arc<Vec<u8>> = Arc::new((0..300_000).map(|i| (i % 251) as u8).collect());
+        let (url, ranges) = start_server(content.clone()).await;
+
+        // 临时文件已经下载完整
+        let download_dir = get_download_dir("complete");
+        let download_file_path = download_dir.join("plugin.zip");
+        write_temp_file(&download_file_path, &content, Some(ETAG)).await;
+        download_file(
+            &Client::new(),
+            url.clone(),
+            &download_file_path,
+            &AtomicBool::new(false),
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            tokio::fs::read(&download_file_path).await.unwrap(),
+            *content
+        );
+        tokio::fs::remove_dir_all(&download_dir).await.unwrap();
+
+        // 临时文件比服务器上的文件大时重新下载
+        let download_dir = get_download_dir("oversized");
+        let download_file_path = download_dir.join("plugin.zip");
+        let mut oversized_content = content.to_vec();
+        oversized_content.extend_from_slice(&[0u8; 10]);
+        write_temp_file(&download_file_path, &oversized_content, Some(ETAG)).await;
+        download_file(
+            &Client::new(),
+            url,
+            &download_file_path,
+            &AtomicBool::new(false),
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            tokio::fs::read(&download_file_path).await.unwrap(),
+            *content
+        );
+        tokio::fs::remove_dir_all(&download_dir).await.unwrap();
+
+        assert_eq!(
+            *ranges.lock().unwrap(),
+            vec![content.len() as u64, oversized_content.len() as u64, 0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_file_cancel() {
+        let content: Arc<Vec<u8>> = Arc::new(vec![1; 300_000]);
+        let (url, _) = start_server(content).await;
+
+        let download_dir = get_download_dir("cancel");
+        let download_file_path = download_dir.join("plugin.zip");
+
+        let result = download_file(
+            &Client::new(),
+            url,
+            &download_file_path,
+            &AtomicBool::new(true),
+            |_| {},
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(!download_file_path.exists());
+
+        tokio::fs::remove_dir_all(&download_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_download_optional_file() {
+        let content: Arc<Vec<u8>> = Arc::new(b"{}".to_vec());
+        let (url, _) = start_server(content.clone()).await;
+
+        let download_dir = get_download_dir("optional");
+        let download_file_path = download_dir.join("plugin.manifest.json");
+        assert!(
+            download_optional_file(&Client::new(), url.clone(), &download_file_path)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            tokio::fs::read(&download_file_path).await.unwrap(),
+            *content
+        );
+
+        // 服务器未发布的文件
+        let missing_file_path = download_dir.join("plugin.manifest.json.sig");
+        assert!(
+            !download_optional_file(
+                &Client::new(),
+                url.join("not_found.sig").unwrap(),
+                &missing_file_path
+            )
+            .await
+            .unwrap()
+        );
+        assert!(!missing_file_path.exists());
+
+        tokio::fs::remove_dir_all(&download_dir).await.unwrap();
+    }
+}
@@ -1,2 +1,4 @@
+pub mod download;
 pub mod plugin;
+pub mod plugin_manifest;
 pub mod plugin_service;
@@ -1,15 +1,22 @@
 use std::{
-    path::{Path, PathBuf},
-    sync::Arc,
+    path::{Component, Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
-use async_zip::tokio::read::seek::ZipFileReader;
-use futures_util::StreamExt;
+use async_zip::tokio::{read::seek::ZipFileReader, write::ZipFileWriter};
+use async_zip::{Compression, ZipEntryBuilder};
 use reqwest::{Client, Url};
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::{fs, io::BufReader, sync::RwLock};
-use tokio_util::compat::TokioAsyncWriteCompatExt;
+use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+
+use crate::download::{DownloadProgress, download_file, download_optional_file};
+use crate::plugin_manifest::PluginManifest;
+
 /**
  * 插件状态
  */
@@ -35,6 +42,19 @@ pub enum PluginStatus {
      * 卸载中
      */
     Uninstalling,
+    /**
+     * 文件与插件清单不一致
+     */
+    Corrupted,
+}
+
+/**
+ * 插件下载进度事件
+ */
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PluginDownloadProgressEvent {
+    name: String,
+    progress: DownloadProgress,
 }
 
 pub struct Plugin {
@@ -70,6 +90,14 @@ pub struct Plugin {
      * 插件下载服务 URL
      */
     plugin_download_service_url: Url,
+    /**
+     * 插件清单签名公钥，为空时只校验清单中的文件哈希
+     */
+    public_key: Option<String>,
+    /**
+     * 取消下载
+     */
+    download_cancelled: AtomicBool,
     /**
      * 应用句柄
      */
@@ -94,7 +122,52 @@ impl Plugin {
             .with_extension("zip")
     }
 
+    fn get_plugin_manifest_file_name(&self) -> String {
+        format!("{}.manifest.json", self.name)
+    }
+
+    fn get_plugin_manifest_signature_file_name(&self) -> String {
+        format!("{}.sig", self.get_plugin_manifest_file_name())
+    }
+
+    fn get_plugin_manifest_download_file_path(&self) -> PathBuf {
+        self.get_plugin_download_dir()
+            .join(self.get_plugin_manifest_file_name())
+    }
+
+    fn get_plugin_manifest_signature_download_file_path(&self) -> PathBuf {
+        self.get_plugin_download_dir()
+            .join(self.get_plugin_manifest_signature_file_name())
+    }
+
+    /**
+     * 安装后的插件清单，与插件目录放在同一目录
+     */
+    fn get_plugin_manifest_path(&self) -> PathBuf {
+        self.plugin_install_dir
+            .join(&self.version)
+            .join(self.get_plugin_manifest_file_name())
+    }
+
+    fn get_plugin_manifest_signature_path(&self) -> PathBuf {
+        self.plugin_install_dir
+            .join(&self.version)
+            .join(self.get_plugin_manifest_signature_file_name())
+    }
+
+    /**
+     * 离线安装时的临时目录
+     */
+    fn get_plugin_local_install_dir(&self) -> PathBuf {
+        self.get_plugin_download_dir()
+            .join(format!("{}.local", self.name))
+    }
+
     fn get_plugin_download_url(&self) -> Url {
+        self.get_plugin_download_file_url(&format!("{}.zip", self.name))
+    }
+
+    fn get_plugin_download_file_url(&self, file_name: &str) -> Url {
         let os_dir_name;
         #[cfg(target_os = "windows")]
         {
@@ -106,10 +179,7 @@ impl Plugin {
         }
 
         self.plugin_download_service_url
-            .join(&format!(
-                "{}/{}/{}.zip",
-                self.version, os_dir_name, self.name
-            ))
+            .join(&format!("{}/{}/{}", self.version, os_dir_name, file_name))
             .unwrap()
     }
 
@@ -137,11 +207,13 @@ impl Plugin {
      */
     pub async fn refresh_status(&self) {
         let current_status = self.get_status().await;
-        if current_status != PluginStatus::NotInstalled {
+        if current_status != PluginStatus::NotInstalled && current_status != PluginStatus::Corrupted
+        {
             return;
         }
 
         let plugin_dir = self.get_plugin_dir();
+        let manifest = self.read_installed_manifest().await;
 
         let status = if plugin_dir.exists()
             && plugin_dir.is_dir()
@@ -150,7 +222,32 @@ impl Plugin {
                 .iter()
                 .all(|file| plugin_dir.join(file).exists())
         {
-            PluginStatus::Installed
+            // 刷新状态时只校验文件大小，完整校验使用 verify
+            match manifest {
+                Some(Ok(manifest)) => match manifest.verify_files(&plugin_dir, false).await {
+                    Ok(_) => PluginStatus::Installed,
+                    Err(e) => {
+                        log::warn!(
+                            "[Plugin::refresh_status] Plugin {} is corrupted: {}",
+                            self.name,
+                            e
+                        );
+                        PluginStatus::Corrupted
+                    }
+                },
+                Some(Err(e)) => {
+                    log::warn!(
+                        "[Plugin::refresh_status] Plugin {} is corrupted: {}",
+                        self.name,
+                        e
+                    );
+                    PluginStatus::Corrupted
+                }
+                // 旧版本安装的插件没有清单
+                None => PluginStatus::Installed,
+            }
+        } else if manifest.is_some() {
+            PluginStatus::Corrupted
         } else {
             PluginStatus::NotInstalled
         };
@@ -158,6 +255,202 @@ impl Plugin {
         self.set_status(status).await;
     }
 
+    /**
+     * 读取安装时保存的插件清单，不存在时返回 None
+     */
+    async fn read_installed_manifest(&self) -> Option<Result<PluginManifest, String>> {
+        let manifest_path = self.get_plugin_manifest_path();
+        if !manifest_path.is_file() {
+            return None;
+        }
+
+        Some(match fs::read(&manifest_path).await {
+            // 安装时已校验
+            Ok(content) => PluginManifest::parse(&content),
+            Err(e) => Err(format!(
+                "[Plugin::read_installed_manifest] Failed to read manifest: {}",
+                e
+            )),
+        })
+    }
+
+    /**
+     * 读取插件清单，并校验插件信息和应用版本
+     *
+     * 配置了公钥且签名文件存在时校验签名，否则只通过清单中的文件哈希校验
+     */
+    async fn read_manifest(
+        &self,
+        manifest_path: &Path,
+        signature_path: &Path,
+    ) -> Result<PluginManifest, String> {
+        let content = match fs::read(manifest_path).await {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(format!(
+                    "[Plugin::read_manifest] Failed to read manifest {}: {}",
+                    manifest_path.display(),
+                    e
+                ));
+            }
+        };
+
+        let manifest = match &self.public_key {
+            Some(public_key) if signature_path.is_file() => {
+                let signature = match fs::read_to_string(signature_path).await {
+                    Ok(signature) => signature,
+                    Err(e) => {
+                        return Err(format!(
+                            "[Plugin::read_manifest] Failed to read manifest signature {}: {}",
+                            signature_path.display(),
+                            e
+                        ));
+                    }
+                };
+
+                PluginManifest::parse_signed(&content, &signature, public_key)?
+            }
+            public_key => {
+                log::warn!(
+                    "[Plugin::read_manifest] Skip signature verification of {}, public key configured: {}, signature found: {}",
+                    self.name,
+                    public_key.is_some(),
+                    signature_path.is_file()
+                );
+
+                PluginManifest::parse(&content)?
+            }
+        };
+
+        if manifest.name != self.name || manifest.version != self.version {
+            return Err(format!(
+                "[Plugin::read_manifest] Manifest mismatch, expected: {}@{}, actual: {}@{}",
+                self.name, self.version, manifest.name, manifest.version
+            ));
+        }
+
+        if let Some(app_handle) = &*self.app_handle.read().await {
+            manifest.check_app_version(&app_handle.package_info().version.to_string())?;
+        }
+
+        Ok(manifest)
+    }
+
+    /**
+     * 保存插件清单和签名，用于之后校验和导出
+     */
+    async fn save_manifest(
+        &self,
+        manifest_path: &Path,
+        signature_path: &Path,
+    ) -> Result<(), String> {
+        match fs::copy(manifest_path, self.get_plugin_manifest_path()).await {
+            Ok(_) => (),
+            Err(e) => {
+                return Err(format!(
+                    "[Plugin::save_manifest] Failed to save plugin manifest: {}",
+                    e
+                ));
+            }
+        }
+
+        let installed_signature_path = self.get_plugin_manifest_signature_path();
+        let result = if signature_path.is_file() {
+            fs::copy(signature_path, &installed_signature_path)
+                .await
+                .map(|_| ())
+        } else if installed_signature_path.exists() {
+            fs::remove_file(&installed_signature_path).await
+        } else {
+            Ok(())
+        };
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!(
+                "[Plugin::save_manifest] Failed to save plugin manifest signature: {}",
+                e
+            )),
+        }
+    }
+
+    /**
+     * 删除安装时保存的插件清单和签名
+     */
+    async fn remove_installed_manifest(&self) -> Result<(), String> {
+        for manifest_path in [
+            self.get_plugin_manifest_path(),
+            self.get_plugin_manifest_signature_path(),
+        ] {
+            if manifest_path.exists() {
+                match fs::remove_file(&manifest_path).await {
+                    Ok(_) => (),
+                    Err(e) => {
+                        return Err(format!(
+                            "[Plugin::remove_installed_manifest] Failed to remove plugin manifest: {}",
+                            e
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * 删除下载的插件文件，校验失败后重新下载
+     */
+    async fn remove_downloaded_files(&self) {
+        for path in [
+            self.get_plugin_download_file_path(),
+            self.get_plugin_manifest_download_file_path(),
+            self.get_plugin_manifest_signature_download_file_path(),
+        ] {
+            match fs::remove_file(&path).await {
+                Ok(_) => (),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+                Err(e) => {
+                    log::warn!(
+                        "[Plugin::remove_downloaded_files] Failed to remove {}: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    /**
+     * 按插件清单完整校验插件文件，校验失败时标记为损坏
+     */
+    pub async fn verify(&self) -> Result<(), String> {
+        let manifest = match self.read_installed_manifest().await {
+            Some(manifest) => manifest,
+            None => {
+                return Err(format!(
+                    "[Plugin::verify] Plugin manifest not found: {}",
+                    self.name
+                ));
+            }
+        };
+
+        let result = match manifest {
+            Ok(manifest) => manifest.verify_files(&self.get_plugin_dir(), true).await,
+            Err(e) => Err(e),
+        };
+
+        match &result {
+            Ok(_) => self.set_status(PluginStatus::Installed).await,
+            Err(e) => {
+                log::warn!("[Plugin::verify] Plugin {} is corrupted: {}", self.name, e);
+                self.set_status(PluginStatus::Corrupted).await;
+            }
+        }
+
+        result
+    }
+
     pub fn new(
         plugin_install_dir: &Path,
         plugin_download_dir: &Path,
@@ -165,6 +458,7 @@ impl Plugin {
         file_list: Vec<PathBuf>,
         version: String,
         plugin_download_service_url: Url,
+        public_key: Option<String>,
         app_handle: Arc<RwLock<Option<AppHandle>>>,
     ) -> Self {
         let relative_path = PathBuf::from(&version).join(&name);
@@ -178,6 +472,8 @@ impl Plugin {
             plugin_install_dir: plugin_install_dir.to_path_buf(),
             plugin_download_dir: plugin_download_dir.to_path_buf(),
             plugin_download_service_url,
+            public_key,
+            download_cancelled: AtomicBool::new(false),
             app_handle,
         };
 
@@ -237,7 +533,26 @@ impl Plugin {
         let entry_count = zip_reader.file().entries().len();
         for index in 0..entry_count {
             let entry = zip_reader.file().entries().get(index).unwrap();
-            let path = extract_to.join(entry.filename().as_str().unwrap());
+            let entry_path = match entry.filename().as_str() {
+                Ok(entry_path) => Path::new(entry_path),
+                Err(e) => {
+                    return Err(format!(
+                        "[Plugin::extract_zip_to_dir] Invalid entry name: {}",
+                        e
+                    ));
+                }
+            };
+            // 禁止解压到目标目录之外
+            if !entry_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+            {
+                return Err(format!(
+                    "[Plugin::extract_zip_to_dir] Invalid entry path: {}",
+                    entry_path.display()
+                ));
+            }
+            let path = extract_to.join(entry_path);
 
             let is_dir = entry.dir().unwrap();
 
@@ -332,111 +647,72 @@ impl Plugin {
         Ok(())
     }
 
+    /**
+     * 下载插件压缩包和插件清单，压缩包的下载进度通过 plugin-download-progress 事件通知
+     *
+     * 插件清单和签名是可选的，未发布时按旧版本的方式安装
+     */
     async fn download(&self) -> Result<(), String> {
-        let download_url = self.get_plugin_download_url();
-
-        // 获取下载文件路径
-        let download_file_path = self.get_plugin_download_file_path();
-
-        if download_file_path.exists() && download_file_path.is_file() {
-            return Ok(());
-        }
-
-        // 确保下载目录存在
-        let download_dir = download_file_path.parent().unwrap();
-        if !download_dir.exists() {
-            match tokio::fs::create_dir_all(download_dir).await {
-                Ok(_) => (),
-                Err(e) => {
-                    return Err(format!(
-                        "[Plugin::download] Failed to create download directory {}: {}",
-                        download_dir.display(),
-                        e
-                    ));
-                }
-            }
-        }
-
-        // 创建 HTTP 客户端
         let client = Client::new();
 
-        // 发送下载请求
-        let response = match client.get(download_url.clone()).send().await {
-            Ok(resp) => resp,
-            Err(e) => {
-                return Err(format!(
-                    "[Plugin::download] Failed to send download request to {}: {}",
-                    download_url, e
-                ));
-            }
-        };
-
-        // 检查响应状态
-        if !response.status().is_success() {
-            return Err(format!(
-                "[Plugin::download] Download request failed with status {} for URL: {}",
-                response.status(),
-                download_url
-            ));
+        let manifest_found = download_optional_file(
+            &client,
+            self.get_plugin_download_file_url(&self.get_plugin_manifest_file_name()),
+            &self.get_plugin_manifest_download_file_path(),
+        )
+        .await?;
+
+        if manifest_found {
+            download_optional_file(
+                &client,
+                self.get_plugin_download_file_url(&self.get_plugin_manifest_signature_file_name()),
+                &self.get_plugin_manifest_signature_download_file_path(),
+            )
+            .await?;
         }
 
-        // 创建目标文件
-        let temp_file_path = download_file_path.with_extension("temp"); // 写入临时文件避免文件传输终端
-        let mut file = match tokio::fs::File::create(&temp_file_path).await {
-            Ok(file) => file,
-            Err(e) => {
-                return Err(format!(
-                    "[Plugin::download] Failed to create download file {}: {}",
-                    temp_file_path.display(),
-                    e
-                ));
-            }
-        };
-
-        // 获取响应字节流并复制到文件
-        use tokio::io::AsyncWriteExt;
+        let app_handle = self.app_handle.read().await.clone();
+        download_file(
+            &client,
+            self.get_plugin_download_url(),
+            &self.get_plugin_download_file_path(),
+            &self.download_cancelled,
+            |progress| {
+                let app_handle = match &app_handle {
+                    Some(app_handle) => app_handle,
+                    None => return,
+                };
 
-        let mut stream = response.bytes_stream();
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = match chunk_result {
-                Ok(chunk) => chunk,
-                Err(e) => {
-                    return Err(format!(
-                        "[Plugin::download] Failed to read chunk from download stream: {}",
-                        e
-                    ));
+                let event = PluginDownloadProgressEvent {
+                    name: self.name.clone(),
+                    progress,
+                };
+                match app_handle.emit("plugin-download-progress", event) {
+                    Ok(_) => (),
+                    Err(e) => {
+                        log::error!(
+                            "[Plugin::download] Failed to emit plugin download progress: {}",
+                            e
+                        );
+                    }
                 }
-            };
-
-            if let Err(e) = file.write_all(&chunk).await {
-                return Err(format!(
-                    "[Plugin::download] Failed to write chunk to file {}: {}",
-                    temp_file_path.display(),
-                    e
-                ));
-            }
-        }
+            },
+        )
+        .await
+    }
 
-        // 确保文件写入完成
-        if let Err(e) = file.flush().await {
+    /**
+     * 取消正在进行的下载，已下载的部分会在下次安装时继续下载
+     */
+    pub async fn cancel_download(&self) -> Result<(), String> {
+        if self.get_status().await != PluginStatus::Downloading {
             return Err(format!(
-                "[Plugin::download] Failed to flush download file {}: {}",
-                temp_file_path.display(),
-                e
+                "[Plugin::cancel_download] Plugin is not downloading: {}",
+                self.name
             ));
         }
 
-        match tokio::fs::rename(&temp_file_path, &download_file_path).await {
-            Ok(_) => (),
-            Err(e) => {
-                return Err(format!(
-                    "[Plugin::download] Failed to rename download file {} to {}: {}",
-                    temp_file_path.display(),
-                    download_file_path.display(),
-                    e
-                ));
-            }
-        }
+        self.download_cancelled.store(true, Ordering::Relaxed);
 
         Ok(())
     }
@@ -455,7 +731,11 @@ impl Plugin {
 
         // 如果不是未安装状态
         // 如果要求强制安装并且是已安装状态
-        if !(status == PluginStatus::NotInstalled || (force && status == PluginStatus::Installed)) {
+        // 如果是损坏状态，则重新安装
+        if !(status == PluginStatus::NotInstalled
+            || status == PluginStatus::Corrupted
+            || (force && status == PluginStatus::Installed))
+        {
             return Ok(());
         }
 
@@ -483,7 +763,35 @@ impl Plugin {
 
         // 下载插件
         self.set_status(PluginStatus::Downloading).await;
-        self.download().await?;
+        self.download_cancelled.store(false, Ordering::Relaxed);
+        if let Err(e) = self.download().await {
+            self.set_status(status).await;
+            return Err(e);
+        }
+
+        let manifest_path = self.get_plugin_manifest_download_file_path();
+        let manifest = if manifest_path.is_file() {
+            match self
+                .read_manifest(
+                    &manifest_path,
+                    &self.get_plugin_manifest_signature_download_file_path(),
+                )
+                .await
+            {
+                Ok(manifest) => Some(manifest),
+                Err(e) => {
+                    self.remove_downloaded_files().await;
+                    self.set_status(status).await;
+                    return Err(e);
+                }
+            }
+        } else {
+            log::warn!(
+                "[Plugin::install] Manifest of {} is not published, install without verification",
+                self.name
+            );
+            None
+        };
 
         // 清除插件目录
         if self.get_plugin_dir().exists() {
@@ -503,6 +811,44 @@ impl Plugin {
         self.set_status(PluginStatus::Unzipping).await;
         self.unzip().await?;
 
+        let manifest = match manifest {
+            Some(manifest) => manifest,
+            None => {
+                // 旧版本的插件没有清单，只检查文件是否存在
+                let plugin_dir = self.get_plugin_dir();
+                if let Some(file) = self
+                    .file_list
+                    .iter()
+                    .find(|file| !plugin_dir.join(file).exists())
+                {
+                    self.remove_downloaded_files().await;
+                    self.set_status(PluginStatus::Corrupted).await;
+                    return Err(format!(
+                        "[Plugin::install] Plugin file not found: {}",
+                        file.display()
+                    ));
+                }
+
+                self.remove_installed_manifest().await?;
+                self.set_status(PluginStatus::Installed).await;
+
+                return Ok(());
+            }
+        };
+
+        if let Err(e) = manifest.verify_files(&self.get_plugin_dir(), true).await {
+            log::error!("[Plugin::install] Plugin {} is corrupted: {}", self.name, e);
+            self.remove_downloaded_files().await;
+            self.set_status(PluginStatus::Corrupted).await;
+            return Err(e);
+        }
+
+        self.save_manifest(
+            &manifest_path,
+            &self.get_plugin_manifest_signature_download_file_path(),
+        )
+        .await?;
+
         self.set_status(PluginStatus::Installed).await;
 
         Ok(())
@@ -523,8 +869,642 @@ impl Plugin {
             }
         }
 
+        self.remove_installed_manifest().await?;
+
         self.set_status(PluginStatus::NotInstalled).await;
 
         Ok(())
     }
+
+    /**
+     * 从本地压缩包或目录安装插件，校验方式与下载安装相同
+     *
+     * 压缩包或目录的结构与导出的一致：{name}/、{name}.manifest.json 和 {name}.manifest.json.sig
+     */
+    pub async fn install_from_local(&self, source_path: &Path) -> Result<(), String> {
+        log::info!(
+            "[Plugin::install_from_local] Installing plugin {} from: {}",
+            self.name,
+            source_path.display()
+        );
+
+        let status = self.get_status().await;
+        if matches!(
+            status,
+            PluginStatus::Downloading | PluginStatus::Unzipping | PluginStatus::Uninstalling
+        ) {
+            return Err(format!(
+                "[Plugin::install_from_local] Plugin is busy: {}, status: {:?}",
+                self.name, status
+            ));
+        }
+
+        let local_install_dir = self.get_plugin_local_install_dir();
+        if local_install_dir.exists() {
+            match fs::remove_dir_all(&local_install_dir).await {
+                Ok(_) => (),
+                Err(e) => {
+                    return Err(format!(
+                        "[Plugin::install_from_local] Failed to clear local install directory: {}",
+                        e
+                    ));
+                }
+            }
+        }
+
+        self.set_status(PluginStatus::Unzipping).await;
+        let result = self
+            .install_from_local_dir(source_path, &local_install_dir)
+            .await;
+
+        match fs::remove_dir_all(&local_install_dir).await {
+            Ok(_) => (),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+            Err(e) => {
+                log::warn!(
+                    "[Plugin::install_from_local] Failed to remove local install directory: {}",
+                    e
+                );
+            }
+        }
+
+        match result {
+            Ok(_) => {
+                self.set_status(PluginStatus::Installed).await;
+                Ok(())
+            }
+            Err(e) => {
+                // 根据插件目录重新判断状态
+                self.set_status(PluginStatus::NotInstalled).await;
+                self.refresh_status().await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn install_from_local_dir(
+        &self,
+        source_path: &Path,
+        local_install_dir: &Path,
+    ) -> Result<(), String> {
+        if source_path.is_dir() {
+            Self::copy_dir_all(source_path, local_install_dir).await?;
+        } else if source_path.is_file() {
+            Self::extract_zip_to_dir(source_path, local_install_dir).await?;
+        } else {
+            return Err(format!(
+                "[Plugin::install_from_local_dir] Source not found: {}",
+                source_path.display()
+            ));
+        }
+
+        let manifest_path = local_install_dir.join(self.get_plugin_manifest_file_name());
+        let signature_path = local_install_dir.join(self.get_plugin_manifest_signature_file_name());
+        let manifest = if manifest_path.is_file() {
+            Some(self.read_manifest(&manifest_path, &signature_path).await?)
+        } else {
+            log::warn!(
+                "[Plugin::install_from_local_dir] Manifest of {} not found, install without verification",
+                self.name
+            );
+            None
+        };
+
+        let local_plugin_dir = local_install_dir.join(&self.name);
+        if let Some(manifest) = &manifest {
+            manifest.verify_files(&local_plugin_dir, true).await?;
+        }
+        if let Some(file) = self
+            .file_list
+            .iter()
+            .find(|file| !local_plugin_dir.join(file).exists())
+        {
+            return Err(format!(
+                "[Plugin::install_from_local_dir] Plugin file not found: {}",
+                file.display()
+            ));
+        }
+
+        // 校验通过后再替换已安装的插件
+        let plugin_dir = self.get_plugin_dir();
+        if plugin_dir.exists() {
+            match fs::remove_dir_all(&plugin_dir).await {
+                Ok(_) => (),
+                Err(e) => {
+                    return Err(format!(
+                        "[Plugin::install_from_local_dir] Failed to clear plugin directory: {}",
+                        e
+                    ));
+                }
+            }
+        }
+        match fs::create_dir_all(plugin_dir.parent().unwrap()).await {
+            Ok(_) => (),
+            Err(e) => {
+                return Err(format!(
+                    "[Plugin::install_from_local_dir] Failed to create plugin directory: {}",
+                    e
+                ));
+            }
+        }
+
+        // 下载目录和插件目录不在同一磁盘时无法移动
+        if fs::rename(&local_plugin_dir, &plugin_dir).await.is_err() {
+            Self::copy_dir_all(&local_plugin_dir, &plugin_dir).await?;
+        }
+
+        match manifest {
+            Some(_) => self.save_manifest(&manifest_path, &signature_path).await,
+            None => self.remove_installed_manifest().await,
+        }
+    }
+
+    /// 复制目录
+    async fn copy_dir_all(from: &Path, to: &Path) -> Result<(), String> {
+        let mut dirs = vec![(from.to_path_buf(), to.to_path_buf())];
+        while let Some((from, to)) = dirs.pop() {
+            match fs::create_dir_all(&to).await {
+                Ok(_) => (),
+                Err(e) => {
+                    return Err(format!(
+                        "[Plugin::copy_dir_all] Failed to create directory {}: {}",
+                        to.display(),
+                        e
+                    ));
+                }
+            }
+
+            let mut entries = match fs::read_dir(&from).await {
+                Ok(entries) => entries,
+                Err(e) => {
+                    return Err(format!(
+                        "[Plugin::copy_dir_all] Failed to read directory {}: {}",
+                        from.display(),
+                        e
+                    ));
+                }
+            };
+
+            while let Some(entry) = entries.next_entry().await.map_err(|e| {
+                format!(
+                    "[Plugin::copy_dir_all] Failed to read directory entry: {}",
+                    e
+                )
+            })? {
+                let target = to.join(entry.file_name());
+                if entry.path().is_dir() {
+                    dirs.push((entry.path(), target));
+                    continue;
+                }
+
+                match fs::copy(entry.path(), &target).await {
+                    Ok(_) => (),
+                    Err(e) => {
+                        return Err(format!(
+                            "[Plugin::copy_dir_all] Failed to copy file {}: {}",
+                            entry.path().display(),
+                            e
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * 导出已安装的插件为压缩包，用于在其它设备上离线安装
+     */
+    pub async fn export(&self, output_file: &Path) -> Result<(), String> {
+        let manifest = match self.read_installed_manifest().await {
+            Some(manifest) => manifest?,
+            None => {
+                return Err(format!(
+                    "[Plugin::export] Plugin manifest not found: {}",
+                    self.name
+                ));
+            }
+        };
+
+        // 不导出损坏的插件
+        manifest.verify_files(&self.get_plugin_dir(), true).await?;
+
+        if let Some(parent) = output_file.parent() {
+            match fs::create_dir_all(parent).await {
+                Ok(_) => (),
+                Err(e) => {
+                    return Err(format!(
+                        "[Plugin::export] Failed to create output directory: {}",
+                        e
+                    ));
+                }
+            }
+        }
+
+        let temp_file_path = output_file.with_extension("temp");
+        if let Err(e) = self.write_export_zip(&manifest, &temp_file_path).await {
+            let _ = fs::remove_file(&temp_file_path).await;
+            return Err(e);
+        }
+
+        match fs::rename(&temp_file_path, output_file).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!(
+                "[Plugin::export] Failed to rename export file {} to {}: {}",
+                temp_file_path.display(),
+                output_file.display(),
+                e
+            )),
+        }
+    }
+
+    async fn write_export_zip(
+        &self,
+        manifest: &PluginManifest,
+        zip_path: &Path,
+    ) -> Result<(), String> {
+        use tokio::io::AsyncWriteExt;
+
+        let file = match fs::File::create(zip_path).await {
+            Ok(file) => file,
+            Err(e) => {
+                return Err(format!(
+                    "[Plugin::write_export_zip] Failed to create zip file: {}",
+                    e
+                ));
+            }
+        };
+        let mut writer = ZipFileWriter::with_tokio(file);
+
+        let mut entries = vec![(
+            self.get_plugin_manifest_path(),
+            self.get_plugin_manifest_file_name(),
+        )];
+        let signature_path = self.get_plugin_manifest_signature_path();
+        if signature_path.is_file() {
+            entries.push((
+                signature_path,
+                self.get_plugin_manifest_signature_file_name(),
+            ));
+        }
+        // 只导出清单中的文件，压缩包中统一使用 / 分隔路径
+        let plugin_dir = self.get_plugin_dir();
+        for file in &manifest.files {
+            let entry_name = Path::new(&self.name)
+                .join(&file.path)
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            entries.push((plugin_dir.join(&file.path), entry_name));
+        }
+
+        for (path, entry_name) in entries {
+            let source = match fs::File::open(&path).await {
+                Ok(source) => source,
+                Err(e) => {
+                    return Err(format!(
+                        "[Plugin::write_export_zip] Failed to open file {}: {}",
+                        path.display(),
+                        e
+                    ));
+                }
+            };
+
+            let entry = ZipEntryBuilder::new(entry_name.into(), Compression::Deflate);
+            let mut entry_writer = match writer.write_entry_stream(entry).await {
+                Ok(entry_writer) => entry_writer,
+                Err(e) => {
+                    return Err(format!(
+                        "[Plugin::write_export_zip] Failed to create zip entry: {}",
+                        e
+                    ));
+                }
+            };
+
+            match futures_lite::io::copy(&mut source.compat(), &mut entry_writer).await {
+                Ok(_) => (),
+                Err(e) => {
+                    return Err(format!(
+                        "[Plugin::write_export_zip] Failed to write zip entry {}: {}",
+                        path.display(),
+                        e
+                    ));
+                }
+            }
+
+            match entry_writer.close().await {
+                Ok(_) => (),
+                Err(e) => {
+                    return Err(format!(
+                        "[Plugin::write_export_zip] Failed to close zip entry: {}",
+                        e
+                    ));
+                }
+            }
+        }
+
+        let file = match writer.close().await {
+            Ok(file) => file,
+            Err(e) => {
+                return Err(format!(
+                    "[Plugin::write_export_zip] Failed to close zip file: {}",
+                    e
+                ));
+            }
+        };
+
+        match file.into_inner().flush().await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!(
+                "[Plugin::write_export_zip] Failed to flush zip file: {}",
+                e
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin_manifest::PluginManifestFile;
+    use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+    use ed25519_dalek::{Signer, SigningKey};
+    use sha2::{Digest, Sha256};
+
+    const PLUGIN_NAME: &str = "paddle_ocr";
+    const PLUGIN_VERSION: &str = "0.5.0";
+    const PLUGIN_FILES: [(&str, &[u8]); 2] =
+        [("models/det.onnx", b"det model"), ("config.json", b"{}")];
+
+    fn get_signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn get_plugin(root_dir: &Path) -> Plugin {
+        Plugin::new(
+            &root_dir.join("install"),
+            &root_dir.join("download"),
+            PLUGIN_NAME.to_string(),
+            PLUGIN_FILES
+                .iter()
+                .map(|(path, _)| PathBuf::from(path))
+                .collect(),
+            PLUGIN_VERSION.to_string(),
+            Url::parse("https://snowshot.top/plugins/").unwrap(),
+            Some(BASE64.encode(get_signing_key().verifying_key().to_bytes())),
+            Arc::new(RwLock::new(None)),
+        )
+    }
+
+    /// 创建与导出结构一致的本地安装目录
+    async fn write_local_source(
+        source_dir: &Path,
+        version: &str,
+        files: &[(&str, &[u8])],
+        signing_key: &SigningKey,
+    ) {
+        let plugin_dir = source_dir.join(PLUGIN_NAME);
+        for (path, content) in files {
+            let path = plugin_dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+            fs::write(&path, content).await.unwrap();
+        }
+
+        let manifest = serde_json::to_vec(&PluginManifest {
+            name: PLUGIN_NAME.to_string(),
+            version: version.to_string(),
+            min_app_version: None,
+            files: files
+                .iter()
+                .map(|(path, content)| PluginManifestFile {
+                    path: PathBuf::from(path),
+                    size: content.len() as u64,
+                    sha256: Sha256::digest(content)
+                        .iter()
+                        .map(|byte| format!("{:02x}", byte))
+                        .collect(),
+                })
+                .collect(),
+        })
+        .unwrap();
+        let manifest_path = source_dir.join(format!("{}.manifest.json", PLUGIN_NAME));
+        fs::write(&manifest_path, &manifest).await.unwrap();
+        fs::write(
+            manifest_path.with_extension("json.sig"),
+            BASE64.encode(signing_key.sign(&manifest).to_bytes()),
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn assert_installed_files(plugin: &Plugin, files: &[(&str, &[u8])]) {
+        assert_eq!(plugin.get_status().await, PluginStatus::Installed);
+        for (path, content) in files {
+            assert_eq!(
+                fs::read(plugin.get_plugin_dir().join(path)).await.unwrap(),
+                *content
+            );
+        }
+        assert!(plugin.verify().await.is_ok());
+    }
+
+    fn get_root_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "snow_shot_plugin_test_{}_{}",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_install_from_local_dir_and_export() {
+        let root_dir = get_root_dir("export");
+        let plugin = get_plugin(&root_dir);
+
+        let source_dir = root_dir.join("source");
+        write_local_source(
+            &source_dir,
+            PLUGIN_VERSION,
+            &PLUGIN_FILES,
+            &get_signing_key(),
+        )
+        .await;
+        plugin.install_from_local(&source_dir).await.unwrap();
+        assert_installed_files(&plugin, &PLUGIN_FILES).await;
+        // 安装完成后清理临时目录
+        assert!(!plugin.get_plugin_local_install_dir().exists());
+
+        let export_path = root_dir.join("export").join("paddle_ocr.zip");
+        plugin.export(&export_path).await.unwrap();
+        assert!(export_path.is_file());
+
+        plugin.uninstall().await.unwrap();
+        assert!(!plugin.get_plugin_dir().exists());
+        assert!(!plugin.get_plugin_manifest_path().exists());
+
+        plugin.install_from_local(&export_path).await.unwrap();
+        assert_installed_files(&plugin, &PLUGIN_FILES).await;
+        assert!(plugin.get_plugin_manifest_signature_path().is_file());
+
+        fs::remove_dir_all(&root_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_install_from_local_rejected() {
+        let root_dir = get_root_dir("rejected");
+        let plugin = get_plugin(&root_dir);
+
+        let source_dir = root_dir.join("source");
+        write_local_source(
+            &source_dir,
+            PLUGIN_VERSION,
+            &PLUGIN_FILES,
+            &get_signing_key(),
+        )
+        .await;
+        plugin.install_from_local(&source_dir).await.unwrap();
+
+        // 文件被修改
+        let tampered_dir = root_dir.join("tampered");
+        write_local_source(
+            &tampered_dir,
+            PLUGIN_VERSION,
+            &PLUGIN_FILES,
+            &get_signing_key(),
+        )
+        .await;
+        fs::write(tampered_dir.join(PLUGIN_NAME).join("config.json"), b"[]")
+            .await
+            .unwrap();
+
+        // 使用其它密钥签名
+        let unsigned_dir = root_dir.join("unsigned");
+        write_local_source(
+            &unsigned_dir,
+            PLUGIN_VERSION,
+            &[
+                ("models/det.onnx", b"other model".as_slice()),
+                ("config.json", b"{}".as_slice()),
+            ],
+            &SigningKey::from_bytes(&[8u8; 32]),
+        )
+        .await;
+
+        // 插件版本不一致
+        let mismatched_dir = root_dir.join("mismatched");
+        write_local_source(&mismatched_dir, "0.6.0", &PLUGIN_FILES, &get_signing_key()).await;
+
+        // 缺少插件需要的文件
+        let incomplete_dir = root_dir.join("incomplete");
+        write_local_source(
+            &incomplete_dir,
+            PLUGIN_VERSION,
+            &PLUGIN_FILES[..1],
+            &get_signing_key(),
+        )
+        .await;
+
+        for source_dir in [
+            tampered_dir,
+            unsigned_dir,
+            mismatched_dir,
+            incomplete_dir,
+            root_dir.join("not_found"),
+        ] {
+            assert!(
+                plugin.install_from_local(&source_dir).await.is_err(),
+                "{}",
+                source_dir.display()
+            );
+            assert_installed_files(&plugin, &PLUGIN_FILES).await;
+        }
+
+        fs::remove_dir_all(&root_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_install_from_local_without_signature() {
+        let root_dir = get_root_dir("without_signature");
+        let plugin = get_plugin(&root_dir);
+        let signature_file_name = format!("{}.manifest.json.sig", PLUGIN_NAME);
+
+        // 没有签名时只校验文件哈希
+        let source_dir = root_dir.join("source");
+        write_local_source(
+            &source_dir,
+            PLUGIN_VERSION,
+            &PLUGIN_FILES,
+            &get_signing_key(),
+        )
+        .await;
+        fs::remove_file(source_dir.join(&signature_file_name))
+            .await
+            .unwrap();
+        plugin.install_from_local(&source_dir).await.unwrap();
+        assert_installed_files(&plugin, &PLUGIN_FILES).await;
+        assert!(!plugin.get_plugin_manifest_signature_path().exists());
+
+        let tampered_dir = root_dir.join("tampered");
+        write_local_source(
+            &tampered_dir,
+            PLUGIN_VERSION,
+            &PLUGIN_FILES,
+            &get_signing_key(),
+        )
+        .await;
+        fs::remove_file(tampered_dir.join(&signature_file_name))
+            .await
+            .unwrap();
+        fs::write(tampered_dir.join(PLUGIN_NAME).join("config.json"), b"[]")
+            .await
+            .unwrap();
+        assert!(plugin.install_from_local(&tampered_dir).await.is_err());
+        assert_installed_files(&plugin, &PLUGIN_FILES).await;
+
+        // 未配置公钥时忽略签名
+        let mut unconfigured_plugin = get_plugin(&root_dir);
+        unconfigured_plugin.public_key = None;
+        let signed_dir = root_dir.join("signed");
+        write_local_source(
+            &signed_dir,
+            PLUGIN_VERSION,
+            &PLUGIN_FILES,
+            &SigningKey::from_bytes(&[8u8; 32]),
+        )
+        .await;
+        unconfigured_plugin
+            .install_from_local(&signed_dir)
+            .await
+            .unwrap();
+        assert_installed_files(&unconfigured_plugin, &PLUGIN_FILES).await;
+
+        // 旧版本导出的插件没有清单
+        let legacy_dir = root_dir.join("legacy");
+        write_local_source(
+            &legacy_dir,
+            PLUGIN_VERSION,
+            &PLUGIN_FILES,
+            &get_signing_key(),
+        )
+        .await;
+        fs::remove_file(legacy_dir.join(format!("{}.manifest.json", PLUGIN_NAME)))
+            .await
+            .unwrap();
+        fs::remove_file(legacy_dir.join(&signature_file_name))
+            .await
+            .unwrap();
+        plugin.install_from_local(&legacy_dir).await.unwrap();
+        assert_eq!(plugin.get_status().await, PluginStatus::Installed);
+        assert!(!plugin.get_plugin_manifest_path().exists());
+        for (path, content) in PLUGIN_FILES {
+            assert_eq!(
+                fs::read(plugin.get_plugin_dir().join(path)).await.unwrap(),
+                content
+            );
+        }
+
+        fs::remove_dir_all(&root_dir).await.unwrap();
+    }
 }
@@ -0,0 +1,316 @@
+use std::{
+    cmp::Ordering,
+    path::{Component, Path, PathBuf},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
+
+/// 插件清单签名公钥，Base64 编码，构建时通过 SNOW_SHOT_PLUGIN_PUBLIC_KEY 环境变量指定
+///
+/// 未指定时不校验签名，只通过清单中的文件哈希校验插件
+pub const PLUGIN_PUBLIC_KEY: Option<&str> = option_env!("SNOW_SHOT_PLUGIN_PUBLIC_KEY");
+
+/**
+ * 插件清单中的文件
+ */
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PluginManifestFile {
+    /**
+     * 相对插件目录的路径
+     */
+    pub path: PathBuf,
+    /**
+     * 文件大小，字节
+     */
+    pub size: u64,
+    /**
+     * 小写十六进制的 SHA-256
+     */
+    pub sha256: String,
+}
+
+/**
+ * 插件清单，与插件压缩包一起发布
+ */
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PluginManifest {
+    pub name: String,
+    pub version: String,
+    /**
+     * 最低应用版本
+     */
+    #[serde(default)]
+    pub min_app_version: Option<String>,
+    pub files: Vec<PluginManifestFile>,
+}
+
+/// 比较版本号，只比较数字部分，例如: 0.9.1 < 0.10.0
+pub fn compare_version(left: &str, right: &str) -> Ordering {
+    let parse = |version: &str| -> Vec<u64> {
+        version
+            .trim()
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse::<u64>().unwrap_or(0))
+            .collect()
+    };
+
+    let left = parse(left);
+    let right = parse(right);
+    for index in 0..left.len().max(right.len()) {
+        let ordering = left
+            .get(index)
+            .unwrap_or(&0)
+            .cmp(right.get(index).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// 计算文件的 SHA-256
+pub async fn get_file_sha256(path: &Path) -> Result<String, String> {
+    let mut file = tokio::fs::File::open(path).await.map_err(|e| {
+        format!(
+            "[get_file_sha256] Failed to open file {}: {}",
+            path.display(),
+            e
+        )
+    })?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let size = file.read(&mut buffer).await.map_err(|e| {
+            format!(
+                "[get_file_sha256] Failed to read file {}: {}",
+                path.display(),
+                e
+            )
+        })?;
+        if size == 0 {
+            break;
+        }
+
+        hasher.update(&buffer[..size]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+impl PluginManifest {
+    /// 校验 ed25519 签名后解析清单
+    ///
+    /// # 参数
+    /// * `content` - 清单文件内容
+    /// * `signature` - Base64 编码的签名
+    /// * `public_key` - Base64 编码的公钥
+    pub fn parse_signed(content: &[u8], signature: &str, public_key: &str) -> Result<Self, String> {
+        Self::verify_signature(content, signature, public_key)?;
+
+        Self::parse(content)
+    }
+
+    /// 解析清单，不校验签名
+    pub fn parse(content: &[u8]) -> Result<Self, String> {
+        let manifest: PluginManifest = serde_json::from_slice(content)
+            .map_err(|e| format!("[PluginManifest::parse] Failed to parse manifest: {}", e))?;
+
+        for file in &manifest.files {
+            // 禁止访问插件目录之外的文件
+            if file.path.as_os_str().is_empty()
+                || !file
+                    .path
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)))
+            {
+                return Err(format!(
+                    "[PluginManifest::parse] Invalid file path: {}",
+                    file.path.display()
+                ));
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    fn verify_signature(content: &[u8], signature: &str, public_key: &str) -> Result<(), String> {
+        let public_key: [u8; 32] = BASE64
+            .decode(public_key.trim())
+            .ok()
+            .and_then(|public_key| public_key.try_into().ok())
+            .ok_or_else(|| "[PluginManifest::verify_signature] Invalid public key".to_string())?;
+        let public_key = VerifyingKey::from_bytes(&public_key).map_err(|e| {
+            format!(
+                "[PluginManifest::verify_signature] Invalid public key: {}",
+                e
+            )
+        })?;
+
+        let signature: [u8; 64] = BASE64
+            .decode(signature.trim())
+            .ok()
+            .and_then(|signature| signature.try_into().ok())
+            .ok_or_else(|| "[PluginManifest::verify_signature] Invalid signature".to_string())?;
+
+        public_key
+            .verify(content, &Signature::from_bytes(&signature))
+            .map_err(|e| {
+                format!(
+                    "[PluginManifest::verify_signature] Signature verification failed: {}",
+                    e
+                )
+            })
+    }
+
+    /// 检查应用版本是否满足最低版本
+    pub fn check_app_version(&self, app_version: &str) -> Result<(), String> {
+        match &self.min_app_version {
+            Some(min_app_version)
+                if compare_version(app_version, min_app_version) == Ordering::Less =>
+            {
+                Err(format!(
+                    "[PluginManifest::check_app_version] Plugin {} requires app version {}, current version: {}",
+                    self.name, min_app_version, app_version
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 校验插件目录中的文件
+    ///
+    /// # 参数
+    /// * `plugin_dir` - 插件目录
+    /// * `check_hash` - 为 false 时只校验文件大小
+    pub async fn verify_files(&self, plugin_dir: &Path, check_hash: bool) -> Result<(), String> {
+        for file in &self.files {
+            let path = plugin_dir.join(&file.path);
+
+            let size = match tokio::fs::metadata(&path).await {
+                Ok(metadata) if metadata.is_file() => metadata.len(),
+                _ => {
+                    return Err(format!(
+                        "[PluginManifest::verify_files] File not found: {}",
+                        path.display()
+                    ));
+                }
+            };
+            if size != file.size {
+                return Err(format!(
+                    "[PluginManifest::verify_files] File size mismatch: {}, expected: {}, actual: {}",
+                    path.display(),
+                    file.size,
+                    size
+                ));
+            }
+
+            if check_hash {
+                let sha256 = get_file_sha256(&path).await?;
+                if !sha256.eq_ignore_ascii_case(&file.sha256) {
+                    return Err(format!(
+                        "[PluginManifest::verify_files] File hash mismatch: {}",
+                        path.display()
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn get_manifest_content() -> Vec<u8> {
+        serde_json::to_vec(&PluginManifest {
+            name: "paddle_ocr".to_string(),
+            version: "0.5.0".to_string(),
+            min_app_version: Some("0.5.0".to_string()),
+            files: vec![PluginManifestFile {
+                path: PathBuf::from("models/det.onnx"),
+                size: 5,
+                sha256: "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                    .to_string(),
+            }],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_compare_version() {
+        assert_eq!(compare_version("0.9.1", "0.10.0"), Ordering::Less);
+        assert_eq!(compare_version("v1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_version("1.3.0-beta.1", "1.2.9"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let content = get_manifest_content();
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = BASE64.encode(signing_key.verifying_key().to_bytes());
+        let signature = BASE64.encode(signing_key.sign(&content).to_bytes());
+
+        let manifest = PluginManifest::parse_signed(&content, &signature, &public_key).unwrap();
+        assert_eq!(manifest.files.len(), 1);
+        assert!(manifest.check_app_version("0.5.1").is_ok());
+        assert!(manifest.check_app_version("0.4.9").is_err());
+
+        let mut tampered = content.clone();
+        tampered[0] = b' ';
+        assert!(PluginManifest::parse_signed(&tampered, &signature, &public_key).is_err());
+        assert!(PluginManifest::parse_signed(&content, "", &public_key).is_err());
+        let other_public_key = BASE64.encode(
+            SigningKey::from_bytes(&[8u8; 32])
+                .verifying_key()
+                .to_bytes(),
+        );
+        assert!(PluginManifest::parse_signed(&content, &signature, &other_public_key).is_err());
+        assert!(PluginManifest::parse(&content).is_ok());
+
+        let content =
+            br#"{"name":"a","version":"1","files":[{"path":"../a","size":0,"sha256":""}]}"#;
+        assert!(PluginManifest::parse(content).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_files() {
+        let plugin_dir = std::env::temp_dir().join(format!(
+            "snow_shot_plugin_manifest_test_{}",
+            std::process::id()
+        ));
+        tokio::fs::create_dir_all(plugin_dir.join("models"))
+            .await
+            .unwrap();
+        tokio::fs::write(plugin_dir.join("models/det.onnx"), b"hello")
+            .await
+            .unwrap();
+
+        let manifest = PluginManifest::parse(&get_manifest_content()).unwrap();
+        assert!(manifest.verify_files(&plugin_dir, true).await.is_ok());
+
+        tokio::fs::write(plugin_dir.join("models/det.onnx"), b"world")
+            .await
+            .unwrap();
+        assert!(manifest.verify_files(&plugin_dir, false).await.is_ok());
+        assert!(manifest.verify_files(&plugin_dir, true).await.is_err());
+
+        tokio::fs::remove_dir_all(&plugin_dir).await.unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use crate::plugin::{Plugin, PluginStatus};
+use crate::plugin_manifest::PLUGIN_PUBLIC_KEY;
 use dashmap::DashMap;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
@@ -136,6 +137,10 @@ impl PluginService {
             file_list,
             self.version.read().await.clone(),
             self.plugin_download_service_url.read().await.clone(),
+            // 构建时未设置变量的值为空字符串
+            PLUGIN_PUBLIC_KEY
+                .filter(|public_key| !public_key.trim().is_empty())
+                .map(|public_key| public_key.to_string()),
             self.app_handle.clone(),
         )
     }
@@ -181,6 +186,82 @@ impl PluginService {
         plugin_guard.uninstall().await
     }
 
+    /**
+     * 从本地压缩包或目录安装插件
+     */
+    pub async fn install_plugin_from_local(
+        &self,
+        name: String,
+        source_path: &Path,
+    ) -> Result<(), String> {
+        let plugin = match self.plugins.get(&name) {
+            Some(plugin) => plugin,
+            None => {
+                return Err(format!(
+                    "[PluginService::install_plugin_from_local] Plugin not found: {}",
+                    name
+                ));
+            }
+        };
+
+        let plugin_guard = plugin.read().await;
+        plugin_guard.install_from_local(source_path).await
+    }
+
+    /**
+     * 导出已安装的插件为压缩包
+     */
+    pub async fn export_plugin(&self, name: String, output_file: &Path) -> Result<(), String> {
+        let plugin = match self.plugins.get(&name) {
+            Some(plugin) => plugin,
+            None => {
+                return Err(format!(
+                    "[PluginService::export_plugin] Plugin not found: {}",
+                    name
+                ));
+            }
+        };
+
+        let plugin_guard = plugin.read().await;
+        plugin_guard.export(output_file).await
+    }
+
+    /**
+     * 取消插件下载
+     */
+    pub async fn cancel_plugin_download(&self, name: String) -> Result<(), String> {
+        let plugin = match self.plugins.get(&name) {
+            Some(plugin) => plugin,
+            None => {
+                return Err(format!(
+                    "[PluginService::cancel_plugin_download] Plugin not found: {}",
+                    name
+                ));
+            }
+        };
+
+        let plugin_guard = plugin.read().await;
+        plugin_guard.cancel_download().await
+    }
+
+    /**
+     * 按插件清单校验插件文件
+     */
+    pub async fn verify_plugin(&self, name: String) -> Result<(), String> {
+        let plugin = match self.plugins.get(&name) {
+            Some(plugin) => plugin,
+            None => {
+                return Err(format!(
+                    "[PluginService::verify_plugin] Plugin not found: {}",
+                    name
+                ));
+            }
+        };
+
+        let plugin_guard = plugin.read().await;
+        plugin_guard.verify().await
+    }
+
     pub async fn get_plugins_status(&self) -> Result<Vec<PluginStatusResult>, String> {
         let mut plugins_status = Vec::new();
         for plugin in self.plugins.iter() {
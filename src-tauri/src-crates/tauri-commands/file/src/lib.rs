@@ -21,6 +21,23 @@ pub async fn save_file(request: tauri::ipc::Request<'_>) -> Result<(), String> {
         None => return Err(String::from("[save_file] Missing file path")),
     };
 
+    let file_type: String = match request.headers().get("x-file-type") {
+        Some(header) => match BASE64_STANDARD.decode(header.to_str().unwrap()) {
+            Ok(file_type) => String::from_utf8(file_type).unwrap(),
+            Err(_) => return Err(String::from("[save_file] Invalid file type")),
+        },
+        None => return Err(String::from("[save_file] Missing file type")),
+    };
+
+    save_file_data(file_path, &file_type, file_data).await
+}
+
+/// 按文件类型写入文件，avif 和 jpeg-xl 的数据为 WebP 格式，写入前重新编码
+pub async fn save_file_data(
+    file_path: PathBuf,
+    file_type: &str,
+    file_data: &[u8],
+) -> Result<(), String> {
     if let Some(parent_dir) = file_path.parent() {
         if !parent_dir.exists() {
             if let Err(e) = fs::create_dir_all(parent_dir).await {
@@ -32,20 +49,9 @@ pub async fn save_file(request: tauri::ipc::Request<'_>) -> Result<(), String> {
         }
     }
 
-    let file_type: String = match request.headers().get("x-file-type") {
-        Some(header) => match BASE64_STANDARD.decode(header.to_str().unwrap()) {
-            Ok(file_type) => String::from_utf8(file_type).unwrap(),
-            Err(_) => return Err(String::from("[save_file] Invalid file type")),
-        },
-        None => return Err(String::from("[save_file] Missing file type")),
-    };
-
     // 如果是 avif 则重写解码写入
     if file_type == "image/avif" {
-        let image = match image::load_from_memory_with_format(
-            file_data.as_slice(),
-            image::ImageFormat::WebP,
-        ) {
+        let image = match image::load_from_memory_with_format(file_data, image::ImageFormat::WebP) {
             Ok(image) => image,
             Err(_) => return Err(String::from("[save_file] Invalid image")),
         };
@@ -58,10 +64,7 @@ pub async fn save_file(request: tauri::ipc::Request<'_>) -> Result<(), String> {
             )),
         };
     } else if file_type == "image/jpeg-xl" {
-        let image = match image::load_from_memory_with_format(
-            file_data.as_slice(),
-            image::ImageFormat::WebP,
-        ) {
+        let image = match image::load_from_memory_with_format(file_data, image::ImageFormat::WebP) {
             Ok(image) => image,
             Err(_) => return Err(String::from("[save_file] Invalid image")),
         };
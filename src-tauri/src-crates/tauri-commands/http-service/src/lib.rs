@@ -1,33 +1,908 @@
-use snow_shot_http_services::{S3Config, S3Service};
-
-pub async fn upload_to_s3(
-    endpoint: String,
-    region: String,
-    access_key_id: String,
-    secret_access_key: String,
-    bucket: String,
-    path_prefix: Option<String>,
-    force_path_style: bool,
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use snow_shot_http_services::{
+    create_upload_destination, is_retryable_error, CredentialVault, PostUploadOptions,
+    PostUploadResult, S3Config, S3MultipartOptions, S3MultipartUploadState, S3Service,
+    S3ShareOptions, S3UploadProgress, UploadDestinationConfig, UploadHistoryQuery,
+    UploadHistoryRecord, UploadHistoryStore, UploadProfile, UploadQueueJob, UploadQueueJobStatus,
+    UploadQueueOptions, UploadQueueStore, UploadResult, UrlShortener, UrlShortenerProfile,
+};
+use tauri::{Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_notification::NotificationExt;
+
+const UPLOAD_HISTORY_DATABASE_FILE_NAME: &str = "upload_history.db";
+const UPLOAD_QUEUE_DATABASE_FILE_NAME: &str = "upload_queue.db";
+/// 队列保存上传数据的目录
+const UPLOAD_QUEUE_SPOOL_DIR_NAME: &str = "upload_queue";
+/// 没有到期任务时的最长等待时间，毫秒
+const UPLOAD_QUEUE_MAX_IDLE_MILLIS: u64 = 30 * 1000;
+
+/// 正在进行的上传任务，用于取消上传
+#[derive(Default)]
+pub struct S3UploadTaskList {
+    tasks: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl S3UploadTaskList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// 上传历史，首次使用时打开数据库
+#[derive(Default)]
+pub struct UploadHistoryState {
+    store: Mutex<Option<UploadHistoryStore>>,
+}
+
+impl UploadHistoryState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// 后台上传队列，首次使用时打开数据库
+#[derive(Default)]
+pub struct UploadQueueState {
+    store: Mutex<Option<UploadQueueStore>>,
+    /// 添加或重试任务时唤醒上传线程
+    notify: tokio::sync::Notify,
+    started: AtomicBool,
+    /// 重试策略，由前端通过 upload_queue_set_options 设置
+    options: Mutex<UploadQueueOptions>,
+}
+
+impl UploadQueueState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn with_upload_history<T>(
+    app: &tauri::AppHandle,
+    f: impl FnOnce(&UploadHistoryStore) -> anyhow::Result<T>,
+) -> Result<T, String> {
+    let state = app.state::<UploadHistoryState>();
+    let mut store = state.store.lock().unwrap();
+
+    if store.is_none() {
+        let data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("[with_upload_history] Failed to get data dir: {}", e))?;
+        *store = Some(
+            UploadHistoryStore::open(&data_dir.join(UPLOAD_HISTORY_DATABASE_FILE_NAME))
+                .map_err(|e| format!("[with_upload_history] {:#}", e))?,
+        );
+    }
+
+    f(store.as_ref().unwrap()).map_err(|e| format!("[with_upload_history] {:#}", e))
+}
+
+fn get_now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// 写入上传记录，失败时只记录日志，不影响上传结果
+fn record_upload(
+    app: &tauri::AppHandle,
+    profile_id: Option<&str>,
+    destination_type: &str,
+    result: &UploadResult,
+    size: u64,
+    content_type: Option<&str>,
+    source_path: Option<&Path>,
+) {
+    let uploaded_at = get_now_millis();
+
+    let record = UploadHistoryRecord {
+        id: 0,
+        profile_id: profile_id.map(|profile_id| profile_id.to_string()),
+        destination_type: destination_type.to_string(),
+        remote_path: result.remote_path.clone(),
+        url: result.url.clone(),
+        delete_url: result.delete_url.clone(),
+        size,
+        content_type: content_type.map(|content_type| content_type.to_string()),
+        uploaded_at,
+        source_path: source_path.map(|path| path.to_string_lossy().to_string()),
+    };
+
+    if let Err(e) = with_upload_history(app, |store| store.add(&record)) {
+        log::warn!("[record_upload] Failed to record upload: {}", e);
+    }
+}
+
+/// 记录 S3 上传
+fn record_s3_upload(
+    app: &tauri::AppHandle,
+    profile_id: Option<&str>,
+    service: &S3Service,
+    object_key: &str,
+    url: &str,
+    size: u64,
+    content_type: Option<&str>,
+    source_path: Option<&Path>,
+) {
+    let result = UploadResult {
+        url: url.to_string(),
+        remote_path: service.get_full_key(object_key),
+        delete_url: None,
+    };
+
+    record_upload(
+        app,
+        profile_id,
+        "S3",
+        &result,
+        size,
+        content_type,
+        source_path,
+    );
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct S3UploadProgressEvent {
+    task_id: String,
+    progress: S3UploadProgress,
+}
+
+fn get_credential_vault(app: &tauri::AppHandle) -> Result<CredentialVault, String> {
+    let data_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("[get_credential_vault] Failed to get config dir: {}", e))?;
+
+    Ok(CredentialVault::new(&data_dir))
+}
+
+/// 获取包含密钥的上传配置
+fn get_upload_profile(app: &tauri::AppHandle, profile_id: &str) -> Result<UploadProfile, String> {
+    get_credential_vault(app)?
+        .get_profile(profile_id)
+        .map_err(|e| format!("[get_upload_profile] {:#}", e))
+}
+
+fn get_s3_config(app: &tauri::AppHandle, profile_id: &str) -> Result<S3Config, String> {
+    match get_upload_profile(app, profile_id)?.destination {
+        UploadDestinationConfig::S3(config) => Ok(config),
+        _ => Err(format!(
+            "[get_s3_config] Upload profile is not a S3 profile: {}",
+            profile_id
+        )),
+    }
+}
+
+/// 获取所有上传配置，不包含密钥
+pub fn upload_profile_list(app: &tauri::AppHandle) -> Result<Vec<UploadProfile>, String> {
+    get_credential_vault(app)?
+        .list_profiles()
+        .map_err(|e| format!("[upload_profile_list] {:#}", e))
+}
+
+/// 保存上传配置，密钥保存到系统密钥环
+pub fn upload_profile_save(app: &tauri::AppHandle, profile: UploadProfile) -> Result<(), String> {
+    get_credential_vault(app)?
+        .save_profile(profile)
+        .map_err(|e| format!("[upload_profile_save] {:#}", e))
+}
+
+pub fn upload_profile_delete(app: &tauri::AppHandle, profile_id: &str) -> Result<(), String> {
+    get_credential_vault(app)?
+        .delete_profile(profile_id)
+        .map_err(|e| format!("[upload_profile_delete] {:#}", e))
+}
+
+/// 获取所有短链接配置，不包含密钥
+pub fn url_shortener_list(app: &tauri::AppHandle) -> Result<Vec<UrlShortenerProfile>, String> {
+    get_credential_vault(app)?
+        .list_url_shorteners()
+        .map_err(|e| format!("[url_shortener_list] {:#}", e))
+}
+
+/// 保存短链接配置，密钥保存到系统密钥环
+pub fn url_shortener_save(
+    app: &tauri::AppHandle,
+    shortener: UrlShortenerProfile,
+) -> Result<(), String> {
+    get_credential_vault(app)?
+        .save_url_shortener(shortener)
+        .map_err(|e| format!("[url_shortener_save] {:#}", e))
+}
+
+pub fn url_shortener_delete(app: &tauri::AppHandle, shortener_id: &str) -> Result<(), String> {
+    get_credential_vault(app)?
+        .delete_url_shortener(shortener_id)
+        .map_err(|e| format!("[url_shortener_delete] {:#}", e))
+}
+
+/// 使用凭据库中保存的短链接配置缩短链接
+async fn shorten_url(
+    app: &tauri::AppHandle,
+    shortener_id: &str,
+    url: &str,
+) -> Result<String, String> {
+    let shortener = get_credential_vault(app)?
+        .get_url_shortener(shortener_id)
+        .map_err(|e| format!("{:#}", e))?;
+
+    UrlShortener::new(shortener.shortener)
+        .map_err(|e| format!("{:#}", e))?
+        .shorten(url)
+        .await
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// 上传完成后依次缩短链接、复制到剪贴板、发送通知，单个操作失败不影响其它操作
+pub async fn run_post_upload_actions(
+    app: &tauri::AppHandle,
+    url: String,
+    filename: &str,
+    content_type: Option<&str>,
+    options: &PostUploadOptions,
+) -> PostUploadResult {
+    let mut result = PostUploadResult {
+        url,
+        short_url: None,
+        copied_text: None,
+        errors: Vec::new(),
+    };
+
+    if let Some(shortener_id) = &options.shortener_id {
+        match shorten_url(app, shortener_id, &result.url).await {
+            Ok(short_url) => result.short_url = Some(short_url),
+            Err(e) => result.errors.push(format!(
+                "[run_post_upload_actions] Failed to shorten url: {}",
+                e
+            )),
+        }
+    }
+
+    if let Some(copy_format) = options.copy_format {
+        let text = copy_format.format(result.get_final_url(), filename, content_type);
+        match app.clipboard().write_text(text.clone()) {
+            Ok(_) => result.copied_text = Some(text),
+            Err(e) => result.errors.push(format!(
+                "[run_post_upload_actions] Failed to write clipboard: {}",
+                e
+            )),
+        }
+    }
+
+    if options.notification {
+        if let Err(e) = app
+            .notification()
+            .builder()
+            .title(filename)
+            .body(result.get_final_url())
+            .show()
+        {
+            result.errors.push(format!(
+                "[run_post_upload_actions] Failed to send notification: {}",
+                e
+            ));
+        }
+    }
+
+    for error in &result.errors {
+        log::warn!("{}", error);
+    }
+
+    result
+}
+
+/// 使用保存的 S3 配置上传，`share_options` 不为空时覆盖配置中的分享参数
+pub async fn upload_to_s3_with_profile(
+    app: &tauri::AppHandle,
+    profile_id: &str,
     data: &[u8],
     filename: String,
     content_type: Option<String>,
+    share_options: Option<S3ShareOptions>,
+    post_upload_options: Option<PostUploadOptions>,
 ) -> Result<String, String> {
-    let config = S3Config {
-        endpoint,
-        region,
-        access_key_id,
-        secret_access_key,
-        bucket,
-        path_prefix,
-        force_path_style,
-    };
+    let mut config = get_s3_config(app, profile_id)?;
+    if let Some(share_options) = share_options {
+        config.share_options = share_options;
+    }
 
     let service = S3Service::new(config).await.map_err(|e| e.to_string())?;
 
     let url = service
-        .upload_bytes(data, filename, content_type)
+        .upload_bytes(data, filename.clone(), content_type.clone())
         .await
         .map_err(|e| e.to_string())?;
 
-    Ok(url)
+    record_s3_upload(
+        app,
+        Some(profile_id),
+        &service,
+        &filename,
+        &url,
+        data.len() as u64,
+        content_type.as_deref(),
+        None,
+    );
+
+    match post_upload_options {
+        Some(options) => {
+            Ok(
+                run_post_upload_actions(app, url, &filename, content_type.as_deref(), &options)
+                    .await
+                    .get_final_url()
+                    .to_string(),
+            )
+        }
+        None => Ok(url),
+    }
+}
+
+/// 为已上传的对象生成预签名地址，私有存储桶也可以分享
+pub async fn s3_get_presigned_url(
+    app: &tauri::AppHandle,
+    profile_id: &str,
+    object_key: &str,
+    expiry_secs: u32,
+) -> Result<String, String> {
+    let service = S3Service::new(get_s3_config(app, profile_id)?)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    service
+        .get_presigned_url(object_key, expiry_secs)
+        .await
+        .map_err(|e| format!("[s3_get_presigned_url] {:#}", e))
+}
+
+/// 上传本地文件，大文件使用分片上传，进度通过 s3-upload-progress 事件通知
+pub async fn upload_file_to_s3(
+    app: &tauri::AppHandle,
+    task_list: &S3UploadTaskList,
+    task_id: String,
+    profile_id: String,
+    file_path: PathBuf,
+    filename: String,
+    content_type: Option<String>,
+    options: Option<S3MultipartOptions>,
+    resume_state: Option<S3MultipartUploadState>,
+) -> Result<String, String> {
+    let config = get_s3_config(app, &profile_id)?;
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    task_list
+        .tasks
+        .lock()
+        .unwrap()
+        .insert(task_id.clone(), cancel.clone());
+
+    let result = async {
+        let service = S3Service::new(config).await?;
+
+        let url = service
+            .upload_file(
+                &file_path,
+                filename.clone(),
+                content_type.clone(),
+                &options.unwrap_or_default(),
+                resume_state,
+                &cancel,
+                |progress| {
+                    let event = S3UploadProgressEvent {
+                        task_id: task_id.clone(),
+                        progress,
+                    };
+                    if let Err(e) = app.emit("s3-upload-progress", event) {
+                        log::warn!("[upload_file_to_s3] Failed to emit progress: {}", e);
+                    }
+                },
+            )
+            .await?;
+
+        let size = tokio::fs::metadata(&file_path).await?.len();
+        record_s3_upload(
+            app,
+            Some(&profile_id),
+            &service,
+            &filename,
+            &url,
+            size,
+            content_type.as_deref(),
+            Some(&file_path),
+        );
+
+        anyhow::Ok(url)
+    }
+    .await;
+
+    task_list.tasks.lock().unwrap().remove(&task_id);
+
+    result.map_err(|e| format!("{:#}", e))
+}
+
+/// 取消正在进行的上传，已上传的分片会被清理
+pub fn cancel_s3_upload(task_list: &S3UploadTaskList, task_id: &str) -> Result<(), String> {
+    match task_list.tasks.lock().unwrap().get(task_id) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+        None => Err(format!(
+            "[cancel_s3_upload] Upload task not found: {}",
+            task_id
+        )),
+    }
+}
+
+/// 使用指定的上传配置上传数据，`post_upload_options` 不为空时返回处理后的地址
+pub async fn upload_to_destination(
+    app: &tauri::AppHandle,
+    profile_id: &str,
+    data: &[u8],
+    filename: String,
+    content_type: Option<String>,
+    post_upload_options: Option<PostUploadOptions>,
+) -> Result<UploadResult, String> {
+    let profile = get_upload_profile(app, profile_id)?;
+
+    log::info!(
+        "[upload_to_destination] Upload {} with profile: {}",
+        filename,
+        profile.id
+    );
+
+    let destination_type = profile.destination.destination_type();
+    let destination = create_upload_destination(profile.destination)
+        .map_err(|e| format!("[upload_to_destination] {:#}", e))?;
+
+    let mut result = destination
+        .upload(data, &filename, content_type.as_deref())
+        .await
+        .map_err(|e| format!("[upload_to_destination] {:#}", e))?;
+
+    record_upload(
+        app,
+        Some(profile_id),
+        destination_type,
+        &result,
+        data.len() as u64,
+        content_type.as_deref(),
+        None,
+    );
+
+    if let Some(options) = post_upload_options {
+        result.url = run_post_upload_actions(
+            app,
+            result.url,
+            &filename,
+            content_type.as_deref(),
+            &options,
+        )
+        .await
+        .get_final_url()
+        .to_string();
+    }
+
+    Ok(result)
+}
+
+pub fn upload_history_query(
+    app: &tauri::AppHandle,
+    query: UploadHistoryQuery,
+) -> Result<Vec<UploadHistoryRecord>, String> {
+    with_upload_history(app, |store| store.query(&query))
+}
+
+/// 删除上传记录，`delete_remote` 为 true 时同时删除远程文件
+pub async fn upload_history_delete(
+    app: &tauri::AppHandle,
+    id: i64,
+    delete_remote: bool,
+) -> Result<(), String> {
+    let record = with_upload_history(app, |store| store.get(id))?
+        .ok_or_else(|| format!("[upload_history_delete] Upload record not found: {}", id))?;
+
+    if delete_remote {
+        let profile_id = record.profile_id.as_deref().ok_or_else(|| {
+            format!(
+                "[upload_history_delete] Upload record has no profile, can not delete remote file: {}",
+                id
+            )
+        })?;
+
+        let destination =
+            create_upload_destination(get_upload_profile(app, profile_id)?.destination)
+                .map_err(|e| format!("[upload_history_delete] {:#}", e))?;
+        destination
+            .delete(&record.remote_path, record.delete_url.as_deref())
+            .await
+            .map_err(|e| format!("[upload_history_delete] {:#}", e))?;
+    }
+
+    with_upload_history(app, |store| store.remove(id))
+}
+
+fn with_upload_queue<T>(
+    app: &tauri::AppHandle,
+    f: impl FnOnce(&UploadQueueStore) -> anyhow::Result<T>,
+) -> Result<T, String> {
+    let state = app.state::<UploadQueueState>();
+    let mut store = state.store.lock().unwrap();
+
+    if store.is_none() {
+        let data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("[with_upload_queue] Failed to get data dir: {}", e))?;
+        *store = Some(
+            UploadQueueStore::open(&data_dir.join(UPLOAD_QUEUE_DATABASE_FILE_NAME))
+                .map_err(|e| format!("[with_upload_queue] {:#}", e))?,
+        );
+    }
+
+    f(store.as_ref().unwrap()).map_err(|e| format!("[with_upload_queue] {:#}", e))
+}
+
+/// 通过 upload-queue-status 事件通知任务状态变化
+fn emit_upload_queue_job(app: &tauri::AppHandle, id: i64) {
+    match with_upload_queue(app, |store| store.get(id)) {
+        Ok(Some(job)) => {
+            if let Err(e) = app.emit("upload-queue-status", job) {
+                log::warn!("[emit_upload_queue_job] Failed to emit status: {}", e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("[emit_upload_queue_job] {}", e),
+    }
+}
+
+/// 启动后台上传线程，重复调用时忽略
+pub fn start_upload_queue(app: tauri::AppHandle) {
+    if app
+        .state::<UploadQueueState>()
+        .started
+        .swap(true, Ordering::SeqCst)
+    {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let job = with_upload_queue(&app, |store| store.take_next_due(get_now_millis()))
+                .unwrap_or_else(|e| {
+                    log::error!("[start_upload_queue] {}", e);
+                    None
+                });
+
+            if let Some(job) = job {
+                emit_upload_queue_job(&app, job.id);
+                process_upload_queue_job(&app, &job).await;
+                emit_upload_queue_job(&app, job.id);
+                continue;
+            }
+
+            let now = get_now_millis();
+            let wait_millis = with_upload_queue(&app, |store| store.get_next_attempt_at())
+                .ok()
+                .flatten()
+                .map(|next_attempt_at| next_attempt_at.saturating_sub(now))
+                .unwrap_or(UPLOAD_QUEUE_MAX_IDLE_MILLIS)
+                .min(UPLOAD_QUEUE_MAX_IDLE_MILLIS);
+
+            let state = app.state::<UploadQueueState>();
+            tokio::select! {
+                _ = state.notify.notified() => {}
+                _ = tokio::time::sleep(Duration::from_millis(wait_millis)) => {}
+            }
+        }
+    });
+}
+
+/// 上传单个任务，目标不可用时按指数退避重试
+async fn process_upload_queue_job(app: &tauri::AppHandle, job: &UploadQueueJob) {
+    log::info!(
+        "[process_upload_queue_job] Upload {} with profile: {}, attempt: {}",
+        job.filename,
+        job.profile_id,
+        job.attempts
+    );
+
+    let data = match tokio::fs::read(&job.source_path).await {
+        Ok(data) => data,
+        Err(e) => {
+            // 源文件不存在时重试没有意义
+            let error = format!(
+                "[process_upload_queue_job] Failed to read file {}: {}",
+                job.source_path, e
+            );
+            log::error!("{}", error);
+            if let Err(e) = with_upload_queue(app, |store| {
+                store.fail(job.id, &error, None, get_now_millis())
+            }) {
+                log::error!("[process_upload_queue_job] {}", e);
+            }
+            return;
+        }
+    };
+
+    // 错误中同时返回是否可以重试，配置不存在或无效时重试没有意义
+    let result = async {
+        let profile = get_upload_profile(app, &job.profile_id).map_err(|e| (e, false))?;
+        let destination_type = profile.destination.destination_type();
+        let destination = create_upload_destination(profile.destination)
+            .map_err(|e| (format!("[process_upload_queue_job] {:#}", e), false))?;
+
+        let result = destination
+            .upload(&data, &job.filename, job.content_type.as_deref())
+            .await
+            .map_err(|e| {
+                (
+                    format!("[process_upload_queue_job] {:#}", e),
+                    is_retryable_error(&e),
+                )
+            })?;
+
+        Ok::<_, (String, bool)>((destination_type, result))
+    }
+    .await;
+
+    let now = get_now_millis();
+    let update_result = match result {
+        Ok((destination_type, result)) => {
+            let source_path = Path::new(&job.source_path);
+            record_upload(
+                app,
+                Some(&job.profile_id),
+                destination_type,
+                &result,
+                data.len() as u64,
+                job.content_type.as_deref(),
+                (!job.delete_after_upload).then_some(source_path),
+            );
+
+            if job.delete_after_upload {
+                if let Err(e) = tokio::fs::remove_file(source_path).await {
+                    log::warn!(
+                        "[process_upload_queue_job] Failed to remove file {}: {}",
+                        job.source_path,
+                        e
+                    );
+                }
+            }
+
+            // 任务中保存处理后的地址
+            let url = match &job.post_upload_options {
+                Some(options) => run_post_upload_actions(
+                    app,
+                    result.url,
+                    &job.filename,
+                    job.content_type.as_deref(),
+                    options,
+                )
+                .await
+                .get_final_url()
+                .to_string(),
+                None => result.url,
+            };
+
+            with_upload_queue(app, |store| store.complete(job.id, &url, now))
+        }
+        Err((error, retryable)) => {
+            let next_attempt_at = if retryable {
+                let state = app.state::<UploadQueueState>();
+                let options = state.options.lock().unwrap();
+                options.get_next_attempt_at(job.attempts, now)
+            } else {
+                None
+            };
+            log::warn!(
+                "[process_upload_queue_job] Upload failed, next attempt at: {:?}, error: {}",
+                next_attempt_at,
+                error
+            );
+
+            with_upload_queue(app, |store| {
+                store.fail(job.id, &error, next_attempt_at, now)
+            })
+        }
+    };
+
+    if let Err(e) = update_result {
+        log::error!("[process_upload_queue_job] {}", e);
+    }
+
+    // 上传过程中任务被移除时，清理队列保存的数据
+    if job.delete_after_upload {
+        if let Ok(None) = with_upload_queue(app, |store| store.get(job.id)) {
+            let _ = tokio::fs::remove_file(&job.source_path).await;
+        }
+    }
+}
+
+fn add_upload_queue_job(
+    app: &tauri::AppHandle,
+    profile_id: String,
+    source_path: &Path,
+    filename: String,
+    content_type: Option<String>,
+    post_upload_options: Option<PostUploadOptions>,
+    delete_after_upload: bool,
+) -> Result<UploadQueueJob, String> {
+    let now = get_now_millis();
+    let job = UploadQueueJob {
+        id: 0,
+        profile_id,
+        source_path: source_path.to_string_lossy().to_string(),
+        filename,
+        content_type,
+        delete_after_upload,
+        status: UploadQueueJobStatus::Pending,
+        attempts: 0,
+        next_attempt_at: now,
+        last_error: None,
+        url: None,
+        post_upload_options,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let id = with_upload_queue(app, |store| store.add(&job))?;
+    app.state::<UploadQueueState>().notify.notify_one();
+    emit_upload_queue_job(app, id);
+
+    Ok(UploadQueueJob { id, ..job })
+}
+
+/// 将本地文件加入上传队列，`filename` 为空时使用文件名
+///
+/// 保存文件时通过 save_file 的 x-upload-profile-id 请求头自动调用
+pub fn upload_queue_add(
+    app: &tauri::AppHandle,
+    profile_id: String,
+    file_path: PathBuf,
+    filename: Option<String>,
+    content_type: Option<String>,
+    post_upload_options: Option<PostUploadOptions>,
+) -> Result<UploadQueueJob, String> {
+    if !file_path.is_file() {
+        return Err(format!(
+            "[upload_queue_add] File not found: {}",
+            file_path.display()
+        ));
+    }
+
+    // 提前检查配置，避免任务一直失败
+    get_upload_profile(app, &profile_id)?;
+
+    let filename = match filename {
+        Some(filename) => filename,
+        None => file_path
+            .file_name()
+            .map(|filename| filename.to_string_lossy().to_string())
+            .ok_or_else(|| {
+                format!(
+                    "[upload_queue_add] Invalid file path: {}",
+                    file_path.display()
+                )
+            })?,
+    };
+
+    add_upload_queue_job(
+        app,
+        profile_id,
+        &file_path,
+        filename,
+        content_type,
+        post_upload_options,
+        false,
+    )
+}
+
+/// 将数据保存到队列目录后加入上传队列，上传完成后删除
+pub async fn upload_queue_add_data(
+    app: &tauri::AppHandle,
+    profile_id: String,
+    data: &[u8],
+    filename: String,
+    content_type: Option<String>,
+    post_upload_options: Option<PostUploadOptions>,
+) -> Result<UploadQueueJob, String> {
+    get_upload_profile(app, &profile_id)?;
+
+    let spool_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("[upload_queue_add_data] Failed to get data dir: {}", e))?
+        .join(UPLOAD_QUEUE_SPOOL_DIR_NAME);
+    tokio::fs::create_dir_all(&spool_dir)
+        .await
+        .map_err(|e| format!("[upload_queue_add_data] Failed to create dir: {}", e))?;
+
+    let spool_name = Path::new(&filename)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let spool_path = spool_dir.join(format!(
+        "{}_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default(),
+        spool_name
+    ));
+    tokio::fs::write(&spool_path, data)
+        .await
+        .map_err(|e| format!("[upload_queue_add_data] Failed to write file: {}", e))?;
+
+    let result = add_upload_queue_job(
+        app,
+        profile_id,
+        &spool_path,
+        filename,
+        content_type,
+        post_upload_options,
+        true,
+    );
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&spool_path).await;
+    }
+
+    result
+}
+
+pub fn upload_queue_list(app: &tauri::AppHandle) -> Result<Vec<UploadQueueJob>, String> {
+    with_upload_queue(app, |store| store.list())
+}
+
+/// 立即重试任务
+pub fn upload_queue_retry(app: &tauri::AppHandle, id: i64) -> Result<(), String> {
+    with_upload_queue(app, |store| store.retry(id, get_now_millis()))?;
+    app.state::<UploadQueueState>().notify.notify_one();
+    emit_upload_queue_job(app, id);
+
+    Ok(())
+}
+
+/// 移除任务，正在上传的任务会继续上传但不再更新状态
+pub fn upload_queue_remove(app: &tauri::AppHandle, id: i64) -> Result<(), String> {
+    let job = with_upload_queue(app, |store| store.get(id))?
+        .ok_or_else(|| format!("[upload_queue_remove] Upload queue job not found: {}", id))?;
+
+    with_upload_queue(app, |store| store.remove(id))?;
+
+    if job.delete_after_upload && job.status != UploadQueueJobStatus::Uploading {
+        if let Err(e) = std::fs::remove_file(&job.source_path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!(
+                    "[upload_queue_remove] Failed to remove file {}: {}",
+                    job.source_path,
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 设置重试策略，对之后失败的任务生效
+pub fn upload_queue_set_options(
+    app: &tauri::AppHandle,
+    options: UploadQueueOptions,
+) -> Result<(), String> {
+    *app.state::<UploadQueueState>().options.lock().unwrap() = options;
+
+    Ok(())
+}
+
+pub fn upload_queue_clear_completed(app: &tauri::AppHandle) -> Result<(), String> {
+    with_upload_queue(app, |store| store.clear_completed())
 }
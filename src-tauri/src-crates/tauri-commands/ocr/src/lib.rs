@@ -4,10 +4,34 @@ use rayon::iter::IntoParallelIterator;
 use rayon::iter::ParallelIterator;
 use serde::Deserialize;
 use serde::Serialize;
+use snow_shot_app_services::barcode_service::{BarcodeResult, BarcodeService, BarcodeType};
+use snow_shot_app_services::capture_search_service::{
+    CaptureSearchQuery, CaptureSearchRecord, CaptureSearchResult, CaptureSearchService,
+};
+use snow_shot_app_services::ocr_batch_service::{
+    OcrBatchFile, OcrBatchProgress, OcrBatchService, OcrIndex, OcrIndexEntry,
+};
+use snow_shot_app_services::ocr_engine::detect_options::{OcrDetectOptions, OcrDetectPreset};
+use snow_shot_app_services::ocr_engine::rapid_ocr_model::OcrRecModelConfig;
+use snow_shot_app_services::ocr_engine::tesseract::TesseractConfig;
+use snow_shot_app_services::ocr_engine::{OcrEngine, OcrEngineType, OcrModelInfo};
 use snow_shot_app_services::ocr_service::{OcrModel, OcrService};
+use snow_shot_app_services::redaction_service::{
+    RedactionMatch, RedactionOptions, RedactionService,
+};
+use snow_shot_app_services::translation_render_service::{
+    TranslatedTextBlock, TranslationRenderOptions, TranslationRenderService,
+};
+use snow_shot_app_shared::ElementRect;
+use snow_shot_http_services::{TranslationBackendConfig, TranslationService};
 use std::io::Cursor;
 use std::path::PathBuf;
-use tokio::sync::Mutex;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{Emitter, Manager};
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
 
 pub async fn ocr_init(
     orc_plugin_path: PathBuf,
@@ -25,6 +49,34 @@ pub async fn ocr_init(
     Ok(())
 }
 
+pub async fn ocr_init_tesseract(
+    ocr_service: tauri::State<'_, Mutex<OcrService>>,
+    config: TesseractConfig,
+) -> Result<(), String> {
+    let mut ocr_service = ocr_service.lock().await;
+
+    ocr_service.init_tesseract(config).await?;
+
+    Ok(())
+}
+
+pub async fn ocr_register_rec_model(
+    ocr_service: tauri::State<'_, Mutex<OcrService>>,
+    config: OcrRecModelConfig,
+) -> Result<OcrModelInfo, String> {
+    let mut ocr_service = ocr_service.lock().await;
+
+    ocr_service.register_rec_model(config).await
+}
+
+pub async fn ocr_get_models(
+    ocr_service: tauri::State<'_, Mutex<OcrService>>,
+) -> Result<Vec<OcrModelInfo>, String> {
+    let ocr_service = ocr_service.lock().await;
+
+    Ok(ocr_service.get_models())
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct OcrDetectResult {
     pub text_blocks: Vec<TextBlock>,
@@ -55,21 +107,55 @@ fn convert_rgba_to_rgb(image: &[u8]) -> Vec<u8> {
     rgb_data
 }
 
+/// 指定的参数优先于预设，都为空时使用默认预设
+fn get_detect_options(
+    preset: Option<OcrDetectPreset>,
+    options: Option<OcrDetectOptions>,
+) -> OcrDetectOptions {
+    match options {
+        Some(options) => options,
+        None => preset.unwrap_or_default().options(),
+    }
+}
+
 pub async fn ocr_detect_core(
     ocr_service: tauri::State<'_, Mutex<OcrService>>,
     image: image::DynamicImage,
     scale_factor: f32,
     detect_angle: bool,
+    engine: OcrEngineType,
+    model_id: Option<String>,
+    options: OcrDetectOptions,
 ) -> Result<OcrDetectResult, String> {
     let mut ocr_service = ocr_service.lock().await;
+
+    detect_with_engine(
+        ocr_service.get_engine(engine).await?,
+        image,
+        scale_factor,
+        detect_angle,
+        model_id.as_deref(),
+        &options,
+    )
+}
+
+/// 按检测参数缩放和预处理图片后交给引擎识别
+fn detect_with_engine(
+    engine: &mut dyn OcrEngine,
+    image: image::DynamicImage,
+    scale_factor: f32,
+    detect_angle: bool,
+    model_id: Option<&str>,
+    options: &OcrDetectOptions,
+) -> Result<OcrDetectResult, String> {
     let mut scale_factor = scale_factor;
     let mut image = image;
 
-    // 分辨率过小的图片识别可能有问题，当 scale_factor 低于 1.5 时，放大图片使有效缩放达到 1.5
-    let target_scale_factor = 1.5;
+    // 分辨率过小的图片识别可能有问题，当 scale_factor 低于 min_scale_factor 时，放大图片使有效缩放达到 min_scale_factor
+    let target_scale_factor = options.min_scale_factor;
     if scale_factor < target_scale_factor && scale_factor > 0.0 {
-        scale_factor = target_scale_factor;
         let resize_factor = target_scale_factor / scale_factor;
+        scale_factor = target_scale_factor;
         image = image.resize(
             (image.width() as f32 * resize_factor) as u32,
             (image.height() as f32 * resize_factor) as u32,
@@ -77,9 +163,7 @@ pub async fn ocr_detect_core(
         );
     }
 
-    let max_size = image.height().max(image.width());
-
-    let image_buffer = match image {
+    let mut image_buffer = match image {
         image::DynamicImage::ImageRgb8(image) => image,
         image::DynamicImage::ImageRgba8(image) => {
             let rgb_data = convert_rgba_to_rgb(image.as_raw());
@@ -87,25 +171,16 @@ pub async fn ocr_detect_core(
         }
         _ => return Err("[ocr_detect_core] Invalid image".to_string()),
     };
-    let ocr_result = ocr_service.get_session().await?.detect_angle_rollback(
-        &image_buffer,
-        50,
-        max_size,
-        0.5,
-        0.3,
-        1.6,
-        detect_angle,
-        false,
-        0.9, // 屏幕截取的文字质量通常较高，且非横向排版的情况较少，尽量减少角度的影响
-    );
-
-    match ocr_result {
-        Ok(ocr_result) => Ok(OcrDetectResult {
-            text_blocks: ocr_result.text_blocks,
-            scale_factor,
-        }),
-        Err(e) => return Err(format!("[ocr_detect_core] Failed to detect text: {}", e)),
+    if !options.preprocess.is_empty() {
+        options.preprocess.apply(&mut image_buffer);
     }
+
+    let text_blocks = engine.detect(&image_buffer, detect_angle, options, model_id)?;
+
+    Ok(OcrDetectResult {
+        text_blocks,
+        scale_factor,
+    })
 }
 
 pub async fn ocr_detect(
@@ -119,12 +194,12 @@ pub async fn ocr_detect(
         _ => return Err("[ocr_detect] Invalid request body".to_string()),
     };
 
-    let mut image = match image::load(Cursor::new(image_data), image::ImageFormat::Png) {
+    let image = match image::load(Cursor::new(image_data), image::ImageFormat::Png) {
         Ok(image) => image,
         Err(_) => return Err("[ocr_detect] Invalid image".to_string()),
     };
 
-    let mut scale_factor: f32 = match request.headers().get("x-scale-factor") {
+    let scale_factor: f32 = match request.headers().get("x-scale-factor") {
         Some(header) => match header.to_str() {
             Ok(scale_factor) => scale_factor.parse::<f32>().unwrap(),
             Err(_) => return Err("[ocr_detect] Invalid scale factor".to_string()),
@@ -132,18 +207,6 @@ pub async fn ocr_detect(
         None => return Err("[ocr_detect] Missing scale factor".to_string()),
     };
 
-    // 分辨率过小的图片识别可能有问题，当 scale_factor 低于 1.5 时，放大图片使有效缩放达到 1.5
-    let target_scale_factor = 1.5;
-    if scale_factor < target_scale_factor && scale_factor > 0.0 {
-        scale_factor = target_scale_factor;
-        let resize_factor = target_scale_factor / scale_factor;
-        image = image.resize(
-            (image.width() as f32 * resize_factor) as u32,
-            (image.height() as f32 * resize_factor) as u32,
-            image::imageops::FilterType::Lanczos3,
-        );
-    }
-
     let detect_angle = match request.headers().get("x-detect-angle") {
         Some(header) => match header.to_str() {
             Ok(detect_angle) => detect_angle.parse::<bool>().unwrap(),
@@ -152,7 +215,54 @@ pub async fn ocr_detect(
         None => return Err("[ocr_detect] Missing detect angle".to_string()),
     };
 
-    ocr_detect_core(ocr_service, image, scale_factor, detect_angle).await
+    let engine = match request.headers().get("x-ocr-engine") {
+        Some(header) => match header.to_str() {
+            Ok(engine) => engine.parse::<OcrEngineType>()?,
+            Err(_) => return Err("[ocr_detect] Invalid ocr engine".to_string()),
+        },
+        None => OcrEngineType::default(),
+    };
+
+    let model_id = match request.headers().get("x-ocr-model-id") {
+        Some(header) => match header.to_str() {
+            Ok(model_id) => Some(model_id.to_string()),
+            Err(_) => return Err("[ocr_detect] Invalid ocr model id".to_string()),
+        },
+        None => None,
+    };
+
+    let preset = match request.headers().get("x-ocr-detect-preset") {
+        Some(header) => match header.to_str() {
+            Ok(preset) => Some(preset.parse::<OcrDetectPreset>()?),
+            Err(_) => return Err("[ocr_detect] Invalid detect preset".to_string()),
+        },
+        None => None,
+    };
+
+    let options = match request.headers().get("x-ocr-detect-options") {
+        Some(header) => match header
+            .to_str()
+            .map_err(|e| e.to_string())
+            .and_then(|options| serde_json::from_str(options).map_err(|e| e.to_string()))
+        {
+            Ok(options) => Some(options),
+            Err(e) => {
+                return Err(format!("[ocr_detect] Invalid detect options: {}", e));
+            }
+        },
+        None => None,
+    };
+
+    ocr_detect_core(
+        ocr_service,
+        image,
+        scale_factor,
+        detect_angle,
+        engine,
+        model_id,
+        get_detect_options(preset, options),
+    )
+    .await
 }
 
 #[cfg(target_os = "windows")]
@@ -162,6 +272,10 @@ pub async fn ocr_detect_with_shared_buffer(
     channel_id: String,
     scale_factor: f32,
     detect_angle: bool,
+    engine: Option<OcrEngineType>,
+    model_id: Option<String>,
+    preset: Option<OcrDetectPreset>,
+    options: Option<OcrDetectOptions>,
 ) -> Result<OcrDetectResult, String> {
     log::info!("[ocr_detect_with_shared_buffer] start detect");
 
@@ -196,6 +310,9 @@ pub async fn ocr_detect_with_shared_buffer(
         ),
         scale_factor,
         detect_angle,
+        engine.unwrap_or_default(),
+        model_id,
+        get_detect_options(preset, options),
     )
     .await
 }
@@ -207,3 +324,838 @@ pub async fn ocr_release(ocr_service: tauri::State<'_, Mutex<OcrService>>) -> Re
 
     Ok(())
 }
+
+/// 批量识别进度事件
+const OCR_BATCH_PROGRESS_EVENT: &str = "ocr-batch-progress";
+/// 每识别多少个文件保存一次索引，避免中途退出时丢失结果
+const OCR_BATCH_SAVE_INTERVAL: usize = 20;
+const OCR_BATCH_DEFAULT_CONCURRENCY: usize = 2;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OcrBatchParams {
+    pub directory: PathBuf,
+    pub recursive: bool,
+    /// 索引文件路径，JSON 格式
+    pub index_path: PathBuf,
+    /// 同时读取和解码的图片数量，识别会话本身是串行的
+    pub concurrency: Option<usize>,
+    pub scale_factor: f32,
+    pub detect_angle: bool,
+    pub engine: Option<OcrEngineType>,
+    pub model_id: Option<String>,
+    pub preset: Option<OcrDetectPreset>,
+    pub options: Option<OcrDetectOptions>,
+}
+
+fn emit_ocr_batch_progress(app: &tauri::AppHandle, progress: &OcrBatchProgress) {
+    if let Err(e) = app.emit(OCR_BATCH_PROGRESS_EVENT, progress) {
+        log::error!(
+            "[emit_ocr_batch_progress] Failed to emit ocr batch progress: {}",
+            e
+        );
+    }
+}
+
+fn load_batch_image(file: &OcrBatchFile) -> Result<image::DynamicImage, String> {
+    let image = match image::open(&file.path) {
+        Ok(image) => image,
+        Err(e) => {
+            return Err(format!(
+                "[load_batch_image] Failed to open image {}: {}",
+                file.path.display(),
+                e
+            ));
+        }
+    };
+
+    // ocr_detect_core 只接受 RGB 和 RGBA 图片
+    Ok(match image {
+        image::DynamicImage::ImageRgb8(_) | image::DynamicImage::ImageRgba8(_) => image,
+        image => image::DynamicImage::ImageRgb8(image.to_rgb8()),
+    })
+}
+
+async fn run_ocr_batch_job(
+    app: &tauri::AppHandle,
+    job_id: String,
+    files: Vec<OcrBatchFile>,
+    mut index: OcrIndex,
+    params: OcrBatchParams,
+    cancelled: Arc<AtomicBool>,
+) -> OcrBatchProgress {
+    let mut progress = OcrBatchProgress {
+        job_id,
+        total: files.len(),
+        processed: 0,
+        skipped: 0,
+        failed: 0,
+        current_path: None,
+        finished: false,
+        cancelled: false,
+        error: None,
+    };
+
+    index.retain_files(&params.directory, params.recursive, &files);
+
+    let options = get_detect_options(params.preset, params.options);
+    let engine = params.engine.unwrap_or_default();
+    let semaphore = Arc::new(Semaphore::new(
+        params
+            .concurrency
+            .unwrap_or(OCR_BATCH_DEFAULT_CONCURRENCY)
+            .max(1),
+    ));
+
+    let mut join_set = JoinSet::new();
+    for file in files {
+        if index.is_unchanged(&file) {
+            progress.skipped += 1;
+            continue;
+        }
+
+        let app = app.clone();
+        let semaphore = semaphore.clone();
+        let cancelled = cancelled.clone();
+        let model_id = params.model_id.clone();
+        let scale_factor = params.scale_factor;
+        let detect_angle = params.detect_angle;
+
+        join_set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            if cancelled.load(Ordering::Relaxed) {
+                return (file, None);
+            }
+
+            let load_file = file.clone();
+            let image =
+                match tokio::task::spawn_blocking(move || load_batch_image(&load_file)).await {
+                    Ok(Ok(image)) => image,
+                    Ok(Err(e)) => return (file, Some(Err(e))),
+                    Err(e) => {
+                        return (
+                            file,
+                            Some(Err(format!(
+                                "[run_ocr_batch_job] Failed to join load task: {}",
+                                e
+                            ))),
+                        );
+                    }
+                };
+
+            let result = ocr_detect_core(
+                app.state::<Mutex<OcrService>>(),
+                image,
+                scale_factor,
+                detect_angle,
+                engine,
+                model_id,
+                options,
+            )
+            .await;
+
+            (file, Some(result))
+        });
+    }
+
+    emit_ocr_batch_progress(app, &progress);
+
+    let mut unsaved_count = 0;
+    while let Some(join_result) = join_set.join_next().await {
+        let (file, result) = match join_result {
+            Ok(result) => result,
+            Err(e) => {
+                log::error!("[run_ocr_batch_job] Failed to join ocr task: {}", e);
+                progress.failed += 1;
+                continue;
+            }
+        };
+
+        match result {
+            // 任务取消后未执行的文件
+            None => continue,
+            Some(Ok(ocr_result)) => {
+                // 识别前图片可能被放大，换算为相对原图的缩放比例
+                let scale_factor = if params.scale_factor > 0.0 {
+                    ocr_result.scale_factor / params.scale_factor
+                } else {
+                    1.0
+                };
+
+                let search_service = app.state::<Mutex<CaptureSearchService>>();
+                let mut search_service = search_service.lock().await;
+                if search_service.is_initialized() {
+                    if let Err(e) = search_service.upsert(&CaptureSearchRecord {
+                        path: file.path.clone(),
+                        window_title: None,
+                        captured_at: file.modified_time,
+                        text_blocks: ocr_result.text_blocks.clone(),
+                        scale_factor,
+                    }) {
+                        log::error!("[run_ocr_batch_job] {}", e);
+                    }
+                }
+                drop(search_service);
+
+                index.entries.insert(
+                    file.path.clone(),
+                    OcrIndexEntry {
+                        modified_time: file.modified_time,
+                        file_size: file.file_size,
+                        text_blocks: ocr_result.text_blocks,
+                        scale_factor,
+                    },
+                );
+                progress.processed += 1;
+                unsaved_count += 1;
+            }
+            Some(Err(e)) => {
+                log::warn!(
+                    "[run_ocr_batch_job] Failed to detect {}: {}",
+                    file.path.display(),
+                    e
+                );
+                progress.failed += 1;
+            }
+        }
+
+        progress.current_path = Some(file.path);
+
+        if unsaved_count >= OCR_BATCH_SAVE_INTERVAL {
+            unsaved_count = 0;
+            if let Err(e) = index.save(&params.index_path).await {
+                log::error!("[run_ocr_batch_job] {}", e);
+            }
+        }
+
+        emit_ocr_batch_progress(app, &progress);
+    }
+
+    if let Err(e) = index.save(&params.index_path).await {
+        progress.error = Some(e);
+    }
+
+    progress.current_path = None;
+    progress.cancelled = cancelled.load(Ordering::Relaxed);
+    progress.finished = true;
+
+    progress
+}
+
+/// 在后台识别目录下的图片，返回任务 id，进度通过 ocr-batch-progress 事件通知
+pub async fn ocr_batch_start(
+    app: tauri::AppHandle,
+    ocr_batch_service: tauri::State<'_, Arc<OcrBatchService>>,
+    params: OcrBatchParams,
+) -> Result<String, String> {
+    log::info!("[ocr_batch_start] params: {:?}", params);
+
+    let directory = params.directory.clone();
+    let recursive = params.recursive;
+    let files = match tokio::task::spawn_blocking(move || {
+        OcrBatchService::collect_image_files(&directory, recursive)
+    })
+    .await
+    {
+        Ok(files) => files?,
+        Err(e) => {
+            return Err(format!(
+                "[ocr_batch_start] Failed to collect image files: {}",
+                e
+            ));
+        }
+    };
+    let index = OcrIndex::load(&params.index_path).await?;
+
+    let (job_id, cancelled) = ocr_batch_service.create_job();
+    let ocr_batch_service = ocr_batch_service.inner().clone();
+    let task_job_id = job_id.clone();
+
+    tokio::spawn(async move {
+        let progress =
+            run_ocr_batch_job(&app, task_job_id.clone(), files, index, params, cancelled).await;
+
+        ocr_batch_service.finish_job(&task_job_id);
+        emit_ocr_batch_progress(&app, &progress);
+    });
+
+    Ok(job_id)
+}
+
+pub async fn ocr_batch_cancel(
+    ocr_batch_service: tauri::State<'_, Arc<OcrBatchService>>,
+    job_id: String,
+) -> Result<(), String> {
+    ocr_batch_service.cancel_job(&job_id)
+}
+
+pub async fn capture_search_init(
+    capture_search_service: tauri::State<'_, Mutex<CaptureSearchService>>,
+    database_path: PathBuf,
+) -> Result<(), String> {
+    let mut capture_search_service = capture_search_service.lock().await;
+
+    capture_search_service.init(&database_path)
+}
+
+pub async fn capture_search_query(
+    capture_search_service: tauri::State<'_, Mutex<CaptureSearchService>>,
+    query: CaptureSearchQuery,
+) -> Result<Vec<CaptureSearchResult>, String> {
+    let mut capture_search_service = capture_search_service.lock().await;
+
+    capture_search_service.search(&query)
+}
+
+pub async fn capture_search_remove(
+    capture_search_service: tauri::State<'_, Mutex<CaptureSearchService>>,
+    file_path: PathBuf,
+) -> Result<(), String> {
+    let mut capture_search_service = capture_search_service.lock().await;
+
+    capture_search_service.remove(&file_path)
+}
+
+/// 在后台识别截图并写入检索索引
+///
+/// OCR 未初始化或识别失败时仍会记录窗口标题和截图时间
+pub fn capture_search_index_file(
+    app: &tauri::AppHandle,
+    file_path: PathBuf,
+    window_title: Option<String>,
+) {
+    let app = app.clone();
+
+    tokio::spawn(async move {
+        if !app
+            .state::<Mutex<CaptureSearchService>>()
+            .lock()
+            .await
+            .is_initialized()
+        {
+            return;
+        }
+
+        let captured_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0);
+
+        let load_file_path = file_path.clone();
+        let image = tokio::task::spawn_blocking(move || {
+            load_batch_image(&OcrBatchFile {
+                path: load_file_path,
+                modified_time: 0,
+                file_size: 0,
+            })
+        })
+        .await;
+
+        let ocr_result = match image {
+            Ok(Ok(image)) => {
+                ocr_detect_core(
+                    app.state::<Mutex<OcrService>>(),
+                    image,
+                    1.0,
+                    false,
+                    OcrEngineType::default(),
+                    None,
+                    OcrDetectOptions::default(),
+                )
+                .await
+            }
+            Ok(Err(e)) => Err(e),
+            Err(e) => Err(format!(
+                "[capture_search_index_file] Failed to join load task: {}",
+                e
+            )),
+        };
+
+        let (text_blocks, scale_factor) = match ocr_result {
+            Ok(ocr_result) => (ocr_result.text_blocks, ocr_result.scale_factor),
+            Err(e) => {
+                log::warn!(
+                    "[capture_search_index_file] Failed to detect {}: {}",
+                    file_path.display(),
+                    e
+                );
+                (Vec::new(), 1.0)
+            }
+        };
+
+        let capture_search_service = app.state::<Mutex<CaptureSearchService>>();
+        let mut capture_search_service = capture_search_service.lock().await;
+        if let Err(e) = capture_search_service.upsert(&CaptureSearchRecord {
+            path: file_path,
+            window_title,
+            captured_at,
+            text_blocks,
+            scale_factor,
+        }) {
+            log::error!("[capture_search_index_file] {}", e);
+        }
+    });
+}
+
+/// 识别图片中的敏感文字并遮挡，返回遮挡后的图片和匹配结果
+pub async fn ocr_redact_image_core(
+    ocr_service: tauri::State<'_, Mutex<OcrService>>,
+    image: image::DynamicImage,
+    scale_factor: f32,
+    engine: OcrEngineType,
+    model_id: Option<String>,
+    detect_options: OcrDetectOptions,
+    redaction_options: &RedactionOptions,
+) -> Result<(image::DynamicImage, Vec<RedactionMatch>), String> {
+    let redaction_service = RedactionService::new(redaction_options)?;
+
+    let ocr_image = match image {
+        image::DynamicImage::ImageRgb8(_) | image::DynamicImage::ImageRgba8(_) => image.clone(),
+        _ => image::DynamicImage::ImageRgba8(image.to_rgba8()),
+    };
+    let ocr_result = ocr_detect_core(
+        ocr_service,
+        ocr_image,
+        scale_factor,
+        false,
+        engine,
+        model_id,
+        detect_options,
+    )
+    .await?;
+
+    // 识别前图片可能被放大，换算为相对原图的缩放比例
+    let relative_scale_factor = if scale_factor > 0.0 {
+        ocr_result.scale_factor / scale_factor
+    } else {
+        1.0
+    };
+
+    let matches = redaction_service.find_matches(&ocr_result.text_blocks, relative_scale_factor);
+    let mut image = image;
+    redaction_service.apply(&mut image, &matches);
+
+    Ok((image, matches))
+}
+
+/// 遮挡参数，保存和上传时通过 x-redaction-options 请求头传入
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct OcrRedactParams {
+    /// 截图时的缩放比例，为空时视为 1
+    pub scale_factor: Option<f32>,
+    pub engine: Option<OcrEngineType>,
+    pub model_id: Option<String>,
+    pub preset: Option<OcrDetectPreset>,
+    pub detect_options: Option<OcrDetectOptions>,
+    pub redaction_options: Option<RedactionOptions>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OcrRedactFileParams {
+    pub input_path: PathBuf,
+    /// 为空时覆盖原文件
+    pub output_path: Option<PathBuf>,
+    #[serde(flatten)]
+    pub redact_params: OcrRedactParams,
+}
+
+async fn ocr_redact_image_with_params(
+    ocr_service: tauri::State<'_, Mutex<OcrService>>,
+    image: image::DynamicImage,
+    params: OcrRedactParams,
+) -> Result<(image::DynamicImage, Vec<RedactionMatch>), String> {
+    ocr_redact_image_core(
+        ocr_service,
+        image,
+        params.scale_factor.unwrap_or(1.0),
+        params.engine.unwrap_or_default(),
+        params.model_id,
+        get_detect_options(params.preset, params.detect_options),
+        &params.redaction_options.unwrap_or_default(),
+    )
+    .await
+}
+
+/// 遮挡图片文件中的敏感文字，不依赖窗口，可在保存或上传前调用
+pub async fn ocr_redact_file(
+    ocr_service: tauri::State<'_, Mutex<OcrService>>,
+    params: OcrRedactFileParams,
+) -> Result<Vec<RedactionMatch>, String> {
+    log::info!("[ocr_redact_file] params: {:?}", params);
+
+    let input_path = params.input_path.clone();
+    let image = match tokio::task::spawn_blocking(move || image::open(&input_path)).await {
+        Ok(Ok(image)) => image,
+        Ok(Err(e)) => {
+            return Err(format!(
+                "[ocr_redact_file] Failed to open image {}: {}",
+                params.input_path.display(),
+                e
+            ));
+        }
+        Err(e) => {
+            return Err(format!("[ocr_redact_file] Failed to join load task: {}", e));
+        }
+    };
+
+    let (image, matches) =
+        ocr_redact_image_with_params(ocr_service, image, params.redact_params).await?;
+
+    snow_shot_app_utils::save_image_to_file(
+        &image,
+        params.output_path.unwrap_or(params.input_path),
+    )
+    .await?;
+
+    Ok(matches)
+}
+
+/// 遮挡编码后的图片数据中的敏感文字，按原格式重新编码
+pub async fn ocr_redact_data(
+    ocr_service: tauri::State<'_, Mutex<OcrService>>,
+    data: &[u8],
+    params: OcrRedactParams,
+) -> Result<(Vec<u8>, Vec<RedactionMatch>), String> {
+    let format = image::guess_format(data)
+        .map_err(|e| format!("[ocr_redact_data] Unsupported image format: {}", e))?;
+    let data = data.to_vec();
+    let image = match tokio::task::spawn_blocking(move || {
+        image::load_from_memory_with_format(&data, format)
+    })
+    .await
+    {
+        Ok(Ok(image)) => image,
+        Ok(Err(e)) => {
+            return Err(format!("[ocr_redact_data] Invalid image: {}", e));
+        }
+        Err(e) => {
+            return Err(format!("[ocr_redact_data] Failed to join load task: {}", e));
+        }
+    };
+
+    let (image, matches) = ocr_redact_image_with_params(ocr_service, image, params).await?;
+
+    let data = match tokio::task::spawn_blocking(move || {
+        // JPEG 不支持透明通道
+        let image = match format {
+            image::ImageFormat::Jpeg => image::DynamicImage::ImageRgb8(image.to_rgb8()),
+            _ => image,
+        };
+
+        let mut data = Vec::new();
+        image.write_to(&mut Cursor::new(&mut data), format)?;
+        Ok::<_, image::ImageError>(data)
+    })
+    .await
+    {
+        Ok(Ok(data)) => data,
+        Ok(Err(e)) => {
+            return Err(format!("[ocr_redact_data] Failed to encode image: {}", e));
+        }
+        Err(e) => {
+            return Err(format!(
+                "[ocr_redact_data] Failed to join encode task: {}",
+                e
+            ));
+        }
+    };
+
+    Ok((data, matches))
+}
+
+/// 请求中包含 x-redaction-options 时遮挡请求体中的图片，否则返回 None
+///
+/// 保存和上传前调用，遮挡失败时不继续保存或上传
+pub async fn ocr_redact_request_data(
+    ocr_service: tauri::State<'_, Mutex<OcrService>>,
+    request: &tauri::ipc::Request<'_>,
+    data: &[u8],
+) -> Result<Option<Vec<u8>>, String> {
+    let params: OcrRedactParams = match snow_shot_app_utils::get_request_optional_string_header(
+        request,
+        "x-redaction-options",
+    )? {
+        Some(params) => serde_json::from_str(&params)
+            .map_err(|e| format!("[ocr_redact_request_data] Invalid redaction options: {}", e))?,
+        None => return Ok(None),
+    };
+
+    let (data, matches) = ocr_redact_data(ocr_service, data, params).await?;
+    log::info!(
+        "[ocr_redact_request_data] Redacted {} text blocks",
+        matches.len()
+    );
+
+    Ok(Some(data))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OcrTranslateParams {
+    pub backend: TranslationBackendConfig,
+    /// 源语言，为空时自动检测
+    pub source_language: Option<String>,
+    pub target_language: String,
+    /// 请求超时时间，秒
+    pub timeout_secs: Option<u64>,
+    /// 截图时的缩放比例，为空时视为 1
+    pub scale_factor: Option<f32>,
+    pub engine: Option<OcrEngineType>,
+    pub model_id: Option<String>,
+    pub preset: Option<OcrDetectPreset>,
+    pub detect_options: Option<OcrDetectOptions>,
+    pub render_options: Option<TranslationRenderOptions>,
+}
+
+/// 识别图片中的文字并翻译，将译文绘制到原文字的位置
+pub async fn ocr_translate_image_core(
+    ocr_service: tauri::State<'_, Mutex<OcrService>>,
+    image: image::DynamicImage,
+    params: OcrTranslateParams,
+) -> Result<(image::RgbaImage, Vec<TranslatedTextBlock>), String> {
+    // 先加载字体，避免识别和翻译后才发现字体不可用
+    let render_service =
+        TranslationRenderService::new(params.render_options.clone().unwrap_or_default())?;
+    let translation_service = TranslationService::new(params.backend.clone(), params.timeout_secs)
+        .map_err(|e| {
+            format!(
+                "[ocr_translate_image_core] Failed to create translation service: {}",
+                e
+            )
+        })?;
+
+    let scale_factor = params.scale_factor.unwrap_or(1.0);
+    let ocr_image = match image {
+        image::DynamicImage::ImageRgb8(_) | image::DynamicImage::ImageRgba8(_) => image.clone(),
+        _ => image::DynamicImage::ImageRgba8(image.to_rgba8()),
+    };
+    let ocr_result = ocr_detect_core(
+        ocr_service,
+        ocr_image,
+        scale_factor,
+        false,
+        params.engine.unwrap_or_default(),
+        params.model_id.clone(),
+        get_detect_options(params.preset, params.detect_options),
+    )
+    .await?;
+
+    let lines: Vec<String> = ocr_result
+        .text_blocks
+        .iter()
+        .map(|text_block| text_block.text.clone())
+        .collect();
+    let translated_lines = translation_service
+        .translate(
+            &lines,
+            params.source_language.as_deref(),
+            &params.target_language,
+        )
+        .await
+        .map_err(|e| format!("[ocr_translate_image_core] Failed to translate: {}", e))?;
+
+    // 识别前图片可能被放大，换算为相对原图的缩放比例
+    let relative_scale_factor = if scale_factor > 0.0 {
+        ocr_result.scale_factor / scale_factor
+    } else {
+        1.0
+    };
+    let translated_text_blocks: Vec<TranslatedTextBlock> = ocr_result
+        .text_blocks
+        .iter()
+        .zip(translated_lines)
+        .map(|(text_block, translated_text)| {
+            TranslatedTextBlock::new(text_block, translated_text, relative_scale_factor)
+        })
+        .collect();
+
+    let translated_image = render_service.render(&image, &translated_text_blocks);
+
+    Ok((translated_image, translated_text_blocks))
+}
+
+/// 翻译截图，请求体为 PNG 图片，参数通过 x-translate-params 传递，返回 PNG 图片
+pub async fn ocr_translate_image(
+    ocr_service: tauri::State<'_, Mutex<OcrService>>,
+    request: tauri::ipc::Request<'_>,
+) -> Result<tauri::ipc::Response, String> {
+    let image_data = match request.body() {
+        tauri::ipc::InvokeBody::Raw(data) => data,
+        _ => return Err("[ocr_translate_image] Invalid request body".to_string()),
+    };
+
+    let image = match image::load(Cursor::new(image_data), image::ImageFormat::Png) {
+        Ok(image) => image,
+        Err(_) => return Err("[ocr_translate_image] Invalid image".to_string()),
+    };
+
+    let params: OcrTranslateParams = serde_json::from_str(
+        &snow_shot_app_utils::get_request_string_header(&request, "x-translate-params")?,
+    )
+    .map_err(|e| format!("[ocr_translate_image] Invalid translate params: {}", e))?;
+
+    let (translated_image, _) = ocr_translate_image_core(ocr_service, image, params).await?;
+
+    Ok(tauri::ipc::Response::new(
+        snow_shot_app_utils::encode_image(
+            &image::DynamicImage::ImageRgba8(translated_image),
+            snow_shot_app_utils::ImageEncoder::Png,
+        ),
+    ))
+}
+
+/// 翻译图片文件并保存到 output_path，不依赖窗口
+pub async fn ocr_translate_file(
+    ocr_service: tauri::State<'_, Mutex<OcrService>>,
+    input_path: PathBuf,
+    output_path: PathBuf,
+    params: OcrTranslateParams,
+) -> Result<Vec<TranslatedTextBlock>, String> {
+    let load_path = input_path.clone();
+    let image = match tokio::task::spawn_blocking(move || image::open(&load_path)).await {
+        Ok(Ok(image)) => image,
+        Ok(Err(e)) => {
+            return Err(format!(
+                "[ocr_translate_file] Failed to open image {}: {}",
+                input_path.display(),
+                e
+            ));
+        }
+        Err(e) => {
+            return Err(format!(
+                "[ocr_translate_file] Failed to join load task: {}",
+                e
+            ));
+        }
+    };
+
+    let (translated_image, translated_text_blocks) =
+        ocr_translate_image_core(ocr_service, image, params).await?;
+
+    snow_shot_app_utils::save_image_to_file(
+        &image::DynamicImage::ImageRgba8(translated_image),
+        output_path,
+    )
+    .await?;
+
+    Ok(translated_text_blocks)
+}
+
+/// 识别截图中的二维码和条码，请求体为 PNG 图片
+pub async fn barcode_detect(
+    request: tauri::ipc::Request<'_>,
+) -> Result<Vec<BarcodeResult>, String> {
+    let image_data = match request.body() {
+        tauri::ipc::InvokeBody::Raw(data) => data,
+        _ => return Err("[barcode_detect] Invalid request body".to_string()),
+    };
+
+    let image = match image::load(Cursor::new(image_data), image::ImageFormat::Png) {
+        Ok(image) => image,
+        Err(_) => return Err("[barcode_detect] Invalid image".to_string()),
+    };
+
+    let region: Option<ElementRect> = match request.headers().get("x-barcode-region") {
+        Some(header) => match header
+            .to_str()
+            .map_err(|e| e.to_string())
+            .and_then(|region| serde_json::from_str(region).map_err(|e| e.to_string()))
+        {
+            Ok(region) => Some(region),
+            Err(e) => return Err(format!("[barcode_detect] Invalid region: {}", e)),
+        },
+        None => None,
+    };
+
+    let barcode_types: Vec<BarcodeType> = match request.headers().get("x-barcode-types") {
+        Some(header) => match header
+            .to_str()
+            .map_err(|e| e.to_string())
+            .and_then(|types| serde_json::from_str(types).map_err(|e| e.to_string()))
+        {
+            Ok(barcode_types) => barcode_types,
+            Err(e) => return Err(format!("[barcode_detect] Invalid barcode types: {}", e)),
+        },
+        None => Vec::new(),
+    };
+
+    match tokio::task::spawn_blocking(move || {
+        BarcodeService::detect(&image, region, &barcode_types)
+    })
+    .await
+    {
+        Ok(result) => result,
+        Err(e) => Err(format!(
+            "[barcode_detect] Failed to join detect task: {}",
+            e
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 记录引擎收到的参数
+    #[derive(Default)]
+    struct RecordEngine {
+        options: Option<OcrDetectOptions>,
+        image_size: Option<(u32, u32)>,
+    }
+
+    impl OcrEngine for RecordEngine {
+        fn engine_type(&self) -> OcrEngineType {
+            OcrEngineType::RapidOcr
+        }
+
+        fn is_session_ready(&self) -> bool {
+            true
+        }
+
+        fn init_session(&mut self) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn release_session(&mut self) {}
+
+        fn get_models(&self) -> Vec<OcrModelInfo> {
+            Vec::new()
+        }
+
+        fn detect(
+            &mut self,
+            image: &image::RgbImage,
+            _detect_angle: bool,
+            options: &OcrDetectOptions,
+            _model_id: Option<&str>,
+        ) -> Result<Vec<TextBlock>, String> {
+            self.options = Some(*options);
+            self.image_size = Some(image.dimensions());
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_detect_with_preset() {
+        let options = get_detect_options(Some(OcrDetectPreset::DenseCode), None);
+        let mut engine = RecordEngine::default();
+
+        let result = detect_with_engine(
+            &mut engine,
+            image::DynamicImage::ImageRgba8(image::RgbaImage::new(100, 50)),
+            1.0,
+            false,
+            None,
+            &options,
+        )
+        .unwrap();
+
+        // DenseCode 预设的 min_scale_factor 为 2.0
+        assert_eq!(result.scale_factor, 2.0);
+        assert_eq!(engine.image_size, Some((200, 100)));
+
+        let detect_options = engine.options.unwrap();
+        assert_eq!(detect_options.box_thresh, options.box_thresh);
+        assert_eq!(detect_options.box_score_thresh, options.box_score_thresh);
+        assert_eq!(detect_options.unclip_ratio, options.unclip_ratio);
+        assert_eq!(detect_options.angle_threshold, options.angle_threshold);
+    }
+}
@@ -1,10 +1,80 @@
 use snow_shot_app_services::file_cache_service::FileCacheService;
+use snow_shot_app_services::ocr_service::OcrService;
 use std::{path::PathBuf, sync::Arc};
 use tauri::command;
+use tokio::sync::Mutex;
 
 #[command]
-pub async fn save_file(request: tauri::ipc::Request<'_>) -> Result<(), String> {
-    snow_shot_tauri_commands_file::save_file(request).await
+pub async fn save_file(
+    app: tauri::AppHandle,
+    ocr_instance: tauri::State<'_, Mutex<OcrService>>,
+    request: tauri::ipc::Request<'_>,
+) -> Result<(), String> {
+    let file_type = snow_shot_app_utils::get_request_string_header(&request, "x-file-type").ok();
+    let file_path =
+        snow_shot_app_utils::get_request_optional_string_header(&request, "x-file-path")?;
+    let window_title =
+        snow_shot_app_utils::get_request_optional_string_header(&request, "x-window-title")?;
+    // 传入上传配置时，保存后加入后台上传队列
+    let upload_profile_id =
+        snow_shot_app_utils::get_request_optional_string_header(&request, "x-upload-profile-id")?;
+
+    // 传入 x-redaction-options 时先遮挡敏感文字，再写入文件
+    let redacted_data = match request.body() {
+        tauri::ipc::InvokeBody::Raw(data) => {
+            snow_shot_tauri_commands_ocr::ocr_redact_request_data(ocr_instance, &request, data)
+                .await?
+        }
+        _ => None,
+    };
+    match (&redacted_data, &file_path, &file_type) {
+        (Some(data), Some(file_path), Some(file_type)) => {
+            snow_shot_tauri_commands_file::save_file_data(
+                PathBuf::from(file_path),
+                file_type,
+                data,
+            )
+            .await?;
+        }
+        (Some(_), _, _) => {
+            return Err(String::from("[save_file] Missing file path or file type"));
+        }
+        (None, _, _) => snow_shot_tauri_commands_file::save_file(request).await?,
+    }
+
+    let file_path = match file_path {
+        Some(file_path) => PathBuf::from(file_path),
+        None => return Ok(()),
+    };
+
+    // 保存的图片写入截图检索索引
+    if file_type
+        .as_deref()
+        .is_some_and(|file_type| file_type.starts_with("image/"))
+    {
+        snow_shot_tauri_commands_ocr::capture_search_index_file(
+            &app,
+            file_path.clone(),
+            window_title,
+        );
+    }
+
+    if let Some(upload_profile_id) = upload_profile_id {
+        // 上传失败不影响保存结果
+        match snow_shot_tauri_commands_http_service::upload_queue_add(
+            &app,
+            upload_profile_id,
+            file_path,
+            None,
+            file_type,
+            None,
+        ) {
+            Ok(_) => (),
+            Err(e) => log::warn!("[save_file] Failed to add upload queue job: {}", e),
+        }
+    }
+
+    Ok(())
 }
 
 #[command]
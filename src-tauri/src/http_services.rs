@@ -1,38 +1,369 @@
-use snow_shot_app_utils::{
-    get_request_bool_header, get_request_optional_string_header, get_request_string_header,
+use snow_shot_app_services::ocr_service::OcrService;
+use snow_shot_app_utils::{get_request_optional_string_header, get_request_string_header};
+use snow_shot_http_services::{
+    PostUploadOptions, PostUploadResult, S3MultipartOptions, S3MultipartUploadState,
+    S3ShareOptions, UploadHistoryQuery, UploadHistoryRecord, UploadProfile, UploadQueueJob,
+    UploadQueueOptions, UploadResult, UrlShortenerProfile,
 };
+use snow_shot_tauri_commands_http_service::S3UploadTaskList;
+use snow_shot_tauri_commands_ocr::OcrRedactParams;
+use std::path::PathBuf;
 use tauri::command;
+use tokio::sync::Mutex;
 
+/// 使用 x-upload-profile-id 指定的配置上传，密钥从凭据库读取
+///
+/// 传入 x-post-upload-options 时执行上传后处理，并返回处理后的地址
+///
+/// 传入 x-redaction-options 时先遮挡敏感文字再上传
 #[command]
-pub async fn upload_to_s3(request: tauri::ipc::Request<'_>) -> Result<String, String> {
+pub async fn upload_to_s3(
+    app: tauri::AppHandle,
+    ocr_instance: tauri::State<'_, Mutex<OcrService>>,
+    request: tauri::ipc::Request<'_>,
+) -> Result<String, String> {
     let data = match request.body() {
         tauri::ipc::InvokeBody::Raw(data) => data,
         _ => return Err(String::from("[upload_to_s3] Invalid request body")),
     };
+    let redacted_data =
+        snow_shot_tauri_commands_ocr::ocr_redact_request_data(ocr_instance, &request, data).await?;
+    let data = redacted_data.as_deref().unwrap_or(data);
 
-    let endpoint: String = get_request_string_header(&request, "x-endpoint")?;
-    let region: String = get_request_string_header(&request, "x-region")?;
-    let access_key_id: String = get_request_string_header(&request, "x-access-key-id")?;
-    let secret_access_key: String = get_request_string_header(&request, "x-secret-access-key")?;
-    let bucket: String = get_request_string_header(&request, "x-bucket")?;
-    let path_prefix: Option<String> =
-        get_request_optional_string_header(&request, "x-path-prefix")?;
-    let force_path_style: bool = get_request_bool_header(&request, "x-force-path-style")?;
+    let share_options: Option<S3ShareOptions> =
+        match get_request_optional_string_header(&request, "x-share-options")? {
+            Some(share_options) => Some(
+                serde_json::from_str(&share_options)
+                    .map_err(|e| format!("[upload_to_s3] Invalid share options: {}", e))?,
+            ),
+            None => None,
+        };
+    let post_upload_options: Option<PostUploadOptions> =
+        match get_request_optional_string_header(&request, "x-post-upload-options")? {
+            Some(post_upload_options) => Some(
+                serde_json::from_str(&post_upload_options)
+                    .map_err(|e| format!("[upload_to_s3] Invalid post upload options: {}", e))?,
+            ),
+            None => None,
+        };
+
+    let profile_id: String = get_request_string_header(&request, "x-upload-profile-id")?;
+    let filename: String = get_request_string_header(&request, "x-filename")?;
+    let content_type: Option<String> =
+        get_request_optional_string_header(&request, "x-content-type")?;
+
+    snow_shot_tauri_commands_http_service::upload_to_s3_with_profile(
+        &app,
+        &profile_id,
+        data,
+        filename,
+        content_type,
+        share_options,
+        post_upload_options,
+    )
+    .await
+}
+
+/// 对已上传的地址执行上传后处理
+#[command]
+pub async fn upload_post_process(
+    app: tauri::AppHandle,
+    url: String,
+    filename: String,
+    content_type: Option<String>,
+    options: PostUploadOptions,
+) -> Result<PostUploadResult, String> {
+    Ok(
+        snow_shot_tauri_commands_http_service::run_post_upload_actions(
+            &app,
+            url,
+            &filename,
+            content_type.as_deref(),
+            &options,
+        )
+        .await,
+    )
+}
+
+#[command]
+pub async fn s3_get_presigned_url(
+    app: tauri::AppHandle,
+    profile_id: String,
+    object_key: String,
+    expiry_secs: u32,
+) -> Result<String, String> {
+    snow_shot_tauri_commands_http_service::s3_get_presigned_url(
+        &app,
+        &profile_id,
+        &object_key,
+        expiry_secs,
+    )
+    .await
+}
+
+#[command]
+pub async fn upload_file_to_s3(
+    app: tauri::AppHandle,
+    task_list: tauri::State<'_, S3UploadTaskList>,
+    task_id: String,
+    profile_id: String,
+    file_path: PathBuf,
+    filename: String,
+    content_type: Option<String>,
+    options: Option<S3MultipartOptions>,
+    resume_state: Option<S3MultipartUploadState>,
+) -> Result<String, String> {
+    snow_shot_tauri_commands_http_service::upload_file_to_s3(
+        &app,
+        &task_list,
+        task_id,
+        profile_id,
+        file_path,
+        filename,
+        content_type,
+        options,
+        resume_state,
+    )
+    .await
+}
+
+#[command]
+pub async fn cancel_s3_upload(
+    task_list: tauri::State<'_, S3UploadTaskList>,
+    task_id: String,
+) -> Result<(), String> {
+    snow_shot_tauri_commands_http_service::cancel_s3_upload(&task_list, &task_id)
+}
+
+/// 上传到 x-upload-profile-id 指定的目标
+///
+/// 传入 x-post-upload-options 时执行上传后处理，并返回处理后的地址
+///
+/// 传入 x-redaction-options 时先遮挡敏感文字再上传
+#[command]
+pub async fn upload_to_destination(
+    app: tauri::AppHandle,
+    ocr_instance: tauri::State<'_, Mutex<OcrService>>,
+    request: tauri::ipc::Request<'_>,
+) -> Result<UploadResult, String> {
+    let data = match request.body() {
+        tauri::ipc::InvokeBody::Raw(data) => data,
+        _ => return Err(String::from("[upload_to_destination] Invalid request body")),
+    };
+    let redacted_data =
+        snow_shot_tauri_commands_ocr::ocr_redact_request_data(ocr_instance, &request, data).await?;
+    let data = redacted_data.as_deref().unwrap_or(data);
+
+    let profile_id: String = get_request_string_header(&request, "x-upload-profile-id")?;
+    let filename: String = get_request_string_header(&request, "x-filename")?;
+    let content_type: Option<String> =
+        get_request_optional_string_header(&request, "x-content-type")?;
+    let post_upload_options: Option<PostUploadOptions> =
+        match get_request_optional_string_header(&request, "x-post-upload-options")? {
+            Some(post_upload_options) => {
+                Some(serde_json::from_str(&post_upload_options).map_err(|e| {
+                    format!("[upload_to_destination] Invalid post upload options: {}", e)
+                })?)
+            }
+            None => None,
+        };
+
+    snow_shot_tauri_commands_http_service::upload_to_destination(
+        &app,
+        &profile_id,
+        data,
+        filename,
+        content_type,
+        post_upload_options,
+    )
+    .await
+}
+
+#[command]
+pub async fn upload_profile_list(app: tauri::AppHandle) -> Result<Vec<UploadProfile>, String> {
+    snow_shot_tauri_commands_http_service::upload_profile_list(&app)
+}
+
+#[command]
+pub async fn upload_profile_save(
+    app: tauri::AppHandle,
+    profile: UploadProfile,
+) -> Result<(), String> {
+    snow_shot_tauri_commands_http_service::upload_profile_save(&app, profile)
+}
+
+#[command]
+pub async fn upload_profile_delete(
+    app: tauri::AppHandle,
+    profile_id: String,
+) -> Result<(), String> {
+    snow_shot_tauri_commands_http_service::upload_profile_delete(&app, &profile_id)
+}
+
+#[command]
+pub async fn url_shortener_list(app: tauri::AppHandle) -> Result<Vec<UrlShortenerProfile>, String> {
+    snow_shot_tauri_commands_http_service::url_shortener_list(&app)
+}
+
+#[command]
+pub async fn url_shortener_save(
+    app: tauri::AppHandle,
+    shortener: UrlShortenerProfile,
+) -> Result<(), String> {
+    snow_shot_tauri_commands_http_service::url_shortener_save(&app, shortener)
+}
+
+#[command]
+pub async fn url_shortener_delete(
+    app: tauri::AppHandle,
+    shortener_id: String,
+) -> Result<(), String> {
+    snow_shot_tauri_commands_http_service::url_shortener_delete(&app, &shortener_id)
+}
+
+#[command]
+pub async fn upload_history_query(
+    app: tauri::AppHandle,
+    query: UploadHistoryQuery,
+) -> Result<Vec<UploadHistoryRecord>, String> {
+    snow_shot_tauri_commands_http_service::upload_history_query(&app, query)
+}
+
+#[command]
+pub async fn upload_history_delete(
+    app: tauri::AppHandle,
+    id: i64,
+    delete_remote: bool,
+) -> Result<(), String> {
+    snow_shot_tauri_commands_http_service::upload_history_delete(&app, id, delete_remote).await
+}
+
+/// 加入后台上传队列，目标不可用时自动重试，上传完成后执行 `post_upload_options`
+///
+/// 传入 `redaction_options` 时不修改原文件，遮挡后的数据保存到队列目录再上传
+#[command]
+pub async fn upload_queue_add(
+    app: tauri::AppHandle,
+    ocr_instance: tauri::State<'_, Mutex<OcrService>>,
+    profile_id: String,
+    file_path: PathBuf,
+    filename: Option<String>,
+    content_type: Option<String>,
+    post_upload_options: Option<PostUploadOptions>,
+    redaction_options: Option<OcrRedactParams>,
+) -> Result<UploadQueueJob, String> {
+    if let Some(redaction_options) = redaction_options {
+        let data = tokio::fs::read(&file_path).await.map_err(|e| {
+            format!(
+                "[upload_queue_add] Failed to read file {}: {}",
+                file_path.display(),
+                e
+            )
+        })?;
+        let (data, _) =
+            snow_shot_tauri_commands_ocr::ocr_redact_data(ocr_instance, &data, redaction_options)
+                .await?;
+
+        let filename = match filename {
+            Some(filename) => filename,
+            None => file_path
+                .file_name()
+                .map(|filename| filename.to_string_lossy().to_string())
+                .ok_or_else(|| {
+                    format!(
+                        "[upload_queue_add] Invalid file path: {}",
+                        file_path.display()
+                    )
+                })?,
+        };
+
+        return snow_shot_tauri_commands_http_service::upload_queue_add_data(
+            &app,
+            profile_id,
+            &data,
+            filename,
+            content_type,
+            post_upload_options,
+        )
+        .await;
+    }
+
+    snow_shot_tauri_commands_http_service::upload_queue_add(
+        &app,
+        profile_id,
+        file_path,
+        filename,
+        content_type,
+        post_upload_options,
+    )
+}
+
+/// 将请求体加入后台上传队列，配置通过 x-upload-profile-id 指定
+///
+/// 传入 x-redaction-options 时先遮挡敏感文字再加入队列
+#[command]
+pub async fn upload_queue_add_data(
+    app: tauri::AppHandle,
+    ocr_instance: tauri::State<'_, Mutex<OcrService>>,
+    request: tauri::ipc::Request<'_>,
+) -> Result<UploadQueueJob, String> {
+    let data = match request.body() {
+        tauri::ipc::InvokeBody::Raw(data) => data,
+        _ => return Err(String::from("[upload_queue_add_data] Invalid request body")),
+    };
+    let redacted_data =
+        snow_shot_tauri_commands_ocr::ocr_redact_request_data(ocr_instance, &request, data).await?;
+    let data = redacted_data.as_deref().unwrap_or(data);
+
+    let profile_id: String = get_request_string_header(&request, "x-upload-profile-id")?;
     let filename: String = get_request_string_header(&request, "x-filename")?;
     let content_type: Option<String> =
         get_request_optional_string_header(&request, "x-content-type")?;
+    let post_upload_options: Option<PostUploadOptions> =
+        match get_request_optional_string_header(&request, "x-post-upload-options")? {
+            Some(post_upload_options) => {
+                Some(serde_json::from_str(&post_upload_options).map_err(|e| {
+                    format!("[upload_queue_add_data] Invalid post upload options: {}", e)
+                })?)
+            }
+            None => None,
+        };
 
-    snow_shot_tauri_commands_http_service::upload_to_s3(
-        endpoint,
-        region,
-        access_key_id,
-        secret_access_key,
-        bucket,
-        path_prefix,
-        force_path_style,
+    snow_shot_tauri_commands_http_service::upload_queue_add_data(
+        &app,
+        profile_id,
         data,
         filename,
         content_type,
+        post_upload_options,
     )
     .await
 }
+
+#[command]
+pub async fn upload_queue_list(app: tauri::AppHandle) -> Result<Vec<UploadQueueJob>, String> {
+    snow_shot_tauri_commands_http_service::upload_queue_list(&app)
+}
+
+#[command]
+pub async fn upload_queue_retry(app: tauri::AppHandle, id: i64) -> Result<(), String> {
+    snow_shot_tauri_commands_http_service::upload_queue_retry(&app, id)
+}
+
+#[command]
+pub async fn upload_queue_remove(app: tauri::AppHandle, id: i64) -> Result<(), String> {
+    snow_shot_tauri_commands_http_service::upload_queue_remove(&app, id)
+}
+
+#[command]
+pub async fn upload_queue_clear_completed(app: tauri::AppHandle) -> Result<(), String> {
+    snow_shot_tauri_commands_http_service::upload_queue_clear_completed(&app)
+}
+
+#[command]
+pub async fn upload_queue_set_options(
+    app: tauri::AppHandle,
+    options: UploadQueueOptions,
+) -> Result<(), String> {
+    snow_shot_tauri_commands_http_service::upload_queue_set_options(&app, options)
+}
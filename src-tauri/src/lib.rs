@@ -8,12 +8,12 @@ pub mod ocr;
 pub mod plugin;
 pub mod screenshot;
 pub mod scroll_screenshot;
-// pub mod video_record; // 已移除视频录制功能
+pub mod video_record;
 pub mod webview;
 
 use snow_shot_app_services::listen_mouse_service;
 use snow_shot_tauri_commands_core::FullScreenDrawWindowLabels;
-// use snow_shot_tauri_commands_core::VideoRecordWindowLabels; // 已移除视频录制功能
+use snow_shot_tauri_commands_core::VideoRecordWindowLabels;
 use std::sync::Arc;
 use tauri::Emitter;
 use tokio::sync::Mutex;
@@ -24,13 +24,16 @@ use snow_shot_app_os::ui_automation::UIElements;
 use snow_shot_app_scroll_screenshot_service::scroll_screenshot_capture_service;
 use snow_shot_app_scroll_screenshot_service::scroll_screenshot_image_service;
 use snow_shot_app_scroll_screenshot_service::scroll_screenshot_service;
+use snow_shot_app_services::capture_search_service::CaptureSearchService;
 use snow_shot_app_services::file_cache_service;
 use snow_shot_app_services::free_drag_window_service;
 use snow_shot_app_services::hot_load_page_service;
 use snow_shot_app_services::listen_key_service;
+use snow_shot_app_services::native_video_record_service::NativeVideoRecordService;
+use snow_shot_app_services::ocr_batch_service::OcrBatchService;
 use snow_shot_app_services::ocr_service::OcrService;
 use snow_shot_app_services::resize_window_service;
-// use snow_shot_app_services::video_record_service; // 已移除视频录制功能
+use snow_shot_app_services::video_record_service;
 use snow_shot_app_shared::EnigoManager;
 use snow_shot_global_state::{CaptureState, ReadClipboardState, WebViewSharedBufferState};
 use snow_shot_plugin_service::plugin_service;
@@ -42,7 +45,10 @@ pub static PROFILER: std::sync::LazyLock<Mutex<Option<dhat::Profiler>>> =
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let ocr_instance = Mutex::new(OcrService::new());
-    // let video_record_service = Mutex::new(video_record_service::VideoRecordService::new()); // 已移除视频录制功能
+    let ocr_batch_service = Arc::new(OcrBatchService::new());
+    let capture_search_service = Mutex::new(CaptureSearchService::new());
+    let video_record_service = Mutex::new(video_record_service::VideoRecordService::new());
+    let native_video_record_service = Mutex::new(NativeVideoRecordService::new());
     let hot_load_page_service = Arc::new(hot_load_page_service::HotLoadPageService::new());
     let enigo_instance = Mutex::new(EnigoManager::new());
 
@@ -66,6 +72,10 @@ pub fn run() {
 
     let file_cache_service = Arc::new(file_cache_service::FileCacheService::new());
 
+    let s3_upload_task_list = snow_shot_tauri_commands_http_service::S3UploadTaskList::new();
+    let upload_history_state = snow_shot_tauri_commands_http_service::UploadHistoryState::new();
+    let upload_queue_state = snow_shot_tauri_commands_http_service::UploadQueueState::new();
+
     let enable_run_log = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
     let enable_run_log_clone = enable_run_log.clone();
 
@@ -74,7 +84,7 @@ pub fn run() {
     let capture_state = Mutex::new(CaptureState { capturing: false });
 
     let full_screen_draw_window_labels = Mutex::new(Option::<FullScreenDrawWindowLabels>::None);
-    // let video_record_window_label = Mutex::new(Option::<VideoRecordWindowLabels>::None); // 已移除视频录制功能
+    let video_record_window_label = Mutex::new(Option::<VideoRecordWindowLabels>::None);
 
     let webview_shared_buffer_state = WebViewSharedBufferState::new(false);
 
@@ -207,6 +217,9 @@ pub fn run() {
                 }
             });
 
+            // 继续上传上次退出时未完成的任务
+            snow_shot_tauri_commands_http_service::start_upload_queue(app.handle().clone());
+
             // 如果是调试模式，则显示窗口
             #[cfg(debug_assertions)]
             {
@@ -217,22 +230,28 @@ pub fn run() {
         })
         .manage(ui_elements)
         .manage(ocr_instance)
+        .manage(ocr_batch_service)
+        .manage(capture_search_service)
         .manage(enigo_instance)
         .manage(scroll_screenshot_service)
         .manage(scroll_screenshot_image_service)
         .manage(scroll_screenshot_capture_service)
-        // .manage(video_record_service) // 已移除视频录制功能
+        .manage(video_record_service)
+        .manage(native_video_record_service)
         .manage(free_drag_window_service)
         .manage(resize_window_service)
         .manage(listen_key_service)
         .manage(listen_mouse_service)
         .manage(file_cache_service)
+        .manage(s3_upload_task_list)
+        .manage(upload_history_state)
+        .manage(upload_queue_state)
         .manage(enable_run_log_clone)
         .manage(plugin_service)
         .manage(full_screen_draw_window_labels)
         .manage(webview_shared_buffer_state)
         .manage(hot_load_page_service)
-        // .manage(video_record_window_label) // 已移除视频录制功能
+        .manage(video_record_window_label)
         .manage(capture_state)
         .manage(read_clipboard_state)
         .invoke_handler(tauri::generate_handler![
@@ -261,7 +280,19 @@ pub fn run() {
             #[cfg(target_os = "windows")]
             ocr::ocr_detect_with_shared_buffer,
             ocr::ocr_init,
+            ocr::ocr_init_tesseract,
+            ocr::ocr_register_rec_model,
+            ocr::ocr_get_models,
             ocr::ocr_release,
+            ocr::ocr_batch_start,
+            ocr::ocr_batch_cancel,
+            ocr::ocr_redact_file,
+            ocr::ocr_translate_image,
+            ocr::ocr_translate_file,
+            ocr::barcode_detect,
+            ocr::capture_search_init,
+            ocr::capture_search_query,
+            ocr::capture_search_remove,
             core::exit_app,
             core::start_free_drag,
             core::start_resize_window,
@@ -278,9 +309,9 @@ pub fn run() {
             core::get_current_monitor_info,
             core::get_monitors_bounding_box,
             core::send_new_version_notification,
-            // core::create_video_record_window, // 已移除视频录制功能
-            // core::close_video_record_window, // 已移除视频录制功能
-            // core::has_video_record_window, // 已移除视频录制功能
+            core::create_video_record_window,
+            core::close_video_record_window,
+            core::has_video_record_window,
             core::has_focused_full_screen_window,
             core::set_current_window_always_on_top,
             core::auto_start_enable,
@@ -303,13 +334,21 @@ pub fn run() {
             scroll_screenshot::scroll_screenshot_save_to_clipboard,
             scroll_screenshot::scroll_screenshot_get_size,
             scroll_screenshot::scroll_screenshot_clear,
-            // video_record::video_record_start, // 已移除视频录制功能
-            // video_record::video_record_stop, // 已移除视频录制功能
-            // video_record::video_record_pause, // 已移除视频录制功能
-            // video_record::video_record_resume, // 已移除视频录制功能
-            // video_record::video_record_kill, // 已移除视频录制功能
-            // video_record::video_record_get_microphone_device_names, // 已移除视频录制功能
-            // video_record::video_record_init, // 已移除视频录制功能
+            video_record::video_record_start,
+            video_record::video_record_stop,
+            video_record::video_record_save_replay,
+            video_record::video_record_pause,
+            video_record::video_record_resume,
+            video_record::video_record_kill,
+            video_record::video_record_get_microphone_device_names,
+            video_record::video_record_get_system_audio_device_names,
+            video_record::video_record_init,
+            video_record::video_record_native_init,
+            video_record::video_record_native_start,
+            video_record::video_record_native_pause,
+            video_record::video_record_native_resume,
+            video_record::video_record_native_stop,
+            video_record::video_record_export,
             listen_key::listen_key_start,
             listen_key::listen_key_stop,
             listen_key::listen_key_stop_by_window_label,
@@ -325,6 +364,10 @@ pub fn run() {
             plugin::plugin_register_plugin,
             plugin::plugin_install_plugin,
             plugin::plugin_uninstall_plugin,
+            plugin::plugin_verify_plugin,
+            plugin::plugin_cancel_download,
+            plugin::plugin_install_plugin_from_local,
+            plugin::plugin_export_plugin,
             webview::create_webview_shared_buffer,
             webview::set_support_webview_shared_buffer,
             #[cfg(target_os = "windows")]
@@ -332,6 +375,26 @@ pub fn run() {
             #[cfg(target_os = "windows")]
             core::write_image_pixels_to_clipboard_with_shared_buffer,
             http_services::upload_to_s3,
+            http_services::upload_post_process,
+            http_services::upload_file_to_s3,
+            http_services::s3_get_presigned_url,
+            http_services::cancel_s3_upload,
+            http_services::upload_to_destination,
+            http_services::upload_profile_list,
+            http_services::upload_profile_save,
+            http_services::upload_profile_delete,
+            http_services::url_shortener_list,
+            http_services::url_shortener_save,
+            http_services::url_shortener_delete,
+            http_services::upload_history_query,
+            http_services::upload_history_delete,
+            http_services::upload_queue_add,
+            http_services::upload_queue_add_data,
+            http_services::upload_queue_list,
+            http_services::upload_queue_retry,
+            http_services::upload_queue_remove,
+            http_services::upload_queue_clear_completed,
+            http_services::upload_queue_set_options,
             hot_load_page::hot_load_page_init,
             hot_load_page::hot_load_page_add_page,
             global_state::set_capture_state,
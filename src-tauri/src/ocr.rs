@@ -1,10 +1,24 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use tauri::command;
 use tokio::sync::Mutex;
 
+use snow_shot_app_services::barcode_service::BarcodeResult;
+use snow_shot_app_services::capture_search_service::{
+    CaptureSearchQuery, CaptureSearchResult, CaptureSearchService,
+};
+use snow_shot_app_services::ocr_batch_service::OcrBatchService;
+use snow_shot_app_services::ocr_engine::detect_options::{OcrDetectOptions, OcrDetectPreset};
+use snow_shot_app_services::ocr_engine::rapid_ocr_model::OcrRecModelConfig;
+use snow_shot_app_services::ocr_engine::tesseract::TesseractConfig;
+use snow_shot_app_services::ocr_engine::{OcrEngineType, OcrModelInfo};
 use snow_shot_app_services::ocr_service::{OcrModel, OcrService};
-use snow_shot_tauri_commands_ocr::OcrDetectResult;
+use snow_shot_app_services::redaction_service::RedactionMatch;
+use snow_shot_app_services::translation_render_service::TranslatedTextBlock;
+use snow_shot_tauri_commands_ocr::{
+    OcrBatchParams, OcrDetectResult, OcrRedactFileParams, OcrTranslateParams,
+};
 
 #[command]
 pub async fn ocr_init(
@@ -24,6 +38,29 @@ pub async fn ocr_init(
     .await
 }
 
+#[command]
+pub async fn ocr_init_tesseract(
+    ocr_instance: tauri::State<'_, Mutex<OcrService>>,
+    config: TesseractConfig,
+) -> Result<(), String> {
+    snow_shot_tauri_commands_ocr::ocr_init_tesseract(ocr_instance, config).await
+}
+
+#[command]
+pub async fn ocr_register_rec_model(
+    ocr_instance: tauri::State<'_, Mutex<OcrService>>,
+    config: OcrRecModelConfig,
+) -> Result<OcrModelInfo, String> {
+    snow_shot_tauri_commands_ocr::ocr_register_rec_model(ocr_instance, config).await
+}
+
+#[command]
+pub async fn ocr_get_models(
+    ocr_instance: tauri::State<'_, Mutex<OcrService>>,
+) -> Result<Vec<OcrModelInfo>, String> {
+    snow_shot_tauri_commands_ocr::ocr_get_models(ocr_instance).await
+}
+
 #[command]
 pub async fn ocr_detect(
     ocr_instance: tauri::State<'_, Mutex<OcrService>>,
@@ -40,6 +77,10 @@ pub async fn ocr_detect_with_shared_buffer(
     channel_id: String,
     scale_factor: f32,
     detect_angle: bool,
+    engine: Option<OcrEngineType>,
+    model_id: Option<String>,
+    preset: Option<OcrDetectPreset>,
+    options: Option<OcrDetectOptions>,
 ) -> Result<OcrDetectResult, String> {
     snow_shot_tauri_commands_ocr::ocr_detect_with_shared_buffer(
         ocr_instance,
@@ -47,6 +88,10 @@ pub async fn ocr_detect_with_shared_buffer(
         channel_id,
         scale_factor,
         detect_angle,
+        engine,
+        model_id,
+        preset,
+        options,
     )
     .await
 }
@@ -55,3 +100,78 @@ pub async fn ocr_detect_with_shared_buffer(
 pub async fn ocr_release(ocr_instance: tauri::State<'_, Mutex<OcrService>>) -> Result<(), String> {
     snow_shot_tauri_commands_ocr::ocr_release(ocr_instance).await
 }
+
+#[command]
+pub async fn ocr_batch_start(
+    app: tauri::AppHandle,
+    ocr_batch_service: tauri::State<'_, Arc<OcrBatchService>>,
+    params: OcrBatchParams,
+) -> Result<String, String> {
+    snow_shot_tauri_commands_ocr::ocr_batch_start(app, ocr_batch_service, params).await
+}
+
+#[command]
+pub async fn ocr_batch_cancel(
+    ocr_batch_service: tauri::State<'_, Arc<OcrBatchService>>,
+    job_id: String,
+) -> Result<(), String> {
+    snow_shot_tauri_commands_ocr::ocr_batch_cancel(ocr_batch_service, job_id).await
+}
+
+#[command]
+pub async fn capture_search_init(
+    capture_search_service: tauri::State<'_, Mutex<CaptureSearchService>>,
+    database_path: PathBuf,
+) -> Result<(), String> {
+    snow_shot_tauri_commands_ocr::capture_search_init(capture_search_service, database_path).await
+}
+
+#[command]
+pub async fn capture_search_query(
+    capture_search_service: tauri::State<'_, Mutex<CaptureSearchService>>,
+    query: CaptureSearchQuery,
+) -> Result<Vec<CaptureSearchResult>, String> {
+    snow_shot_tauri_commands_ocr::capture_search_query(capture_search_service, query).await
+}
+
+#[command]
+pub async fn capture_search_remove(
+    capture_search_service: tauri::State<'_, Mutex<CaptureSearchService>>,
+    file_path: PathBuf,
+) -> Result<(), String> {
+    snow_shot_tauri_commands_ocr::capture_search_remove(capture_search_service, file_path).await
+}
+
+#[command]
+pub async fn ocr_redact_file(
+    ocr_instance: tauri::State<'_, Mutex<OcrService>>,
+    params: OcrRedactFileParams,
+) -> Result<Vec<RedactionMatch>, String> {
+    snow_shot_tauri_commands_ocr::ocr_redact_file(ocr_instance, params).await
+}
+
+#[command]
+pub async fn ocr_translate_image(
+    ocr_instance: tauri::State<'_, Mutex<OcrService>>,
+    request: tauri::ipc::Request<'_>,
+) -> Result<tauri::ipc::Response, String> {
+    snow_shot_tauri_commands_ocr::ocr_translate_image(ocr_instance, request).await
+}
+
+#[command]
+pub async fn ocr_translate_file(
+    ocr_instance: tauri::State<'_, Mutex<OcrService>>,
+    input_path: PathBuf,
+    output_path: PathBuf,
+    params: OcrTranslateParams,
+) -> Result<Vec<TranslatedTextBlock>, String> {
+    snow_shot_tauri_commands_ocr::ocr_translate_file(ocr_instance, input_path, output_path, params)
+        .await
+}
+
+#[command]
+pub async fn barcode_detect(
+    request: tauri::ipc::Request<'_>,
+) -> Result<Vec<BarcodeResult>, String> {
+    snow_shot_tauri_commands_ocr::barcode_detect(request).await
+}
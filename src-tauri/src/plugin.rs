@@ -65,3 +65,39 @@ pub async fn plugin_uninstall_plugin(
 ) -> Result<(), String> {
     plugin_service.uninstall_plugin(name.clone()).await
 }
+
+#[command]
+pub async fn plugin_verify_plugin(
+    plugin_service: tauri::State<'_, Arc<PluginService>>,
+    name: String,
+) -> Result<(), String> {
+    plugin_service.verify_plugin(name).await
+}
+
+#[command]
+pub async fn plugin_cancel_download(
+    plugin_service: tauri::State<'_, Arc<PluginService>>,
+    name: String,
+) -> Result<(), String> {
+    plugin_service.cancel_plugin_download(name).await
+}
+
+#[command]
+pub async fn plugin_install_plugin_from_local(
+    plugin_service: tauri::State<'_, Arc<PluginService>>,
+    name: String,
+    source_path: PathBuf,
+) -> Result<(), String> {
+    plugin_service
+        .install_plugin_from_local(name, &source_path)
+        .await
+}
+
+#[command]
+pub async fn plugin_export_plugin(
+    plugin_service: tauri::State<'_, Arc<PluginService>>,
+    name: String,
+    output_file: PathBuf,
+) -> Result<(), String> {
+    plugin_service.export_plugin(name, &output_file).await
+}
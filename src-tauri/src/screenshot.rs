@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use tauri::command;
 use tauri::ipc::Response;
 use tauri_plugin_clipboard_manager::ClipboardExt;
@@ -126,8 +128,12 @@ pub async fn capture_full_screen(
     capture_history_file_path: String,
     correct_hdr_color_algorithm: CorrectHdrColorAlgorithm,
     correct_color_filter: bool,
+    window_title: Option<String>,
 ) -> Result<CaptureFullScreenResult, String> {
-    snow_shot_tauri_commands_screenshot::capture_full_screen(
+    let search_app = app.clone();
+    let search_file_path = PathBuf::from(&file_path);
+
+    let result = snow_shot_tauri_commands_screenshot::capture_full_screen(
         app.clone(),
         move |image| match app.clipboard().write_image(&tauri::image::Image::new(
             image.to_rgba8().as_raw(),
@@ -147,5 +153,13 @@ pub async fn capture_full_screen(
         correct_hdr_color_algorithm,
         correct_color_filter,
     )
-    .await
+    .await?;
+
+    snow_shot_tauri_commands_ocr::capture_search_index_file(
+        &search_app,
+        search_file_path,
+        window_title,
+    );
+
+    Ok(result)
 }
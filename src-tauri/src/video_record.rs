@@ -1,25 +1,37 @@
 use std::path::PathBuf;
+use std::time::Duration;
 
 use tokio::sync::Mutex;
 
-use tauri::command;
+use tauri::{Emitter, Manager, command};
 
+use snow_shot_app_services::native_video_record_service::{
+    NativeVideoRecordOptions, NativeVideoRecordService,
+};
+use snow_shot_app_services::video_export_service::{VideoExportOptions, VideoExportService};
+use snow_shot_app_services::video_record_service::LinuxCaptureBackend;
 use snow_shot_app_services::video_record_service::VideoFormat;
+use snow_shot_app_services::video_record_service::VideoRecordAudioOptions;
+use snow_shot_app_services::video_record_service::VideoRecordAutoStopEvent;
+use snow_shot_app_services::video_record_service::VideoRecordLimits;
+use snow_shot_app_services::video_record_service::VideoRecordReplayOptions;
 use snow_shot_app_services::video_record_service::VideoRecordService;
 
 #[command]
 pub async fn video_record_init(
     video_service: tauri::State<'_, Mutex<VideoRecordService>>,
     ffmpeg_plugin_dir: PathBuf,
+    linux_capture_backend: Option<LinuxCaptureBackend>,
 ) -> Result<(), String> {
     let mut service = video_service.lock().await;
-    service.init(&ffmpeg_plugin_dir);
+    service.init(&ffmpeg_plugin_dir, linux_capture_backend);
     Ok(())
 }
 
 /// 开始视频录制
 #[command]
 pub async fn video_record_start(
+    app: tauri::AppHandle,
     video_service: tauri::State<'_, Mutex<VideoRecordService>>,
     min_x: i32,
     min_y: i32,
@@ -36,6 +48,9 @@ pub async fn video_record_start(
     encoder_preset: String,
     video_max_width: i32,
     video_max_height: i32,
+    audio_options: Option<VideoRecordAudioOptions>,
+    limits: Option<VideoRecordLimits>,
+    replay_options: Option<VideoRecordReplayOptions>,
 ) -> Result<(), String> {
     println!(
         "Starting video recording: area=({},{}) to ({},{}), output={}",
@@ -60,9 +75,19 @@ pub async fn video_record_start(
         encoder_preset,
         video_max_width,
         video_max_height,
+        audio_options.unwrap_or_default(),
+        limits.clone().unwrap_or_default(),
+        replay_options,
     ) {
         Ok(_) => {
             println!("Video recording started successfully");
+
+            if let (Some(limits), Some(record_id)) = (limits, service.get_record_id()) {
+                if limits != VideoRecordLimits::default() {
+                    spawn_video_record_limit_watcher(app, record_id);
+                }
+            }
+
             Ok(())
         }
         Err(e) => {
@@ -72,6 +97,64 @@ pub async fn video_record_start(
     }
 }
 
+/// 定时检查录制限制，超出时停止录制并发送 video-record-auto-stop 事件
+fn spawn_video_record_limit_watcher(app: tauri::AppHandle, record_id: u64) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let video_service = app.state::<Mutex<VideoRecordService>>();
+            let mut service = video_service.lock().await;
+            if service.get_record_id() != Some(record_id) {
+                break;
+            }
+
+            let reason = match service.check_limits() {
+                Some(reason) => reason,
+                None => continue,
+            };
+
+            log::warn!(
+                "[video_record_limit_watcher] Stopping recording, reason: {:?}",
+                reason
+            );
+
+            // 持有锁时只发送停止信号，等待 ffmpeg 和合并片段在锁外进行
+            let mut recording = match service.detach_recording() {
+                Some(recording) => recording,
+                None => break,
+            };
+            drop(service);
+
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                recording
+                    .stop(false, "gif", 0, 0, 0)
+                    .map_err(|e| e.to_string())
+            })
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+
+            let event = match result {
+                Ok(output_file) => VideoRecordAutoStopEvent {
+                    reason,
+                    output_file,
+                    error: None,
+                },
+                Err(e) => VideoRecordAutoStopEvent {
+                    reason,
+                    output_file: None,
+                    error: Some(e),
+                },
+            };
+
+            if let Err(e) = app.emit("video-record-auto-stop", event) {
+                log::error!("[video_record_limit_watcher] Failed to emit event: {}", e);
+            }
+            break;
+        }
+    });
+}
+
 /// 停止视频录制
 #[command]
 pub async fn video_record_stop(
@@ -104,6 +187,21 @@ pub async fn video_record_stop(
     }
 }
 
+/// 保存回放缓冲中最近一段时间的录像
+#[command]
+pub async fn video_record_save_replay(
+    video_service: tauri::State<'_, Mutex<VideoRecordService>>,
+    duration_secs: u32,
+    output_file: String,
+) -> Result<String, String> {
+    let service = video_service.lock().await;
+
+    match service.save_replay(duration_secs, &output_file) {
+        Ok(final_filename) => Ok(final_filename),
+        Err(e) => Err(format!("Save replay failed: {}", e)),
+    }
+}
+
 /// 暂停视频录制
 #[command]
 pub async fn video_record_pause(
@@ -138,6 +236,14 @@ pub async fn video_record_get_microphone_device_names(
     Ok(service.get_microphone_device_names())
 }
 
+#[command]
+pub async fn video_record_get_system_audio_device_names(
+    video_service: tauri::State<'_, Mutex<VideoRecordService>>,
+) -> Result<Vec<String>, String> {
+    let service = video_service.lock().await;
+    Ok(service.get_system_audio_device_names())
+}
+
 #[command]
 pub async fn video_record_kill(
     video_service: tauri::State<'_, Mutex<VideoRecordService>>,
@@ -148,3 +254,70 @@ pub async fn video_record_kill(
         Err(e) => Err(format!("Kill recording failed: {}", e)),
     }
 }
+
+#[command]
+pub async fn video_record_native_init(
+    native_video_service: tauri::State<'_, Mutex<NativeVideoRecordService>>,
+    openh264_plugin_dir: PathBuf,
+) -> Result<(), String> {
+    let mut service = native_video_service.lock().await;
+    service.init(&openh264_plugin_dir);
+    Ok(())
+}
+
+/// 开始进程内录制，不依赖 ffmpeg，需要安装 OpenH264 插件
+#[command]
+pub async fn video_record_native_start(
+    native_video_service: tauri::State<'_, Mutex<NativeVideoRecordService>>,
+    options: NativeVideoRecordOptions,
+) -> Result<(), String> {
+    let mut service = native_video_service.lock().await;
+    service.start(options)
+}
+
+#[command]
+pub async fn video_record_native_pause(
+    native_video_service: tauri::State<'_, Mutex<NativeVideoRecordService>>,
+) -> Result<(), String> {
+    let mut service = native_video_service.lock().await;
+    service.pause()
+}
+
+#[command]
+pub async fn video_record_native_resume(
+    native_video_service: tauri::State<'_, Mutex<NativeVideoRecordService>>,
+) -> Result<(), String> {
+    let mut service = native_video_service.lock().await;
+    service.resume()
+}
+
+#[command]
+pub async fn video_record_native_stop(
+    native_video_service: tauri::State<'_, Mutex<NativeVideoRecordService>>,
+) -> Result<Option<PathBuf>, String> {
+    let mut service = native_video_service.lock().await;
+    service.stop().await
+}
+
+/// 裁剪、转换录制好的视频，进度通过 video-export-progress 事件通知
+#[command]
+pub async fn video_record_export(
+    app: tauri::AppHandle,
+    video_service: tauri::State<'_, Mutex<VideoRecordService>>,
+    options: VideoExportOptions,
+) -> Result<PathBuf, String> {
+    let ffmpeg_path = match video_service.lock().await.get_ffmpeg_path() {
+        Some(ffmpeg_path) => ffmpeg_path.clone(),
+        None => return Err("[video_record_export] ffmpeg is not initialized".to_string()),
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        VideoExportService::export(&ffmpeg_path, &options, |progress| {
+            if let Err(e) = app.emit("video-export-progress", progress) {
+                log::warn!("[video_record_export] Failed to emit progress: {}", e);
+            }
+        })
+    })
+    .await
+    .map_err(|e| format!("[video_record_export] Failed to join export task: {}", e))?
+}
@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { Base64 } from "js-base64";
+import type { OcrRedactParams } from "@/types/commands/ocr";
 import {
 	type ElementRect,
 	type ImageBuffer,
@@ -71,12 +72,24 @@ export const saveFile = async (
 	filePath: string,
 	data: ArrayBuffer | Uint8Array,
 	fileType: ImageFormat,
+	/** 保存后加入后台上传队列 */
+	uploadProfileId?: string,
+	/** 保存前遮挡敏感文字 */
+	redactParams?: OcrRedactParams,
 ) => {
+	const headers: Record<string, string> = {
+		"x-file-path": Base64.encode(filePath),
+		"x-file-type": Base64.encode(fileType),
+	};
+	if (uploadProfileId) {
+		headers["x-upload-profile-id"] = Base64.encode(uploadProfileId);
+	}
+	if (redactParams) {
+		headers["x-redaction-options"] = Base64.encode(JSON.stringify(redactParams));
+	}
+
 	const result = await invoke<void>("save_file", data, {
-		headers: {
-			"x-file-path": Base64.encode(filePath),
-			"x-file-type": Base64.encode(fileType),
-		},
+		headers,
 	});
 	return result;
 };
@@ -83,6 +83,13 @@ export const videoRecordInit = async (ffmpegPluginDir: string) => {
 	return result;
 };
 
+export const videoRecordNativeInit = async (openh264PluginDir: string) => {
+	const result = await invoke("video_record_native_init", {
+		openh264PluginDir,
+	});
+	return result;
+};
+
 export const setExcludeFromCapture = async (enable: boolean) => {
 	if (getPlatform() === "macos") {
 		return;
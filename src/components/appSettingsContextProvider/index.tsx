@@ -68,6 +68,10 @@ import { DrawState } from "@/types/draw";
 import { ImageFormat } from "@/types/utils/file";
 import { getConfigDirPath } from "@/utils/environment";
 import { appError, appWarn, formatErrorDetails } from "@/utils/log";
+import {
+	getLegacyS3Credentials,
+	saveScreenshotS3UploadProfile,
+} from "@/utils/uploadProfile";
 
 const getFilePath = async (group: AppSettingsGroup) => {
 	const configDirPath = await getConfigDirPath();
@@ -955,16 +959,6 @@ const AppSettingsContextProviderCore: React.FC<{
 						typeof newSettings?.cloudSaveUrlType === "string"
 							? (newSettings.cloudSaveUrlType as CloudSaveUrlType)
 							: (prevSettings?.cloudSaveUrlType ?? CloudSaveUrlType.S3),
-					s3AccessKeyId:
-						typeof newSettings?.s3AccessKeyId === "string"
-							? newSettings.s3AccessKeyId
-							: (prevSettings?.s3AccessKeyId ??
-								defaultAppSettingsData[group].s3AccessKeyId),
-					s3SecretAccessKey:
-						typeof newSettings?.s3SecretAccessKey === "string"
-							? newSettings.s3SecretAccessKey
-							: (prevSettings?.s3SecretAccessKey ??
-								defaultAppSettingsData[group].s3SecretAccessKey),
 					s3Region:
 						typeof newSettings?.s3Region === "string"
 							? newSettings.s3Region
@@ -1019,6 +1013,24 @@ const AppSettingsContextProviderCore: React.FC<{
 							? newSettings.selectRectPresetList
 							: (prevSettings?.selectRectPresetList ?? []),
 				};
+
+				// 旧版本将 S3 密钥明文保存在设置中，迁移到凭据库后重新写入设置文件
+				const legacyS3Credentials = getLegacyS3Credentials(newSettings);
+				if (legacyS3Credentials) {
+					const screenshotSettings =
+						settings as AppSettingsData[AppSettingsGroup.FunctionScreenshot];
+					saveScreenshotS3UploadProfile(
+						screenshotSettings,
+						legacyS3Credentials,
+					)
+						.then(() => writeAppSettings(group, screenshotSettings, false))
+						.catch((error) => {
+							appError(
+								"[updateAppSettings] migrate S3 credentials failed",
+								error,
+							);
+						});
+				}
 			} else if (group === AppSettingsGroup.FunctionOutput) {
 				newSettings = newSettings as AppSettingsData[typeof group];
 				const prevSettings = appSettingsRef.current[group] as
@@ -8,9 +8,13 @@ import {
 } from "@/commands/core";
 import { hotLoadPageInit } from "@/commands/hotLoadPage";
 import { ocrInit } from "@/commands/ocr";
-import { videoRecordInit } from "@/commands/videoRecord";
+import {
+	videoRecordInit,
+	videoRecordNativeInit,
+} from "@/commands/videoRecord";
 import {
 	PLUGIN_ID_FFMPEG,
+	PLUGIN_ID_OPENH264,
 	PLUGIN_ID_RAPID_OCR,
 } from "@/constants/pluginService";
 import { usePluginServiceContext } from "@/contexts/pluginServiceContext";
@@ -186,5 +190,26 @@ export const InitService = () => {
 		}
 	}, [isReadyStatus, pluginConfigRef]);
 
+	const hasInitNativeVideoRecord = useRef(false);
+	useEffect(() => {
+		if (hasInitNativeVideoRecord.current) {
+			return;
+		}
+
+		if (isReadyStatus?.(PLUGIN_ID_OPENH264)) {
+			hasInitNativeVideoRecord.current = true;
+
+			if (pluginConfigRef.current) {
+				pluginConfigRef.current
+					.getPluginDirPath(PLUGIN_ID_OPENH264)
+					.then((openh264PluginDir) => {
+						videoRecordNativeInit(openh264PluginDir);
+					});
+			} else {
+				appWarn("[InitService] pluginConfigRef.current is not set");
+			}
+		}
+	}, [isReadyStatus, pluginConfigRef]);
+
 	return null;
 };
@@ -10,6 +10,7 @@ import {
 import {
 	PLUGIN_ID_AI_CHAT,
 	PLUGIN_ID_FFMPEG,
+	PLUGIN_ID_OPENH264,
 	PLUGIN_ID_RAPID_OCR,
 	PLUGIN_ID_TRANSLATE,
 } from "@/constants/pluginService";
@@ -24,6 +25,20 @@ import {
 import { getAppConfigBaseDirWithCache } from "@/utils/environment";
 import { getPlatform } from "@/utils/platform";
 
+/**
+ * Cisco 发布的 OpenH264 动态库，文件名需要与 NativeVideoRecordService 一致
+ */
+const getOpenH264LibraryName = () => {
+	switch (getPlatform()) {
+		case "windows":
+			return "openh264.dll";
+		case "macos":
+			return "libopenh264.dylib";
+		default:
+			return "libopenh264.so";
+	}
+};
+
 export const PluginServiceContextProvider: React.FC<{
 	children: React.ReactNode;
 	autoInit: boolean;
@@ -43,6 +58,10 @@ export const PluginServiceContextProvider: React.FC<{
 				id: PLUGIN_ID_FFMPEG,
 				file_list: getPlatform() === "windows" ? ["ffmpeg.exe"] : ["ffmpeg"],
 			},
+			{
+				id: PLUGIN_ID_OPENH264,
+				file_list: [getOpenH264LibraryName()],
+			},
 			{
 				id: PLUGIN_ID_TRANSLATE,
 				file_list: [],
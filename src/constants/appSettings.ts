@@ -243,8 +243,6 @@ Priority order (highest to lowest):
 		cloudSaveUrlType: CloudSaveUrlType.S3,
 		cloudSaveUrlFormat: CloudSaveUrlFormat.Origin,
 		cloudProxyUrl: "",
-		s3AccessKeyId: "",
-		s3SecretAccessKey: "",
 		s3Region: "",
 		s3Endpoint: "",
 		s3BucketName: "",
@@ -2,5 +2,6 @@ export const PLUGIN_EVENT_PLUGIN_STATUS_CHANGE = "plugin-status-change";
 
 export const PLUGIN_ID_RAPID_OCR = "rapid_ocr";
 export const PLUGIN_ID_FFMPEG = "ffmpeg";
+export const PLUGIN_ID_OPENH264 = "openh264";
 export const PLUGIN_ID_AI_CHAT = "ai_chat";
 export const PLUGIN_ID_TRANSLATE = "translate";
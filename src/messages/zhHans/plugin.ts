@@ -4,6 +4,10 @@ export const plugin = {
 	"plugin.ffmpeg.description":
 		"基于 FFmpeg 实现音频和视频多种格式的录制、转换等功能",
 	"plugin.ffmpeg.functionDescription": "视频录制",
+	"plugin.openh264.name": "H.264 编码",
+	"plugin.openh264.description":
+		"下载 Cisco 发布的 OpenH264 编码器，用于不依赖 FFmpeg 的内置录制。内置录制只录制画面，不包含音频",
+	"plugin.openh264.functionDescription": "内置录制",
 	"plugin.rapid_ocr.name": "文本识别",
 	"plugin.rapid_ocr.description":
 		"使用基于 ONNXRuntime 和 Paddle OCR 的 Rapid OCR 实现高性能跨平台文本识别。支持结合 AI 对话插件获得更高阶的文本识别能力",
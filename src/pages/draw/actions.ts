@@ -23,6 +23,10 @@ import { generateImageFileName, showImageDialog } from "@/utils/file";
 import { appError } from "@/utils/log";
 import { getPlatform } from "@/utils/platform";
 import { randomString } from "@/utils/random";
+import {
+	SCREENSHOT_S3_UPLOAD_PROFILE_ID,
+	saveScreenshotS3UploadProfile,
+} from "@/utils/uploadProfile";
 import { getWebViewSharedBuffer } from "@/utils/webview";
 import { setWindowRect } from "@/utils/window";
 import type { FixedContentActionType } from "../fixedContent/components/fixedContentCore";
@@ -584,14 +588,12 @@ export const saveCanvasToCloud = async (
 		const fileName = `${generateImageFileName(
 			appSettings[AppSettingsGroup.FunctionOutput].uploadToCloudSaveUrlFormat,
 		)}.png`;
+		// 密钥保存在凭据库中，这里只同步其它 S3 设置
+		await saveScreenshotS3UploadProfile(
+			appSettings[AppSettingsGroup.FunctionScreenshot],
+		);
 		let result = await uploadToS3(
-			appSettings[AppSettingsGroup.FunctionScreenshot].s3Endpoint,
-			appSettings[AppSettingsGroup.FunctionScreenshot].s3Region,
-			appSettings[AppSettingsGroup.FunctionScreenshot].s3AccessKeyId,
-			appSettings[AppSettingsGroup.FunctionScreenshot].s3SecretAccessKey,
-			appSettings[AppSettingsGroup.FunctionScreenshot].s3BucketName,
-			appSettings[AppSettingsGroup.FunctionScreenshot].s3PathPrefix,
-			appSettings[AppSettingsGroup.FunctionScreenshot].s3ForcePathStyle,
+			SCREENSHOT_S3_UPLOAD_PROFILE_ID,
 			imageBuffer,
 			fileName,
 			"image/png",
@@ -8,6 +8,7 @@ import { FormattedMessage, useIntl } from "react-intl";
 import { pluginInstallPlugin, pluginUninstallPlugin } from "@/commands/plugin";
 import {
 	PLUGIN_ID_FFMPEG,
+	PLUGIN_ID_OPENH264,
 	PLUGIN_ID_RAPID_OCR,
 } from "@/constants/pluginService";
 import { usePluginServiceContext } from "@/contexts/pluginServiceContext";
@@ -25,6 +26,9 @@ export const PluginsPage = () => {
 				case PLUGIN_ID_FFMPEG:
 					link = "https://ffmpeg.org/";
 					break;
+				case PLUGIN_ID_OPENH264:
+					link = "https://www.openh264.org/";
+					break;
 				case PLUGIN_ID_RAPID_OCR:
 					link = "https://github.com/RapidAI/RapidOCR";
 					break;
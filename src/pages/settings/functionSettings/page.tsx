@@ -25,6 +25,7 @@ import {
 	theme,
 } from "antd";
 import type { AggregationColor } from "antd/es/color-picker/color";
+import { debounce } from "es-toolkit";
 import {
 	useCallback,
 	useContext,
@@ -80,6 +81,11 @@ import {
 	getImageSaveDirectory,
 	getVideoRecordSaveDirectory,
 } from "@/utils/file";
+import { appError } from "@/utils/log";
+import {
+	type S3Credentials,
+	saveScreenshotS3UploadProfile,
+} from "@/utils/uploadProfile";
 import { TestChat } from "./components/testChat";
 import { TranslationConfig } from "./components/translationConfig";
 
@@ -97,7 +103,30 @@ export const FunctionSettingsPage = () => {
 	const [translationForm] =
 		Form.useForm<AppSettingsData[AppSettingsGroup.FunctionTranslation]>();
 	const [screenshotForm] =
-		Form.useForm<AppSettingsData[AppSettingsGroup.FunctionScreenshot]>();
+		Form.useForm<
+			AppSettingsData[AppSettingsGroup.FunctionScreenshot] & S3Credentials
+		>();
+	// S3 密钥只保存到凭据库，不写入设置文件
+	const saveS3CredentialsDebounce = useMemo(
+		() =>
+			debounce(
+				(
+					settings: AppSettingsData[AppSettingsGroup.FunctionScreenshot],
+					credentials: S3Credentials,
+				) => {
+					saveScreenshotS3UploadProfile(settings, credentials).catch(
+						(error) => {
+							appError(
+								"[FunctionSettingsPage] save S3 credentials failed",
+								error,
+							);
+						},
+					);
+				},
+				1000,
+			),
+		[],
+	);
 	const [outputForm] =
 		Form.useForm<AppSettingsData[AppSettingsGroup.FunctionOutput]>();
 	const [fullScreenDrawForm] =
@@ -730,16 +759,28 @@ export const FunctionSettingsPage = () => {
 			<Spin spinning={appSettingsLoading}>
 				<ProForm
 					form={screenshotForm}
-					onValuesChange={(_, values) => {
+					onValuesChange={(changedValues, values) => {
+						const { s3AccessKeyId, s3SecretAccessKey, ...screenshotSettings } =
+							values;
 						updateAppSettings(
 							AppSettingsGroup.FunctionScreenshot,
-							values,
+							screenshotSettings,
 							true,
 							true,
 							true,
 							true,
 							false,
 						);
+
+						if (
+							"s3AccessKeyId" in changedValues ||
+							"s3SecretAccessKey" in changedValues
+						) {
+							saveS3CredentialsDebounce(screenshotSettings, {
+								s3AccessKeyId: s3AccessKeyId ?? "",
+								s3SecretAccessKey: s3SecretAccessKey ?? "",
+							});
+						}
 					}}
 					submitter={false}
 					layout="horizontal"
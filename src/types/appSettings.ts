@@ -427,10 +427,6 @@ export type AppSettingsData = {
 		cloudProxyUrl: string;
 		/** 云端保存协议 */
 		cloudSaveUrlType: CloudSaveUrlType;
-		/** S3 访问密钥 ID */
-		s3AccessKeyId: string;
-		/** S3 访问密钥 */
-		s3SecretAccessKey: string;
 		/** S3 区域 */
 		s3Region: string;
 		/** S3 端点 */
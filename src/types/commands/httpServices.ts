@@ -0,0 +1,27 @@
+export type S3Config = {
+	endpoint: string;
+	region: string;
+	/** 为空时使用凭据库中已保存的密钥 */
+	access_key_id: string;
+	/** 为空时使用凭据库中已保存的密钥 */
+	secret_access_key: string;
+	bucket: string;
+	path_prefix: string | undefined;
+	force_path_style: boolean;
+};
+
+/**
+ * 上传目标，目前前端只使用 S3
+ */
+export type UploadDestinationConfig = {
+	type: "S3";
+} & S3Config;
+
+/**
+ * 保存在凭据库中的上传配置，密钥不会返回给前端
+ */
+export type UploadProfile = {
+	id: string;
+	name: string;
+	destination: UploadDestinationConfig;
+};
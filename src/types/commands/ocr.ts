@@ -13,3 +13,29 @@ export interface OcrDetectResult {
 	text_blocks: OcrDetectResultTextBlock[];
 	scale_factor: number;
 }
+
+export interface RedactionRule {
+	id: string;
+	name: string;
+	pattern: string;
+	luhn_check?: boolean;
+}
+
+export type RedactionStyle = { Pixelate: { block_size: number } } | "Blackout";
+
+export interface RedactionOptions {
+	/** 启用的内置规则 id，为空时启用所有内置规则 */
+	default_rule_ids?: string[];
+	custom_rules?: RedactionRule[];
+	style?: RedactionStyle;
+	padding?: number;
+}
+
+/** 保存和上传前遮挡敏感文字的参数 */
+export interface OcrRedactParams {
+	scale_factor?: number;
+	engine?: "RapidOcr" | "Tesseract";
+	model_id?: string;
+	preset?: "ScreenUi" | "DenseCode" | "PhotoDocument";
+	redaction_options?: RedactionOptions;
+}
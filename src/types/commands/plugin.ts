@@ -4,6 +4,7 @@ export enum PluginStatus {
 	Downloading = "Downloading",
 	Unzipping = "Unzipping",
 	Uninstalling = "Uninstalling",
+	Corrupted = "Corrupted",
 }
 
 export type PluginStatusResult = {
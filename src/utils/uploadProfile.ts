@@ -0,0 +1,62 @@
+import { uploadProfileSave } from "@/commands/httpServices";
+import type { AppSettingsData, AppSettingsGroup } from "@/types/appSettings";
+
+/** 截图保存到云端使用的上传配置 */
+export const SCREENSHOT_S3_UPLOAD_PROFILE_ID = "screenshot_s3";
+
+export type S3Credentials = {
+	s3AccessKeyId: string;
+	s3SecretAccessKey: string;
+};
+
+/**
+ * 将截图设置中的 S3 配置同步到凭据库
+ *
+ * 未传入密钥时保留凭据库中已保存的密钥
+ */
+export const saveScreenshotS3UploadProfile = async (
+	settings: AppSettingsData[AppSettingsGroup.FunctionScreenshot],
+	credentials?: Partial<S3Credentials>,
+) => {
+	await uploadProfileSave({
+		id: SCREENSHOT_S3_UPLOAD_PROFILE_ID,
+		name: "Screenshot S3",
+		destination: {
+			type: "S3",
+			endpoint: settings.s3Endpoint,
+			region: settings.s3Region,
+			access_key_id: credentials?.s3AccessKeyId ?? "",
+			secret_access_key: credentials?.s3SecretAccessKey ?? "",
+			bucket: settings.s3BucketName,
+			path_prefix: settings.s3PathPrefix || undefined,
+			force_path_style: settings.s3ForcePathStyle,
+		},
+	});
+};
+
+/**
+ * 旧版本将 S3 密钥明文保存在截图设置中，返回需要迁移到凭据库的密钥
+ */
+export const getLegacyS3Credentials = (
+	settings: unknown,
+): S3Credentials | undefined => {
+	if (typeof settings !== "object" || settings === null) {
+		return undefined;
+	}
+
+	const { s3AccessKeyId, s3SecretAccessKey } = settings as Partial<
+		Record<keyof S3Credentials, unknown>
+	>;
+	if (
+		(typeof s3AccessKeyId !== "string" || !s3AccessKeyId) &&
+		(typeof s3SecretAccessKey !== "string" || !s3SecretAccessKey)
+	) {
+		return undefined;
+	}
+
+	return {
+		s3AccessKeyId: typeof s3AccessKeyId === "string" ? s3AccessKeyId : "",
+		s3SecretAccessKey:
+			typeof s3SecretAccessKey === "string" ? s3SecretAccessKey : "",
+	};
+};